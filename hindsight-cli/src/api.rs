@@ -4,11 +4,70 @@
 //! to bridge from the CLI's synchronous code to the async API client.
 
 use anyhow::Result;
-use hindsight_client::Client as AsyncClient;
+use hindsight_client::{Client as AsyncClient, ClientInfo, Error as ClientError, ResponseValue};
+use crate::cache::{Cached, ResponseCache};
 pub use hindsight_client::types;
+use crate::lenient;
+use crate::memo::Memo;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// Per-request HTTP timeout
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long `--wait` flows poll before giving up
+pub const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Redirects followed per request; a longer chain is usually a load balancer loop
+pub const MAX_REDIRECTS: usize = 5;
+
+/// Pretty-printed JSON request body, as shown by `--verbose` and `--dry-run`
+pub fn format_request_body<T: Serialize>(request: &T) -> String {
+    crate::output::to_json(request).unwrap_or_default()
+}
+
+/// A request was redirected more than [`MAX_REDIRECTS`] times
+#[derive(Debug, thiserror::Error)]
+#[error("Stopped after {MAX_REDIRECTS} redirects (last to {last_url}); check the API URL and any load balancer or proxy redirect rules")]
+pub struct TooManyRedirects {
+    pub last_url: String,
+}
+
+/// Follow redirects up to [`MAX_REDIRECTS`]. reqwest keeps the method and body on
+/// 307/308; on 301/302/303 it re-sends a POST as a GET, which `--verbose` points out.
+fn redirect_policy(verbose: bool) -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() > MAX_REDIRECTS {
+            let last_url = attempt.url().to_string();
+            return attempt.error(TooManyRedirects { last_url });
+        }
+        if verbose {
+            let status = attempt.status();
+            let note = match status.as_u16() {
+                307 | 308 => "",
+                _ => " (non-GET requests are re-sent as GET)",
+            };
+            eprintln!("Redirect {} to {}{}", status, attempt.url(), note);
+        }
+        attempt.follow()
+    })
+}
+
+/// A `--wait` flow exceeded `--wait-timeout` before the operation finished
+#[derive(Debug, thiserror::Error)]
+#[error("Gave up waiting for operation {operation_id} after {}s (last status: {last_status}){}",
+    .elapsed.as_secs(),
+    if *.cancelled { "; the operation was cancelled" } else { "" })]
+pub struct WaitTimeout {
+    pub bank_id: String,
+    pub operation_id: String,
+    pub last_status: String,
+    pub elapsed: Duration,
+    pub cancelled: bool,
+}
 
 // Types not defined in OpenAPI spec (TODO: add to openapi.json)
 #[derive(Debug, Serialize, Deserialize)]
@@ -40,6 +99,8 @@ pub struct Operation {
 pub struct OperationsResponse {
     pub bank_id: String,
     pub operations: Vec<Operation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -48,6 +109,33 @@ pub struct TraceInfo {
     pub activation_count: Option<i32>,
 }
 
+/// One stored version of a mental model's content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MentalModelVersion {
+    pub content: String,
+    #[serde(default, alias = "refreshed_at", alias = "created_at")]
+    pub timestamp: Option<String>,
+}
+
+/// Parse a history response: a list of versions, or an object holding one under
+/// `items` or `versions`. Returned oldest first.
+fn parse_history(body: &[u8], verbose: bool) -> Result<Vec<MentalModelVersion>> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum History {
+        List(Vec<MentalModelVersion>),
+        Wrapped {
+            #[serde(alias = "versions")]
+            items: Vec<MentalModelVersion>,
+        },
+    }
+    let mut versions = match decode::<History>(body, verbose)? {
+        History::List(versions) | History::Wrapped { items: versions } => versions,
+    };
+    versions.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    Ok(versions)
+}
+
 // Unified result for put_memories that handles both sync and async responses
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MemoryPutResult {
@@ -58,53 +146,726 @@ pub struct MemoryPutResult {
     pub operation_id: Option<String>,
 }
 
+/// Run an idempotent GET, retrying once if the response body fails to parse.
+/// A truncated read is usually transient; invalid JSON twice points at a server bug.
+async fn with_parse_retry<T, E, F, Fut>(verbose: bool, request: F) -> Result<ResponseValue<T>>
+where
+    T: DeserializeOwned,
+    F: Fn() -> Fut,
+    Fut: std::future::Future<Output = std::result::Result<ResponseValue<T>, ClientError<E>>>,
+    ClientError<E>: std::error::Error + Send + Sync + 'static,
+{
+    match recover(request().await, verbose) {
+        Err(ClientError::InvalidResponsePayload(_, first)) => {
+            if verbose {
+                eprintln!("Failed to parse response ({}), retrying once", first);
+            }
+            match recover(request().await, verbose) {
+                Err(ClientError::InvalidResponsePayload(body, second)) => Err(crate::errors::response_error(
+                    &body,
+                    format!("Failed to parse response: server returned invalid JSON twice ({}; {} bytes)", second, body.len()),
+                )),
+                other => Ok(other?),
+            }
+        }
+        other => Ok(other?),
+    }
+}
+
+/// Deserialize a response body. Under `--parse-lenient`, fields that fail to
+/// deserialize are dropped instead of failing the whole parse.
+fn decode<T: DeserializeOwned>(body: &[u8], verbose: bool) -> serde_json::Result<T> {
+    let error = match serde_json::from_slice(body) {
+        Ok(value) => return Ok(value),
+        Err(e) if lenient::is_enabled() => e,
+        Err(e) => return Err(e),
+    };
+    let (value, dropped) = lenient::parse(body).map_err(|_| error)?;
+    if verbose {
+        crate::ui::print_warning(&format!("Ignored response field(s) that did not match: {}", dropped.join(", ")));
+    }
+    Ok(value)
+}
+
+/// Recover a generated-client response whose body failed to parse, under `--parse-lenient`
+#[allow(clippy::result_large_err)] // the error type is the generated client's
+fn recover<T: DeserializeOwned, E>(
+    result: std::result::Result<ResponseValue<T>, ClientError<E>>,
+    verbose: bool,
+) -> std::result::Result<ResponseValue<T>, ClientError<E>> {
+    match result {
+        Err(ClientError::InvalidResponsePayload(body, error)) => match decode(&body, verbose) {
+            Ok(value) => Ok(ResponseValue::new(value, reqwest::StatusCode::OK, reqwest::header::HeaderMap::new())),
+            Err(_) => Err(ClientError::InvalidResponsePayload(body, error)),
+        },
+        other => other,
+    }
+}
+
+/// Page size for list endpoints that cap each response (at 100 by default) and
+/// report no total
+const LIST_PAGE_SIZE: u64 = 100;
+
+/// Collect every item from such an endpoint: `fetch(limit, offset)` is called for
+/// successive pages until one comes back short
+fn collect_pages<T>(page_size: u64, mut fetch: impl FnMut(u64, u64) -> Result<Vec<T>>) -> Result<Vec<T>> {
+    let mut items = Vec::new();
+    loop {
+        let page = fetch(page_size, items.len() as u64)?;
+        let last = (page.len() as u64) < page_size;
+        items.extend(page);
+        if last {
+            return Ok(items);
+        }
+    }
+}
+
+/// A `limit`/`offset` query value in whichever integer type the generated operation takes
+fn page_param<T: TryFrom<u64>>(value: u64) -> Option<T> {
+    T::try_from(value).ok()
+}
+
+/// Error body the server returns instead of a bank list (`{"detail": ...}` or `{"error": ...}`)
+#[derive(Deserialize)]
+struct ApiErrorBody {
+    #[serde(alias = "detail")]
+    error: serde_json::Value,
+}
+
+/// Parse a bank list response: the success shape first, then the error shape,
+/// and otherwise report the raw body rather than a confusing parse error.
+fn parse_banks_response(status: reqwest::StatusCode, body: &str, verbose: bool) -> Result<Vec<types::BankListItem>> {
+    if status.is_success() {
+        if let Ok(list) = decode::<types::BankListResponse>(body.as_bytes(), verbose) {
+            return Ok(list.banks);
+        }
+    }
+
+    if let Ok(err) = serde_json::from_str::<ApiErrorBody>(body) {
+        let message = match err.error {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        };
+        anyhow::bail!("Failed to list banks (status {}): {}", status, message);
+    }
+
+    let preview: String = body.chars().take(500).collect();
+    Err(crate::errors::response_error(body.as_bytes(), format!("Unexpected banks response shape (status {}): {}", status, preview)))
+}
+
+/// The response's `ETag`, kept for conditional requests
+fn etag_of(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    headers
+        .get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Whether a conditional GET failed only because the server answered 304 Not Modified
+fn is_not_modified(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<ClientError<types::HttpValidationError>>(),
+        Some(ClientError::UnexpectedResponse(response)) if response.status() == reqwest::StatusCode::NOT_MODIFIED
+    )
+}
+
+/// Stable idempotency key for a retain request: SHA-256 of its JSON body
+pub fn retain_idempotency_key(request: &types::RetainRequest) -> Result<String> {
+    use sha2::{Digest, Sha256};
+
+    let body = serde_json::to_vec(request)?;
+    let digest = Sha256::digest(&body);
+    Ok(digest.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// `base_url` with `path` appended, each segment percent-encoded, so ids
+/// containing `/`, `?` or spaces stay inside their own segment
+pub fn endpoint_url(base_url: &str, path: &[&str]) -> Result<reqwest::Url> {
+    let mut url = reqwest::Url::parse(base_url)?;
+    url.path_segments_mut()
+        .map_err(|_| anyhow::anyhow!("Invalid API URL: {}", base_url))?
+        .pop_if_empty()
+        .extend(path);
+    Ok(url)
+}
+
+/// Base URL for a server, with the tenant segment when one is configured.
+/// Every path the client builds is joined onto this, so tenants apply uniformly.
+pub fn tenant_base_url(api_url: &str, tenant: Option<&str>) -> String {
+    let api_url = api_url.trim_end_matches('/');
+    match tenant {
+        Some(tenant) => format!("{}/tenants/{}", api_url, tenant),
+        None => api_url.to_string(),
+    }
+}
+
+/// Header names whose values are never printed, even in verbose mode
+const SENSITIVE_HEADER_HINTS: &[&str] = &["authorization", "cookie", "key", "token", "secret", "password"];
+
+/// Drop `user:password@` from a URL so credentials never reach a support ticket or output file
+pub fn redact_url_credentials(url: &str) -> String {
+    let (scheme, rest) = match url.split_once("://") {
+        Some((scheme, rest)) => (Some(scheme), rest),
+        None => (None, url),
+    };
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    let rest = match rest[..authority_end].rfind('@') {
+        Some(at) => format!("<redacted>@{}", &rest[at + 1..]),
+        None => rest.to_string(),
+    };
+    match scheme {
+        Some(scheme) => format!("{}://{}", scheme, rest),
+        None => rest,
+    }
+}
+
+/// Whether a header's value must be masked in logs and diagnostics
+pub fn is_sensitive_header(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SENSITIVE_HEADER_HINTS.iter().any(|hint| name.contains(hint))
+}
+
+/// Extra header from `--header 'Name: Value'`, sent with every request
+#[derive(Debug, Clone)]
+pub struct CustomHeader {
+    pub name: reqwest::header::HeaderName,
+    pub value: reqwest::header::HeaderValue,
+}
+
+impl CustomHeader {
+    /// Parse `Name: Value`; used as the clap value parser for `--header`
+    pub fn parse(raw: &str) -> std::result::Result<Self, String> {
+        let (name, value) = raw
+            .split_once(':')
+            .ok_or_else(|| format!("expected 'Name: Value', got '{}'", raw))?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(format!("missing header name in '{}'", raw));
+        }
+        let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| format!("invalid header name '{}'", name))?;
+        let value = reqwest::header::HeaderValue::from_str(value.trim())
+            .map_err(|_| format!("invalid value for header '{}'", name))?;
+        Ok(CustomHeader { name, value })
+    }
+
+    pub fn is_sensitive(&self) -> bool {
+        is_sensitive_header(self.name.as_str())
+    }
+
+    /// `name: value` for logs, with sensitive values masked
+    pub fn redacted(&self) -> String {
+        if self.is_sensitive() {
+            format!("{}: <redacted>", self.name)
+        } else {
+            format!("{}: {}", self.name, self.value.to_str().unwrap_or("<binary>"))
+        }
+    }
+}
+
+/// Iterator over pages of documents, from [`ApiClient::iter_documents`]
+pub struct DocumentPages<'a> {
+    client: &'a ApiClient,
+    agent_id: &'a str,
+    q: Option<&'a str>,
+    page_size: i32,
+    offset: i32,
+    seen: i64,
+    /// IDs already yielded. The endpoint only offers limit/offset (no cursor), so
+    /// a document inserted mid-walk shifts later pages and would repeat.
+    yielded: HashSet<String>,
+    done: bool,
+    verbose: bool,
+}
+
+/// Drop documents whose `id` was already yielded, recording the rest; returns how many were dropped
+fn drop_yielded(items: &mut Vec<serde_json::Map<String, serde_json::Value>>, yielded: &mut HashSet<String>) -> usize {
+    let before = items.len();
+    items.retain(|item| match item.get("id").and_then(|id| id.as_str()) {
+        Some(id) => yielded.insert(id.to_string()),
+        None => true,
+    });
+    before - items.len()
+}
+
+impl Iterator for DocumentPages<'_> {
+    type Item = Result<Vec<serde_json::Map<String, serde_json::Value>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut response = match self.client.list_documents(self.agent_id, self.q, Some(self.page_size), Some(self.offset), self.verbose) {
+            Ok(response) => response,
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e));
+            }
+        };
+        self.offset += self.page_size;
+        self.seen += response.items.len() as i64;
+        if response.items.is_empty() || self.seen >= response.total {
+            self.done = true;
+        }
+        if response.items.is_empty() {
+            return None;
+        }
+        let repeated = drop_yielded(&mut response.items, &mut self.yielded);
+        if repeated > 0 {
+            crate::ui::print_warning(&format!(
+                "Skipped {} document(s) repeated across pages; the bank changed while paging",
+                repeated
+            ));
+        }
+        Some(Ok(response.items))
+    }
+}
+
+/// Generation parameters for reflect, dropped when the server does not support them
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Sampling {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+impl Sampling {
+    pub fn is_empty(&self) -> bool {
+        self.temperature.is_none() && self.top_p.is_none() && self.seed.is_none()
+    }
+}
+
+/// What the connected server supports beyond the baseline API.
+/// Reported by `/v1/capabilities` where available, otherwise inferred from `/version`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Capabilities {
+    #[serde(default)]
+    pub api_version: Option<String>,
+    #[serde(default)]
+    pub observations: bool,
+    #[serde(default)]
+    pub mcp: bool,
+    #[serde(default)]
+    pub worker: bool,
+    /// Reflect accepts `temperature`, `top_p` and `seed`; None when the server
+    /// doesn't say, as with servers that have no capabilities endpoint
+    #[serde(default)]
+    pub reflect_sampling: Option<bool>,
+    /// "endpoint" or "version"
+    #[serde(default, skip_deserializing)]
+    pub source: String,
+}
+
+impl Capabilities {
+    /// Servers without a capabilities endpoint only report the feature flags in
+    /// `/version`; support for the optional request fields is unknown
+    fn from_version(version: &types::VersionResponse) -> Self {
+        Capabilities {
+            api_version: Some(version.api_version.clone()),
+            observations: version.features.observations,
+            mcp: version.features.mcp,
+            worker: version.features.worker,
+            reflect_sampling: None,
+            source: "version".to_string(),
+        }
+    }
+
+    /// Drop the sampling parameters this server would not honour. They are sent
+    /// unless the server reported that it doesn't support them.
+    fn supported_sampling(&self, sampling: Sampling) -> Sampling {
+        if self.reflect_sampling != Some(false) || sampling.is_empty() {
+            return sampling;
+        }
+        crate::ui::print_warning(&format!(
+            "Server {} does not support --temperature/--top-p/--seed; reflecting without them",
+            self.api_version.as_deref().unwrap_or("(unknown version)")
+        ));
+        Sampling::default()
+    }
+}
+
 #[derive(Clone)]
 pub struct ApiClient {
     client: AsyncClient,
     runtime: std::sync::Arc<tokio::runtime::Runtime>,
+    cache: Option<ResponseCache>,
+    recall_memo: Option<Memo<types::RecallResponse>>,
+    capabilities: std::sync::Arc<std::sync::OnceLock<Capabilities>>,
+    discover_capabilities: bool,
+    request_id: String,
+    wait_timeout: Duration,
+    cancel_on_timeout: bool,
 }
 
 impl ApiClient {
-    pub fn new(base_url: String, api_key: Option<String>) -> Result<Self> {
+    /// Create a client that tags every request with `X-Request-ID`.
+    /// A UUID is generated for this invocation when `request_id` is None.
+    /// `extra_headers` are applied last, so they can override the built-in ones.
+    /// With `verbose`, redirect hops are logged to stderr.
+    pub fn new(
+        base_url: String,
+        api_key: Option<String>,
+        request_id: Option<String>,
+        extra_headers: &[CustomHeader],
+        verbose: bool,
+    ) -> Result<Self> {
         let runtime = std::sync::Arc::new(tokio::runtime::Runtime::new()?);
+        let request_id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
 
-        // Create HTTP client with 2-minute timeout and optional auth header
-        let mut client_builder = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(120));
+        // Create HTTP client with 2-minute timeout, request ID and optional auth header
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            "x-request-id",
+            reqwest::header::HeaderValue::from_str(&request_id)
+                .map_err(|_| anyhow::anyhow!("Invalid request ID: {}", request_id))?,
+        );
 
         if let Some(key) = api_key {
-            let mut headers = reqwest::header::HeaderMap::new();
             let auth_value = format!("Bearer {}", key);
             headers.insert(
                 reqwest::header::AUTHORIZATION,
                 reqwest::header::HeaderValue::from_str(&auth_value)?,
             );
-            client_builder = client_builder.default_headers(headers);
         }
 
-        let http_client = client_builder.build()?;
+        for header in extra_headers {
+            headers.insert(header.name.clone(), header.value.clone());
+        }
+
+        let http_client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(redirect_policy(verbose))
+            .default_headers(headers)
+            .build()?;
 
         let client = AsyncClient::new_with_client(&base_url, http_client);
-        Ok(ApiClient { client, runtime })
+        Ok(ApiClient {
+            client,
+            runtime,
+            cache: None,
+            recall_memo: None,
+            capabilities: Default::default(),
+            discover_capabilities: true,
+            request_id,
+            wait_timeout: DEFAULT_WAIT_TIMEOUT,
+            cancel_on_timeout: false,
+        })
+    }
+
+    /// URL of `path` (segments under the base URL, including any tenant prefix)
+    pub fn url(&self, path: &[&str]) -> Result<reqwest::Url> {
+        endpoint_url(self.client.baseurl(), path)
+    }
+
+    /// The `X-Request-ID` sent with every request from this client
+    pub fn request_id(&self) -> &str {
+        &self.request_id
+    }
+
+    /// Send a request built outside the generated client. It passes through the same
+    /// request hook first, so `--print-curl` sees (and can stop) it like any other.
+    async fn execute(&self, request: reqwest::Request) -> reqwest::Result<reqwest::Response> {
+        hindsight_client::hooks::before_request(&(), &request);
+        self.client.client().execute(request).await
+    }
+
+    /// Bound every polling flow; optionally cancel the operation when the bound is hit
+    pub fn with_wait_timeout(mut self, timeout: Duration, cancel_on_timeout: bool) -> Self {
+        self.wait_timeout = timeout;
+        self.cancel_on_timeout = cancel_on_timeout;
+        self
+    }
+
+    pub fn wait_timeout(&self) -> Duration {
+        self.wait_timeout
+    }
+
+    /// Build the timeout error for an operation, cancelling it first if requested
+    pub fn wait_timed_out(&self, bank_id: &str, operation_id: &str, last_status: &str, elapsed: Duration) -> anyhow::Error {
+        let cancelled = self.cancel_on_timeout
+            && self
+                .cancel_operation(bank_id, operation_id, false)
+                .map(|r| r.success)
+                .unwrap_or(false);
+        WaitTimeout {
+            bank_id: bank_id.to_string(),
+            operation_id: operation_id.to_string(),
+            last_status: last_status.to_string(),
+            elapsed,
+            cancelled,
+        }
+        .into()
+    }
+
+    /// Serve bank list and profile reads from an on-disk cache
+    pub fn with_cache(mut self, cache: Option<ResponseCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Answer a search identical to one made within `ttl` from memory
+    pub fn with_recall_memo(mut self, ttl: Option<Duration>) -> Self {
+        self.recall_memo = ttl.map(Memo::new);
+        self
+    }
+
+    /// Whether optional request fields are checked against the server's capabilities
+    /// before sending. When off, they are always sent.
+    pub fn with_capability_discovery(mut self, enabled: bool) -> Self {
+        self.discover_capabilities = enabled;
+        self
+    }
+
+    /// What the server supports, fetched once per session
+    pub fn capabilities(&self, verbose: bool) -> Result<Capabilities> {
+        if let Some(capabilities) = self.capabilities.get() {
+            return Ok(capabilities.clone());
+        }
+        let capabilities = match self.capabilities_endpoint(verbose)? {
+            Some(capabilities) => capabilities,
+            None => Capabilities::from_version(&self.get_version(verbose)?),
+        };
+        Ok(self.capabilities.get_or_init(|| capabilities).clone())
+    }
+
+    /// Capabilities for deciding what to send, or None when discovery is off or failed
+    fn known_capabilities(&self, verbose: bool) -> Option<Capabilities> {
+        if !self.discover_capabilities {
+            return None;
+        }
+        match self.capabilities(verbose) {
+            Ok(capabilities) => Some(capabilities),
+            Err(e) => {
+                if verbose {
+                    eprintln!("Capability discovery failed, sending all fields: {}", e);
+                }
+                None
+            }
+        }
     }
 
-    pub fn list_agents(&self, _verbose: bool) -> Result<Vec<types::BankListItem>> {
+    /// The sampling parameters to send. Capabilities are only looked up when there
+    /// is something to filter, so a plain reflect never waits on discovery.
+    fn negotiate_sampling(&self, sampling: Sampling, verbose: bool) -> Sampling {
+        if sampling.is_empty() {
+            return sampling;
+        }
+        match self.known_capabilities(verbose) {
+            Some(capabilities) => capabilities.supported_sampling(sampling),
+            None => sampling,
+        }
+    }
+
+    /// `/v1/capabilities`, or None on servers that don't have it
+    fn capabilities_endpoint(&self, verbose: bool) -> Result<Option<Capabilities>> {
+        let url = self.url(&["v1", "capabilities"])?;
+
+        let request = self
+            .client
+            .client()
+            .get(url)
+            .header("api-version", AsyncClient::api_version())
+            .build()?;
+
         self.runtime.block_on(async {
-            let response = self.client.list_banks(None).await?;
-            Ok(response.into_inner().banks)
+            let response = self.execute(request).await?;
+
+            let status = response.status();
+            if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::METHOD_NOT_ALLOWED {
+                return Ok(None);
+            }
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(crate::errors::response_error(body.as_bytes(), format!("Error Response: status: {}; body: {}", status, body)));
+            }
+            let body = response.bytes().await?;
+            let mut capabilities: Capabilities = decode(&body, verbose)?;
+            // The endpoint lists what the server supports, so a missing field means no
+            capabilities.reflect_sampling.get_or_insert(false);
+            capabilities.source = "endpoint".to_string();
+            Ok(Some(capabilities))
         })
     }
 
-    pub fn get_profile(&self, agent_id: &str, _verbose: bool) -> Result<types::BankProfileResponse> {
+    /// Serve `key` from the cache while fresh. `fetch` is called with the ETag to send
+    /// as `If-None-Match` (when revalidating a stale entry) and returns None when the
+    /// server answers 304 Not Modified, or else the value and its new ETag. A stale
+    /// entry is also served when the server can't be reached to revalidate it.
+    fn cached<T>(
+        &self,
+        key: &str,
+        verbose: bool,
+        fetch: impl FnOnce(Option<&str>) -> Result<Option<(T, Option<String>)>>,
+    ) -> Result<T>
+    where
+        T: Serialize + serde::de::DeserializeOwned,
+    {
+        let unconditional = |fetched: Option<(T, Option<String>)>| {
+            fetched.ok_or_else(|| anyhow::anyhow!("Server answered 304 Not Modified to an unconditional request"))
+        };
+        let Some(cache) = &self.cache else {
+            return unconditional(fetch(None)?).map(|(value, _)| value);
+        };
+        let (value, etag) = match cache.lookup::<T>(key) {
+            Some(entry) if entry.fresh => {
+                if verbose {
+                    eprintln!("Using cached response for {}", key);
+                }
+                return Ok(entry.value);
+            }
+            Some(Cached { value: cached, etag: Some(etag), .. }) => match fetch(Some(&etag)) {
+                Ok(None) => {
+                    if verbose {
+                        eprintln!("Using cached response for {} (not modified)", key);
+                    }
+                    (cached, Some(etag))
+                }
+                Ok(Some(fetched)) => fetched,
+                Err(e) if crate::errors::is_transient(&e) => {
+                    if verbose {
+                        eprintln!("Could not revalidate {} ({}), using the cached response", key, e);
+                    }
+                    return Ok(cached);
+                }
+                Err(e) => return Err(e),
+            },
+            _ => unconditional(fetch(None)?)?,
+        };
+        cache.put(key, &value, etag.as_deref());
+        Ok(value)
+    }
+
+    /// Bank writes make cached list/profile responses stale
+    fn invalidate_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate();
+        }
+    }
+
+    /// Memory writes can change what an identical search returns
+    fn invalidate_recalls(&self) {
+        if let Some(memo) = &self.recall_memo {
+            memo.clear();
+        }
+    }
+
+    pub fn list_agents(&self, verbose: bool) -> Result<Vec<types::BankListItem>> {
+        self.cached("banks", verbose, |etag| {
+            self.runtime.block_on(async {
+                match recover(self.client.list_banks(None, etag).await, verbose) {
+                    Ok(response) => {
+                        let etag = etag_of(response.headers());
+                        Ok(Some((response.into_inner().banks, etag)))
+                    }
+                    Err(ClientError::UnexpectedResponse(response)) if response.status() == reqwest::StatusCode::NOT_MODIFIED => {
+                        Ok(None)
+                    }
+                    // Report an unexpected body clearly rather than as a parse error
+                    Err(ClientError::InvalidResponsePayload(body, _)) => {
+                        let body = String::from_utf8_lossy(&body);
+                        Ok(Some((parse_banks_response(reqwest::StatusCode::OK, &body, verbose)?, None)))
+                    }
+                    Err(ClientError::UnexpectedResponse(response)) => {
+                        let status = response.status();
+                        let body = response.text().await?;
+                        Ok(Some((parse_banks_response(status, &body, verbose)?, None)))
+                    }
+                    Err(e) => Err(e.into()),
+                }
+            })
+        })
+    }
+
+    /// Send an arbitrary request to `path` (relative to the base URL, query string allowed)
+    /// with this client's auth, headers and timeout. Returns the status and raw body.
+    pub fn raw(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<&serde_json::Value>,
+        verbose: bool,
+    ) -> Result<(reqwest::StatusCode, String)> {
+        let url = format!("{}/{}", self.client.baseurl().trim_end_matches('/'), path.trim_start_matches('/'));
+        let url = reqwest::Url::parse(&url).map_err(|e| anyhow::anyhow!("Invalid request path '{}': {}", path, e))?;
+
+        let mut builder = self
+            .client
+            .client()
+            .request(method, url)
+            .header("api-version", AsyncClient::api_version());
+        if let Some(body) = body {
+            builder = builder.json(body);
+        }
+        let request = builder.build()?;
+
+        if verbose {
+            eprintln!("Request: {} {}", request.method(), request.url());
+            for (name, value) in request.headers() {
+                let value = if is_sensitive_header(name.as_str()) {
+                    "<redacted>"
+                } else {
+                    value.to_str().unwrap_or("<binary>")
+                };
+                eprintln!("  {}: {}", name, value);
+            }
+            if let Some(body) = body {
+                eprintln!("Request body:\n{}", format_request_body(body));
+            }
+        }
+
+        self.runtime.block_on(async {
+            let response = self.execute(request).await?;
+            let status = response.status();
+            let body = response.text().await?;
+            Ok((status, body))
+        })
+    }
+
+    pub fn get_profile(&self, agent_id: &str, verbose: bool) -> Result<types::BankProfileResponse> {
+        self.cached(&format!("profile-{}", agent_id), verbose, |etag| {
+            self.runtime.block_on(async {
+                match with_parse_retry(verbose, || self.client.get_bank_profile(agent_id, None, etag)).await {
+                    Ok(response) => {
+                        let etag = etag_of(response.headers());
+                        Ok(Some((response.into_inner(), etag)))
+                    }
+                    Err(e) if is_not_modified(&e) => Ok(None),
+                    Err(e) => Err(e),
+                }
+            })
+        })
+    }
+
+    /// Profile straight from the server, skipping the cache, so a read-modify-write
+    /// starts from what is stored
+    pub fn get_profile_uncached(&self, agent_id: &str, verbose: bool) -> Result<types::BankProfileResponse> {
+        self.runtime.block_on(async {
+            let response = with_parse_retry(verbose, || self.client.get_bank_profile(agent_id, None, None)).await?;
+            Ok(response.into_inner())
+        })
+    }
+
+    /// Replace all disposition traits at once (`PUT .../profile`)
+    pub fn update_disposition(
+        &self,
+        bank_id: &str,
+        disposition: types::DispositionTraits,
+        verbose: bool,
+    ) -> Result<types::BankProfileResponse> {
+        self.invalidate_cache();
         self.runtime.block_on(async {
-            let response = self.client.get_bank_profile(agent_id, None).await?;
+            let request = types::UpdateDispositionRequest { disposition };
+            let response = recover(self.client.update_bank_disposition(bank_id, None, &request).await, verbose)?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn get_stats(&self, agent_id: &str, _verbose: bool) -> Result<AgentStats> {
+    pub fn get_stats(&self, agent_id: &str, verbose: bool) -> Result<AgentStats> {
         self.runtime.block_on(async {
-            let response = self.client.get_agent_stats(agent_id, None).await?;
+            let response = with_parse_retry(verbose, || self.client.get_agent_stats(agent_id, None)).await?;
             let value = response.into_inner();
             // Convert to JSON Value first, then parse into our type
             let json_value = serde_json::to_value(&value)?;
@@ -113,7 +874,8 @@ impl ApiClient {
         })
     }
 
-    pub fn update_agent_name(&self, agent_id: &str, name: &str, _verbose: bool) -> Result<types::BankProfileResponse> {
+    pub fn update_agent_name(&self, agent_id: &str, name: &str, verbose: bool) -> Result<types::BankProfileResponse> {
+        self.invalidate_cache();
         self.runtime.block_on(async {
             let request = types::CreateBankRequest {
                 name: Some(name.to_string()),
@@ -121,43 +883,107 @@ impl ApiClient {
                 background: None,
                 disposition: None,
             };
-            let response = self.client.create_or_update_bank(agent_id, None, &request).await?;
+            let response = recover(self.client.create_or_update_bank(agent_id, None, &request).await, verbose)?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn add_background(&self, agent_id: &str, content: &str, update_disposition: bool, _verbose: bool) -> Result<types::BackgroundResponse> {
+    pub fn add_background(&self, agent_id: &str, content: &str, update_disposition: bool, verbose: bool) -> Result<types::BackgroundResponse> {
+        self.invalidate_cache();
         self.runtime.block_on(async {
             let request = types::AddBackgroundRequest {
                 content: content.to_string(),
                 update_disposition,
             };
-            let response = self.client.add_bank_background(agent_id, None, &request).await?;
+            let response = recover(self.client.add_bank_background(agent_id, None, &request).await, verbose)?;
             Ok(response.into_inner())
         })
     }
 
     pub fn recall(&self, agent_id: &str, request: &types::RecallRequest, verbose: bool) -> Result<types::RecallResponse> {
         if verbose {
-            eprintln!("Request body: {}", serde_json::to_string_pretty(request).unwrap_or_default());
+            eprintln!("Request body: {}", format_request_body(request));
+        }
+        let memo_key = format!("{}\n{}", agent_id, serde_json::to_string(request)?);
+        if let Some(response) = self.recall_memo.as_ref().and_then(|memo| memo.get(&memo_key)) {
+            if verbose {
+                eprintln!("Memo hit: reusing the result of an identical recent search");
+            }
+            return Ok(response);
+        }
+        let response = self.recall_uncached(agent_id, request, verbose)?;
+        if let Some(memo) = &self.recall_memo {
+            memo.put(memo_key, response.clone());
         }
+        Ok(response)
+    }
+
+    /// Recall from the server even when an identical search is memoized, so
+    /// `recall --benchmark` times real requests
+    pub fn recall_uncached(&self, agent_id: &str, request: &types::RecallRequest, verbose: bool) -> Result<types::RecallResponse> {
         self.runtime.block_on(async {
-            let response = self.client.recall_memories(agent_id, None, request).await?;
+            let response = recover(self.client.recall_memories(agent_id, None, request).await, verbose)?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn reflect(&self, agent_id: &str, request: &types::ReflectRequest, _verbose: bool) -> Result<types::ReflectResponse> {
+    /// Reflect; `sampling` overrides the request's own sampling fields, and all of
+    /// them are dropped for servers known not to support them
+    pub fn reflect(
+        &self,
+        agent_id: &str,
+        request: &types::ReflectRequest,
+        sampling: Sampling,
+        verbose: bool,
+    ) -> Result<types::ReflectResponse> {
+        let sampling = Sampling {
+            temperature: sampling.temperature.or(request.temperature),
+            top_p: sampling.top_p.or(request.top_p),
+            seed: sampling.seed.or(request.seed),
+        };
+        let sampling = self.negotiate_sampling(sampling, verbose);
+        let request = types::ReflectRequest {
+            temperature: sampling.temperature,
+            top_p: sampling.top_p,
+            seed: sampling.seed,
+            ..request.clone()
+        };
+        if verbose {
+            eprintln!("Request body: {}", format_request_body(&request));
+        }
+
         self.runtime.block_on(async {
-            let response = self.client.reflect(agent_id, None, request).await?;
+            let response = recover(self.client.reflect(agent_id, None, &request).await, verbose)?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn retain(&self, agent_id: &str, request: &types::RetainRequest, _async_mode: bool, _verbose: bool) -> Result<MemoryPutResult> {
+    /// Retain memories, sending an `Idempotency-Key` so a retried identical submission
+    /// is deduplicated server-side. The key defaults to a hash of the request body.
+    /// A response with `success: false` is returned as an error.
+    pub fn retain(
+        &self,
+        agent_id: &str,
+        request: &types::RetainRequest,
+        idempotency_key: Option<&str>,
+        _async_mode: bool,
+        verbose: bool,
+    ) -> Result<MemoryPutResult> {
+        self.invalidate_recalls();
+        let key = match idempotency_key {
+            Some(key) => key.to_string(),
+            None => retain_idempotency_key(request)?,
+        };
+        if verbose {
+            eprintln!("Idempotency-Key: {}", key);
+        }
+
         self.runtime.block_on(async {
-            let response = self.client.retain_memories(agent_id, None, request).await?;
+            let response = recover(self.client.retain_memories(agent_id, None, Some(&key), request).await, verbose)?;
             let result = response.into_inner();
+            if !result.success {
+                anyhow::bail!("Retain failed: the server did not accept the memories");
+            }
             Ok(MemoryPutResult {
                 success: result.success,
                 items_count: result.items_count,
@@ -169,46 +995,48 @@ impl ApiClient {
     }
 
     /// Poll an operation until it completes or fails.
-    /// Returns Ok(true) if completed successfully, Ok(false) if failed, Err if polling error.
+    /// Returns Ok(true) if completed successfully, Ok(false) if failed, Err if polling error
+    /// or a [`WaitTimeout`] once the client's wait timeout is exceeded.
     pub fn poll_operation(&self, agent_id: &str, operation_id: &str, verbose: bool) -> Result<(bool, Option<String>)> {
-        self.runtime.block_on(async {
-            loop {
-                let response = self.client.list_operations(agent_id, None, None, None, None).await?;
-                let ops = response.into_inner();
+        let start = std::time::Instant::now();
+        loop {
+            let ops = self.list_operations(agent_id, verbose)?;
 
-                // Find our operation
-                let op = ops.operations.iter().find(|o| o.id == operation_id);
+            // Find our operation
+            let op = ops.operations.iter().find(|o| o.id == operation_id);
 
-                match op {
-                    Some(operation) => {
-                        if verbose {
-                            eprintln!("Operation {} status: {}", operation_id, operation.status);
-                        }
-                        match operation.status.as_str() {
-                            "pending" => {
-                                // Still running, wait and poll again
-                                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-                            }
-                            "completed" => {
-                                // Operation completed successfully
-                                return Ok((true, None));
-                            }
-                            "failed" => {
-                                return Ok((false, operation.error_message.clone()));
-                            }
-                            _ => {
-                                // Unknown status, treat as failed
-                                return Ok((false, Some(format!("Unknown status: {}", operation.status))));
+            match op {
+                Some(operation) => {
+                    if verbose {
+                        eprintln!("Operation {} status: {}", operation_id, operation.status);
+                    }
+                    match operation.status.as_str() {
+                        "pending" => {
+                            if start.elapsed() >= self.wait_timeout {
+                                return Err(self.wait_timed_out(agent_id, operation_id, &operation.status, start.elapsed()));
                             }
+                            // Still running, wait and poll again
+                            std::thread::sleep(std::time::Duration::from_millis(500));
+                        }
+                        "completed" => {
+                            // Operation completed successfully
+                            return Ok((true, None));
+                        }
+                        "failed" => {
+                            return Ok((false, operation.error_message.clone()));
+                        }
+                        _ => {
+                            // Unknown status, treat as failed
+                            return Ok((false, Some(format!("Unknown status: {}", operation.status))));
                         }
                     }
-                    None => {
-                        // Operation not in list means it completed successfully (removed from pending/failed)
-                        return Ok((true, None));
-                    }
+                }
+                None => {
+                    // Operation not in list means it completed successfully (removed from pending/failed)
+                    return Ok((true, None));
                 }
             }
-        })
+        }
     }
 
     pub fn delete_memory(&self, _agent_id: &str, _unit_id: &str, _verbose: bool) -> Result<types::DeleteResponse> {
@@ -216,36 +1044,53 @@ impl ApiClient {
         anyhow::bail!("Individual memory deletion is no longer supported. Use 'memory clear' to clear all memories.")
     }
 
-    pub fn clear_memories(&self, agent_id: &str, fact_type: Option<&str>, _verbose: bool) -> Result<types::DeleteResponse> {
+    pub fn clear_memories(&self, agent_id: &str, fact_type: Option<&str>, verbose: bool) -> Result<types::DeleteResponse> {
+        self.invalidate_recalls();
         self.runtime.block_on(async {
-            let response = self.client.clear_bank_memories(agent_id, None, Some(fact_type)).await?;
+            let response = recover(self.client.clear_bank_memories(agent_id, None, Some(fact_type)).await, verbose)?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn list_documents(&self, agent_id: &str, q: Option<&str>, limit: Option<i32>, offset: Option<i32>, _verbose: bool) -> Result<types::ListDocumentsResponse> {
+    pub fn list_documents(&self, agent_id: &str, q: Option<&str>, limit: Option<i32>, offset: Option<i32>, verbose: bool) -> Result<types::ListDocumentsResponse> {
         self.runtime.block_on(async {
-            let response = self.client.list_documents(
+            let response = with_parse_retry(verbose, || self.client.list_documents(
                 agent_id,
                 limit.map(|l| l as i64),
                 offset.map(|o| o as i64),
                 q,
                 None,
-            ).await?;
+            )).await?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn get_document(&self, agent_id: &str, document_id: &str, _verbose: bool) -> Result<types::DocumentResponse> {
+    /// Page through every document in a bank, fetching one page per `next()`
+    pub fn iter_documents<'a>(&'a self, agent_id: &'a str, q: Option<&'a str>, page_size: i32, verbose: bool) -> DocumentPages<'a> {
+        DocumentPages {
+            client: self,
+            agent_id,
+            q,
+            page_size,
+            offset: 0,
+            seen: 0,
+            yielded: HashSet::new(),
+            done: false,
+            verbose,
+        }
+    }
+
+    pub fn get_document(&self, agent_id: &str, document_id: &str, verbose: bool) -> Result<types::DocumentResponse> {
         self.runtime.block_on(async {
-            let response = self.client.get_document(agent_id, document_id, None).await?;
+            let response = with_parse_retry(verbose, || self.client.get_document(agent_id, document_id, None)).await?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn delete_document(&self, agent_id: &str, document_id: &str, _verbose: bool) -> Result<types::DeleteResponse> {
+    pub fn delete_document(&self, agent_id: &str, document_id: &str, verbose: bool) -> Result<types::DeleteResponse> {
+        self.invalidate_recalls();
         self.runtime.block_on(async {
-            let response = self.client.delete_document(agent_id, document_id, None).await?;
+            let response = recover(self.client.delete_document(agent_id, document_id, None).await, verbose)?;
             let value = response.into_inner();
             // Convert typed response to DeleteResponse
             Ok(types::DeleteResponse {
@@ -256,9 +1101,9 @@ impl ApiClient {
         })
     }
 
-    pub fn list_operations(&self, agent_id: &str, _verbose: bool) -> Result<OperationsResponse> {
+    pub fn list_operations(&self, agent_id: &str, verbose: bool) -> Result<OperationsResponse> {
         self.runtime.block_on(async {
-            let response = self.client.list_operations(agent_id, None, None, None, None).await?;
+            let response = with_parse_retry(verbose, || self.client.list_operations(agent_id, None, None, None, None)).await?;
             let value = response.into_inner();
             // Convert to JSON Value first, then parse into our type
             let json_value = serde_json::to_value(&value)?;
@@ -267,9 +1112,9 @@ impl ApiClient {
         })
     }
 
-    pub fn cancel_operation(&self, agent_id: &str, operation_id: &str, _verbose: bool) -> Result<types::DeleteResponse> {
+    pub fn cancel_operation(&self, agent_id: &str, operation_id: &str, verbose: bool) -> Result<types::DeleteResponse> {
         self.runtime.block_on(async {
-            let response = self.client.cancel_operation(agent_id, operation_id, None).await?;
+            let response = recover(self.client.cancel_operation(agent_id, operation_id, None).await, verbose)?;
             let value = response.into_inner();
             // Convert typed response to DeleteResponse
             Ok(types::DeleteResponse {
@@ -280,37 +1125,39 @@ impl ApiClient {
         })
     }
 
-    pub fn list_memories(&self, bank_id: &str, type_filter: Option<&str>, q: Option<&str>, limit: Option<i64>, offset: Option<i64>, _verbose: bool) -> Result<types::ListMemoryUnitsResponse> {
+    pub fn list_memories(&self, bank_id: &str, type_filter: Option<&str>, q: Option<&str>, limit: Option<i64>, offset: Option<i64>, verbose: bool) -> Result<types::ListMemoryUnitsResponse> {
         self.runtime.block_on(async {
-            let response = self.client.list_memories(bank_id, limit, offset, q, type_filter, None).await?;
+            let response = with_parse_retry(verbose, || self.client.list_memories(bank_id, limit, offset, q, type_filter, None)).await?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn list_entities(&self, bank_id: &str, limit: Option<i64>, offset: Option<i64>, _verbose: bool) -> Result<types::EntityListResponse> {
+    pub fn list_entities(&self, bank_id: &str, limit: Option<i64>, offset: Option<i64>, verbose: bool) -> Result<types::EntityListResponse> {
         self.runtime.block_on(async {
-            let response = self.client.list_entities(bank_id, limit, offset, None).await?;
+            let response = with_parse_retry(verbose, || self.client.list_entities(bank_id, limit, offset, None)).await?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn get_entity(&self, bank_id: &str, entity_id: &str, _verbose: bool) -> Result<types::EntityDetailResponse> {
+    pub fn get_entity(&self, bank_id: &str, entity_id: &str, verbose: bool) -> Result<types::EntityDetailResponse> {
         self.runtime.block_on(async {
-            let response = self.client.get_entity(bank_id, entity_id, None).await?;
+            let response = with_parse_retry(verbose, || self.client.get_entity(bank_id, entity_id, None)).await?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn regenerate_entity(&self, bank_id: &str, entity_id: &str, _verbose: bool) -> Result<types::EntityDetailResponse> {
+    pub fn regenerate_entity(&self, bank_id: &str, entity_id: &str, verbose: bool) -> Result<types::EntityDetailResponse> {
         self.runtime.block_on(async {
-            let response = self.client.regenerate_entity_observations(bank_id, entity_id, None).await?;
+            let response = recover(self.client.regenerate_entity_observations(bank_id, entity_id, None).await, verbose)?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn delete_bank(&self, bank_id: &str, _verbose: bool) -> Result<types::DeleteResponse> {
+    pub fn delete_bank(&self, bank_id: &str, verbose: bool) -> Result<types::DeleteResponse> {
+        self.invalidate_cache();
+        self.invalidate_recalls();
         self.runtime.block_on(async {
-            let response = self.client.delete_bank(bank_id, None).await?;
+            let response = recover(self.client.delete_bank(bank_id, None).await, verbose)?;
             Ok(response.into_inner())
         })
     }
@@ -323,9 +1170,9 @@ impl ApiClient {
 impl ApiClient {
     // --- Memory Methods ---
 
-    pub fn get_memory(&self, bank_id: &str, memory_id: &str, _verbose: bool) -> Result<serde_json::Value> {
+    pub fn get_memory(&self, bank_id: &str, memory_id: &str, verbose: bool) -> Result<serde_json::Value> {
         self.runtime.block_on(async {
-            let response = self.client.get_memory(bank_id, memory_id, None).await?;
+            let response = with_parse_retry(verbose, || self.client.get_memory(bank_id, memory_id, None)).await?;
             Ok(response.into_inner())
         })
     }
@@ -336,10 +1183,11 @@ impl ApiClient {
         &self,
         bank_id: &str,
         request: &types::CreateBankRequest,
-        _verbose: bool,
+        verbose: bool,
     ) -> Result<types::BankProfileResponse> {
+        self.invalidate_cache();
         self.runtime.block_on(async {
-            let response = self.client.create_or_update_bank(bank_id, None, request).await?;
+            let response = recover(self.client.create_or_update_bank(bank_id, None, request).await, verbose)?;
             Ok(response.into_inner())
         })
     }
@@ -348,10 +1196,11 @@ impl ApiClient {
         &self,
         bank_id: &str,
         request: &types::CreateBankRequest,
-        _verbose: bool,
+        verbose: bool,
     ) -> Result<types::BankProfileResponse> {
+        self.invalidate_cache();
         self.runtime.block_on(async {
-            let response = self.client.update_bank(bank_id, None, request).await?;
+            let response = recover(self.client.update_bank(bank_id, None, request).await, verbose)?;
             Ok(response.into_inner())
         })
     }
@@ -360,8 +1209,9 @@ impl ApiClient {
         &self,
         bank_id: &str,
         mission: &str,
-        _verbose: bool,
+        verbose: bool,
     ) -> Result<types::BankProfileResponse> {
+        self.invalidate_cache();
         self.runtime.block_on(async {
             let request = types::CreateBankRequest {
                 name: None,
@@ -369,7 +1219,7 @@ impl ApiClient {
                 background: None,
                 disposition: None,
             };
-            let response = self.client.update_bank(bank_id, None, &request).await?;
+            let response = recover(self.client.update_bank(bank_id, None, &request).await, verbose)?;
             Ok(response.into_inner())
         })
     }
@@ -379,10 +1229,10 @@ impl ApiClient {
         bank_id: &str,
         type_filter: Option<&str>,
         limit: Option<i64>,
-        _verbose: bool,
+        verbose: bool,
     ) -> Result<types::GraphDataResponse> {
         self.runtime.block_on(async {
-            let response = self.client.get_graph(bank_id, limit, type_filter, None).await?;
+            let response = with_parse_retry(verbose, || self.client.get_graph(bank_id, limit, type_filter, None)).await?;
             Ok(response.into_inner())
         })
     }
@@ -395,61 +1245,101 @@ impl ApiClient {
         q: Option<&str>,
         limit: Option<i64>,
         offset: Option<i64>,
-        _verbose: bool,
+        verbose: bool,
     ) -> Result<types::ListTagsResponse> {
         self.runtime.block_on(async {
-            let response = self.client.list_tags(bank_id, limit, offset, q, None).await?;
+            let response = with_parse_retry(verbose, || self.client.list_tags(bank_id, limit, offset, q, None)).await?;
             Ok(response.into_inner())
         })
     }
 
     // --- Chunk Methods ---
 
-    pub fn get_chunk(&self, chunk_id: &str, _verbose: bool) -> Result<types::ChunkResponse> {
+    pub fn get_chunk(&self, chunk_id: &str, verbose: bool) -> Result<types::ChunkResponse> {
         self.runtime.block_on(async {
-            let response = self.client.get_chunk(chunk_id, None).await?;
+            let response = with_parse_retry(verbose, || self.client.get_chunk(chunk_id, None)).await?;
             Ok(response.into_inner())
         })
     }
 
     // --- Operation Methods ---
 
-    pub fn get_operation(&self, bank_id: &str, operation_id: &str, _verbose: bool) -> Result<types::OperationStatusResponse> {
+    pub fn get_operation(&self, bank_id: &str, operation_id: &str, verbose: bool) -> Result<types::OperationStatusResponse> {
         self.runtime.block_on(async {
-            let response = self.client.get_operation_status(bank_id, operation_id, None).await?;
+            let response = with_parse_retry(verbose, || self.client.get_operation_status(bank_id, operation_id, None)).await?;
             Ok(response.into_inner())
         })
     }
 
     // --- Health Methods ---
 
-    pub fn health(&self, _verbose: bool) -> Result<serde_json::Value> {
+    pub fn health(&self, verbose: bool) -> Result<serde_json::Value> {
         self.runtime.block_on(async {
-            let response = self.client.health_endpoint_health_get().await?;
+            let response = with_parse_retry(verbose, || self.client.health_endpoint_health_get()).await?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn metrics(&self, _verbose: bool) -> Result<serde_json::Value> {
+    pub fn metrics(&self, verbose: bool) -> Result<serde_json::Value> {
         self.runtime.block_on(async {
-            let response = self.client.metrics_endpoint_metrics_get().await?;
+            let response = recover(self.client.metrics_endpoint_metrics_get().await, verbose)?;
             Ok(response.into_inner())
         })
     }
 
     // --- Mental Model Methods ---
 
-    pub fn list_mental_models(&self, bank_id: &str, _verbose: bool) -> Result<types::MentalModelListResponse> {
+    /// Every mental model in the bank, fetched page by page
+    pub fn list_mental_models(&self, bank_id: &str, verbose: bool) -> Result<types::MentalModelListResponse> {
+        let items = collect_pages(LIST_PAGE_SIZE, |limit, offset| {
+            self.runtime.block_on(async {
+                let response = with_parse_retry(verbose, || {
+                    self.client.list_mental_models(bank_id, page_param(limit), page_param(offset), None, None, None)
+                })
+                .await?;
+                Ok(response.into_inner().items)
+            })
+        })?;
+        Ok(types::MentalModelListResponse { items })
+    }
+
+    pub fn get_mental_model(&self, bank_id: &str, mental_model_id: &str, verbose: bool) -> Result<types::MentalModelResponse> {
         self.runtime.block_on(async {
-            let response = self.client.list_mental_models(bank_id, None, None, None, None, None).await?;
+            let response = with_parse_retry(verbose, || self.client.get_mental_model(bank_id, mental_model_id, None)).await?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn get_mental_model(&self, bank_id: &str, mental_model_id: &str, _verbose: bool) -> Result<types::MentalModelResponse> {
+    /// Earlier versions of a mental model, oldest first, or None when the server
+    /// keeps no history. `.../history` is not in the OpenAPI spec, so this probes it
+    pub fn get_mental_model_history(
+        &self,
+        bank_id: &str,
+        mental_model_id: &str,
+        verbose: bool,
+    ) -> Result<Option<Vec<MentalModelVersion>>> {
+        let url = self.url(&["v1", "default", "banks", bank_id, "mental-models", mental_model_id, "history"])?;
+
+        let request = self
+            .client
+            .client()
+            .get(url)
+            .header("api-version", AsyncClient::api_version())
+            .build()?;
+
         self.runtime.block_on(async {
-            let response = self.client.get_mental_model(bank_id, mental_model_id, None).await?;
-            Ok(response.into_inner())
+            let response = self.execute(request).await?;
+
+            let status = response.status();
+            if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::METHOD_NOT_ALLOWED {
+                return Ok(None);
+            }
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(crate::errors::response_error(body.as_bytes(), format!("Error Response: status: {}; body: {}", status, body)));
+            }
+            let body = response.bytes().await?;
+            parse_history(&body, verbose).map(Some)
         })
     }
 
@@ -457,10 +1347,10 @@ impl ApiClient {
         &self,
         bank_id: &str,
         request: &types::CreateMentalModelRequest,
-        _verbose: bool,
+        verbose: bool,
     ) -> Result<types::CreateMentalModelResponse> {
         self.runtime.block_on(async {
-            let response = self.client.create_mental_model(bank_id, None, request).await?;
+            let response = recover(self.client.create_mental_model(bank_id, None, request).await, verbose)?;
             Ok(response.into_inner())
         })
     }
@@ -470,40 +1360,47 @@ impl ApiClient {
         bank_id: &str,
         mental_model_id: &str,
         request: &types::UpdateMentalModelRequest,
-        _verbose: bool,
+        verbose: bool,
     ) -> Result<types::MentalModelResponse> {
         self.runtime.block_on(async {
-            let response = self.client.update_mental_model(bank_id, mental_model_id, None, request).await?;
+            let response = recover(self.client.update_mental_model(bank_id, mental_model_id, None, request).await, verbose)?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn delete_mental_model(&self, bank_id: &str, mental_model_id: &str, _verbose: bool) -> Result<serde_json::Value> {
+    pub fn delete_mental_model(&self, bank_id: &str, mental_model_id: &str, verbose: bool) -> Result<serde_json::Value> {
         self.runtime.block_on(async {
-            let response = self.client.delete_mental_model(bank_id, mental_model_id, None).await?;
+            let response = recover(self.client.delete_mental_model(bank_id, mental_model_id, None).await, verbose)?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn refresh_mental_model(&self, bank_id: &str, mental_model_id: &str, _verbose: bool) -> Result<types::AsyncOperationSubmitResponse> {
+    pub fn refresh_mental_model(&self, bank_id: &str, mental_model_id: &str, verbose: bool) -> Result<types::AsyncOperationSubmitResponse> {
         self.runtime.block_on(async {
-            let response = self.client.refresh_mental_model(bank_id, mental_model_id, None).await?;
+            let response = recover(self.client.refresh_mental_model(bank_id, mental_model_id, None).await, verbose)?;
             Ok(response.into_inner())
         })
     }
 
     // --- Directive Methods ---
 
-    pub fn list_directives(&self, bank_id: &str, _verbose: bool) -> Result<types::DirectiveListResponse> {
-        self.runtime.block_on(async {
-            let response = self.client.list_directives(bank_id, None, None, None, None, None, None).await?;
-            Ok(response.into_inner())
-        })
+    /// Every directive in the bank, fetched page by page
+    pub fn list_directives(&self, bank_id: &str, verbose: bool) -> Result<types::DirectiveListResponse> {
+        let items = collect_pages(LIST_PAGE_SIZE, |limit, offset| {
+            self.runtime.block_on(async {
+                let response = with_parse_retry(verbose, || {
+                    self.client.list_directives(bank_id, None, page_param(limit), page_param(offset), None, None, None)
+                })
+                .await?;
+                Ok(response.into_inner().items)
+            })
+        })?;
+        Ok(types::DirectiveListResponse { items })
     }
 
-    pub fn get_directive(&self, bank_id: &str, directive_id: &str, _verbose: bool) -> Result<types::DirectiveResponse> {
+    pub fn get_directive(&self, bank_id: &str, directive_id: &str, verbose: bool) -> Result<types::DirectiveResponse> {
         self.runtime.block_on(async {
-            let response = self.client.get_directive(bank_id, directive_id, None).await?;
+            let response = with_parse_retry(verbose, || self.client.get_directive(bank_id, directive_id, None)).await?;
             Ok(response.into_inner())
         })
     }
@@ -512,10 +1409,10 @@ impl ApiClient {
         &self,
         bank_id: &str,
         request: &types::CreateDirectiveRequest,
-        _verbose: bool,
+        verbose: bool,
     ) -> Result<types::DirectiveResponse> {
         self.runtime.block_on(async {
-            let response = self.client.create_directive(bank_id, None, request).await?;
+            let response = recover(self.client.create_directive(bank_id, None, request).await, verbose)?;
             Ok(response.into_inner())
         })
     }
@@ -525,42 +1422,44 @@ impl ApiClient {
         bank_id: &str,
         directive_id: &str,
         request: &types::UpdateDirectiveRequest,
-        _verbose: bool,
+        verbose: bool,
     ) -> Result<types::DirectiveResponse> {
         self.runtime.block_on(async {
-            let response = self.client.update_directive(bank_id, directive_id, None, request).await?;
+            let response = recover(self.client.update_directive(bank_id, directive_id, None, request).await, verbose)?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn delete_directive(&self, bank_id: &str, directive_id: &str, _verbose: bool) -> Result<serde_json::Value> {
+    pub fn delete_directive(&self, bank_id: &str, directive_id: &str, verbose: bool) -> Result<serde_json::Value> {
         self.runtime.block_on(async {
-            let response = self.client.delete_directive(bank_id, directive_id, None).await?;
+            let response = recover(self.client.delete_directive(bank_id, directive_id, None).await, verbose)?;
             Ok(response.into_inner())
         })
     }
 
     // --- Consolidation Methods ---
 
-    pub fn trigger_consolidation(&self, bank_id: &str, _verbose: bool) -> Result<types::ConsolidationResponse> {
+    pub fn trigger_consolidation(&self, bank_id: &str, verbose: bool) -> Result<types::ConsolidationResponse> {
+        self.invalidate_recalls();
         self.runtime.block_on(async {
-            let response = self.client.trigger_consolidation(bank_id, None).await?;
+            let response = recover(self.client.trigger_consolidation(bank_id, None).await, verbose)?;
             Ok(response.into_inner())
         })
     }
 
-    pub fn clear_observations(&self, bank_id: &str, _verbose: bool) -> Result<types::DeleteResponse> {
+    pub fn clear_observations(&self, bank_id: &str, verbose: bool) -> Result<types::DeleteResponse> {
+        self.invalidate_recalls();
         self.runtime.block_on(async {
-            let response = self.client.clear_observations(bank_id, None).await?;
+            let response = recover(self.client.clear_observations(bank_id, None).await, verbose)?;
             Ok(response.into_inner())
         })
     }
 
     // --- Version Methods ---
 
-    pub fn get_version(&self, _verbose: bool) -> Result<types::VersionResponse> {
+    pub fn get_version(&self, verbose: bool) -> Result<types::VersionResponse> {
         self.runtime.block_on(async {
-            let response = self.client.get_version().await?;
+            let response = with_parse_retry(verbose, || self.client.get_version()).await?;
             Ok(response.into_inner())
         })
     }
@@ -582,6 +1481,193 @@ pub use types::{
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_drop_yielded_skips_repeated_documents() {
+        let doc = |id: &str| serde_json::json!({"id": id}).as_object().unwrap().clone();
+        let mut yielded = HashSet::new();
+
+        let mut first = vec![doc("a"), doc("b")];
+        drop_yielded(&mut first, &mut yielded);
+        assert_eq!(first.len(), 2);
+
+        // An insert before "b" shifted it onto the next page
+        let mut second = vec![doc("b"), doc("c")];
+        drop_yielded(&mut second, &mut yielded);
+        assert_eq!(second, vec![doc("c")]);
+    }
+
+    #[test]
+    fn test_redact_url_credentials() {
+        assert_eq!(redact_url_credentials("http://user:pw@proxy:3128"), "http://<redacted>@proxy:3128");
+        assert_eq!(redact_url_credentials("http://proxy:3128/path@x"), "http://proxy:3128/path@x");
+        assert_eq!(redact_url_credentials("proxy:3128"), "proxy:3128");
+    }
+
+    #[test]
+    fn test_parse_history_shapes() {
+        let list = br#"[{"content": "new", "refreshed_at": "2026-02-01T00:00:00Z"}, {"content": "old", "created_at": "2026-01-01T00:00:00Z"}]"#;
+        let versions = parse_history(list, false).unwrap();
+        let contents: Vec<_> = versions.iter().map(|v| v.content.as_str()).collect();
+        assert_eq!(contents, ["old", "new"]);
+
+        let wrapped = br#"{"versions": [{"content": "only", "timestamp": "2026-01-01T00:00:00Z"}]}"#;
+        assert_eq!(parse_history(wrapped, false).unwrap()[0].timestamp.as_deref(), Some("2026-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_capabilities_drop_unsupported_sampling() {
+        // Fields a server doesn't report default to off, or unknown for optional fields
+        let capabilities: Capabilities = serde_json::from_str(r#"{"api_version": "0.5.0", "mcp": true}"#).unwrap();
+        assert!(capabilities.mcp);
+        let capabilities = Capabilities { reflect_sampling: Some(false), ..capabilities };
+
+        let sampling = Sampling { seed: Some(7), ..Sampling::default() };
+        assert!(capabilities.supported_sampling(sampling).is_empty());
+
+        let capabilities = Capabilities { reflect_sampling: Some(true), ..capabilities };
+        assert_eq!(capabilities.supported_sampling(sampling).seed, Some(7));
+    }
+
+    #[test]
+    fn test_capabilities_from_version_keep_sampling() {
+        let version: types::VersionResponse = serde_json::from_value(serde_json::json!({
+            "api_version": "0.4.0",
+            "features": {"mcp": true, "observations": false, "worker": true},
+        }))
+        .unwrap();
+        let capabilities = Capabilities::from_version(&version);
+        assert_eq!(capabilities.source, "version");
+        assert!(capabilities.mcp && capabilities.worker && !capabilities.observations);
+        assert_eq!(capabilities.reflect_sampling, None);
+
+        // Without a capabilities endpoint the fields are sent as given
+        let sampling = Sampling { temperature: Some(0.2), top_p: Some(0.9), seed: Some(7) };
+        let sent = capabilities.supported_sampling(sampling);
+        assert_eq!((sent.temperature, sent.top_p, sent.seed), (Some(0.2), Some(0.9), Some(7)));
+    }
+
+    #[test]
+    fn test_wait_timeout_message() {
+        let timeout = WaitTimeout {
+            bank_id: "b".to_string(),
+            operation_id: "op-1".to_string(),
+            last_status: "pending".to_string(),
+            elapsed: Duration::from_secs(600),
+            cancelled: false,
+        };
+        assert_eq!(timeout.to_string(), "Gave up waiting for operation op-1 after 600s (last status: pending)");
+
+        let err: anyhow::Error = WaitTimeout { cancelled: true, ..timeout }.into();
+        assert!(err.to_string().ends_with("; the operation was cancelled"));
+        assert!(err.downcast_ref::<WaitTimeout>().is_some());
+    }
+
+    #[test]
+    fn test_collect_pages_until_short_page() {
+        let all: Vec<u64> = (0..250).collect();
+        let mut requests = Vec::new();
+        let items = collect_pages(100, |limit, offset| {
+            requests.push((limit, offset));
+            Ok(all.iter().copied().skip(offset as usize).take(limit as usize).collect())
+        })
+        .unwrap();
+        assert_eq!(items, all);
+        assert_eq!(requests, [(100, 0), (100, 100), (100, 200)]);
+
+        // An exact multiple of the page size needs one more (empty) page to be sure
+        let mut calls = 0;
+        let items = collect_pages(2, |_, offset| {
+            calls += 1;
+            Ok(if offset < 4 { vec![offset, offset + 1] } else { Vec::new() })
+        })
+        .unwrap();
+        assert_eq!(items, [0, 1, 2, 3]);
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_empty_sampling_skips_discovery() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let client = ApiClient::new(url, None, None, &[], false).unwrap();
+
+        assert!(client.negotiate_sampling(Sampling::default(), false).is_empty());
+        assert!(client.capabilities.get().is_none());
+        let accepted = listener.accept().map_err(|e| e.kind());
+        assert_eq!(accepted.err(), Some(std::io::ErrorKind::WouldBlock));
+    }
+
+    #[test]
+    fn test_cached_revalidation_outcomes() {
+        let root = std::env::temp_dir().join(format!("hindsight-api-cache-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let cache = ResponseCache::in_dir(root.clone(), "http://localhost:1", &[], Duration::ZERO);
+        cache.put("banks", &vec!["a".to_string()], Some("\"v1\""));
+        let client = ApiClient::new("http://localhost:1".to_string(), None, None, &[], false)
+            .unwrap()
+            .with_cache(Some(cache));
+
+        // Unreachable server: the stale entry is better than failing
+        let value: Vec<String> = client
+            .cached("banks", false, |etag| {
+                assert_eq!(etag, Some("\"v1\""));
+                Err(anyhow::anyhow!("Communication Error: error sending request"))
+            })
+            .unwrap();
+        assert_eq!(value, ["a"]);
+
+        // Any other failure is reported
+        assert!(client
+            .cached::<Vec<String>>("banks", false, |_| Err(anyhow::anyhow!("Error Response: status: 401")))
+            .is_err());
+
+        // 304 keeps the entry; a changed resource replaces it
+        assert_eq!(client.cached::<Vec<String>>("banks", false, |_| Ok(None)).unwrap(), ["a"]);
+        let value: Vec<String> = client
+            .cached("banks", false, |_| Ok(Some((vec!["b".to_string()], Some("\"v2\"".to_string())))))
+            .unwrap();
+        assert_eq!(value, ["b"]);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_tenant_base_url() {
+        assert_eq!(tenant_base_url("http://host:8888/", None), "http://host:8888");
+        assert_eq!(tenant_base_url("http://host:8888/", Some("acme")), "http://host:8888/tenants/acme");
+    }
+
+    #[test]
+    fn test_endpoint_url_encodes_segments() {
+        let url = endpoint_url("http://host:8888/tenants/acme", &["v1", "default", "banks", "a/b c", "directives"]).unwrap();
+        assert_eq!(url.as_str(), "http://host:8888/tenants/acme/v1/default/banks/a%2Fb%20c/directives");
+
+        let url = endpoint_url("http://host:8888/", &["v1", "default", "banks", "b", "mental-models", "m?x=1"]).unwrap();
+        assert_eq!(url.as_str(), "http://host:8888/v1/default/banks/b/mental-models/m%3Fx=1");
+    }
+
+    #[test]
+    fn test_custom_header_parse() {
+        let header = CustomHeader::parse("X-Tenant-ID:  acme ").unwrap();
+        assert_eq!(header.name.as_str(), "x-tenant-id");
+        assert_eq!(header.value, "acme");
+        assert_eq!(header.redacted(), "x-tenant-id: acme");
+
+        assert!(CustomHeader::parse("no-colon").is_err());
+        assert!(CustomHeader::parse(": value").is_err());
+        assert!(CustomHeader::parse("bad name: value").is_err());
+    }
+
+    #[test]
+    fn test_custom_header_redaction() {
+        for raw in ["X-Api-Key: s3cret", "Authorization: Basic abc", "X-Auth-Token: t"] {
+            let header = CustomHeader::parse(raw).unwrap();
+            assert!(header.is_sensitive(), "{} should be sensitive", raw);
+            assert!(!header.redacted().contains("s3cret"));
+            assert!(header.redacted().ends_with("<redacted>"));
+        }
+    }
+
     #[test]
     fn test_operation_deserialize() {
         let json = r#"{
@@ -678,4 +1764,57 @@ mod tests {
         assert_eq!(ops.operations[0].status, "pending");
         assert_eq!(ops.operations[1].status, "completed");
     }
+
+    #[test]
+    fn test_retain_idempotency_key_is_stable() {
+        let request = |content: &str| RetainRequest {
+            items: vec![MemoryItem {
+                content: content.to_string(),
+                context: None,
+                document_id: Some("doc-1".to_string()),
+                entities: None,
+                metadata: None,
+                tags: None,
+                timestamp: None,
+            }],
+            async_: false,
+            document_tags: None,
+        };
+
+        let key = retain_idempotency_key(&request("hello")).unwrap();
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, retain_idempotency_key(&request("hello")).unwrap());
+        assert_ne!(key, retain_idempotency_key(&request("goodbye")).unwrap());
+    }
+
+    #[test]
+    fn test_parse_banks_response_success() {
+        let body = r#"{"banks": [{"bank_id": "alice", "disposition": {"skepticism": 3, "literalism": 3, "empathy": 3}}]}"#;
+        let banks = parse_banks_response(reqwest::StatusCode::OK, body, false).unwrap();
+        assert_eq!(banks.len(), 1);
+        assert_eq!(banks[0].bank_id, "alice");
+    }
+
+    #[test]
+    fn test_parse_banks_response_error_body() {
+        let err = parse_banks_response(reqwest::StatusCode::UNAUTHORIZED, r#"{"detail": "Invalid API key"}"#, false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("401"));
+        assert!(err.contains("Invalid API key"));
+
+        let err = parse_banks_response(reqwest::StatusCode::OK, r#"{"error": "database unavailable"}"#, false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("database unavailable"));
+    }
+
+    #[test]
+    fn test_parse_banks_response_garbage() {
+        let err = parse_banks_response(reqwest::StatusCode::OK, "<html>gateway</html>", false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Unexpected banks response shape"));
+        assert!(err.contains("<html>gateway</html>"));
+    }
 }
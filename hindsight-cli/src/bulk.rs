@@ -0,0 +1,333 @@
+//! Shared error handling for bulk commands (import, retain-files, cancel --all).
+//!
+//! Each command reports per-item results into a [`BulkOutcome`], which decides
+//! whether to stop at the first failure and formats the final error summary.
+//! [`run_ordered`] runs the per-item requests in parallel while keeping output
+//! in input order. [`with_retries`] retries transient failures from a budget shared
+//! by the whole command, so an outage ends the command instead of stalling it.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::ui;
+
+/// Requests a bulk command keeps in flight unless `--concurrency` says otherwise
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Transient failures each item may retry unless `--retries` says otherwise
+pub const DEFAULT_RETRIES: usize = 2;
+
+/// Retries a whole command may spend unless `--max-total-retries` says otherwise
+pub const DEFAULT_MAX_TOTAL_RETRIES: usize = 20;
+
+/// A transient failure came after the command had spent all its retries
+#[derive(Debug, thiserror::Error)]
+#[error("Used all {0} retries allowed by --max-total-retries; the server looks unavailable, so stopping")]
+pub struct RetryBudgetExhausted(pub usize);
+
+/// Retries allowed per item and in total, shared by every sub-request of one command
+pub struct RetryBudget {
+    per_item: AtomicUsize,
+    total: AtomicUsize,
+    used: AtomicUsize,
+    base_delay: Duration,
+}
+
+impl RetryBudget {
+    const fn new(base_delay: Duration) -> Self {
+        Self {
+            per_item: AtomicUsize::new(0),
+            total: AtomicUsize::new(0),
+            used: AtomicUsize::new(0),
+            base_delay,
+        }
+    }
+
+    fn configure(&self, per_item: usize, total: usize) {
+        self.per_item.store(per_item, Ordering::Relaxed);
+        self.total.store(total, Ordering::Relaxed);
+        self.used.store(0, Ordering::Relaxed);
+    }
+
+    /// Claim one retry, or false once the total is spent
+    fn take(&self) -> bool {
+        let total = self.total.load(Ordering::Relaxed);
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| (used < total).then_some(used + 1))
+            .is_ok()
+    }
+
+    fn run<R>(&self, request: impl Fn() -> Result<R>) -> Result<R> {
+        let per_item = self.per_item.load(Ordering::Relaxed);
+        let mut attempt = 0;
+        loop {
+            let error = match request() {
+                Err(e) if attempt < per_item && crate::errors::is_transient(&e) => e,
+                other => return other,
+            };
+            if !self.take() {
+                return Err(error.context(RetryBudgetExhausted(self.total.load(Ordering::Relaxed))));
+            }
+            attempt += 1;
+            // Exponential backoff: 1x, 2x, 4x ... the base delay, capped at 16x
+            std::thread::sleep(self.base_delay * (1 << (attempt - 1).min(4)));
+        }
+    }
+}
+
+static RETRIES: RetryBudget = RetryBudget::new(Duration::from_millis(500));
+
+/// Set `--retries` and `--max-total-retries` for this command
+pub fn set_retries(per_item: usize, total: usize) {
+    RETRIES.configure(per_item, total);
+}
+
+/// Run one sub-request, retrying transient failures while the item's and the
+/// command's retries last
+pub fn with_retries<R>(request: impl Fn() -> Result<R>) -> Result<R> {
+    RETRIES.run(request)
+}
+
+/// Run `task` for every item with at most `concurrency` running at once.
+///
+/// Results are passed to `on_result` strictly in input order: a result that
+/// finishes early is buffered until everything before it has been handled.
+/// If `on_result` fails, no new items are started and that error is returned.
+pub fn run_ordered<I, R, T, F>(items: &[I], concurrency: usize, task: T, mut on_result: F) -> Result<()>
+where
+    I: Sync,
+    R: Send,
+    T: Fn(&I) -> R + Sync,
+    F: FnMut(&I, R) -> Result<()>,
+{
+    let next = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let (tx, rx) = mpsc::channel();
+
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency.clamp(1, items.len().max(1)) {
+            let tx = tx.clone();
+            let (next, stop, task) = (&next, &stop, &task);
+            scope.spawn(move || loop {
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else { break };
+                if tx.send((index, task(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        let mut pending = BTreeMap::new();
+        let mut emitted = 0;
+        for (index, result) in rx {
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&emitted) {
+                if let Err(e) = on_result(&items[emitted], result) {
+                    stop.store(true, Ordering::Relaxed);
+                    return Err(e);
+                }
+                emitted += 1;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// How a bulk command reacts to a failed item
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorMode {
+    /// Stop at the first failure
+    FailFast,
+    /// Process every item, then report all failures
+    CollectErrors,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkFailure {
+    pub item: String,
+    pub error: String,
+}
+
+/// Accumulates per-item results for a bulk command
+#[derive(Debug, Serialize)]
+pub struct BulkOutcome {
+    #[serde(skip)]
+    mode: ErrorMode,
+    pub succeeded: usize,
+    pub failed: Vec<BulkFailure>,
+}
+
+impl BulkOutcome {
+    pub fn new(mode: ErrorMode) -> Self {
+        Self {
+            mode,
+            succeeded: 0,
+            failed: Vec::new(),
+        }
+    }
+
+    pub fn success(&mut self) {
+        self.succeeded += 1;
+    }
+
+    /// Record a failed item. In fail-fast mode the error is returned so the caller stops;
+    /// an exhausted retry budget stops the command in either mode.
+    pub fn failure(&mut self, item: impl Into<String>, error: anyhow::Error) -> Result<()> {
+        let item = item.into();
+        if let Some(exhausted) = error.downcast_ref::<RetryBudgetExhausted>() {
+            let message = format!("{} failed. {}", item, exhausted);
+            return Err(error.context(message));
+        }
+        if self.mode == ErrorMode::FailFast {
+            return Err(error.context(format!("{} failed", item)));
+        }
+        self.failed.push(BulkFailure {
+            item,
+            error: format!("{:#}", error),
+        });
+        Ok(())
+    }
+
+    pub fn has_failures(&self) -> bool {
+        !self.failed.is_empty()
+    }
+
+    /// Print collected failures to stderr, so they never mix with JSON/YAML output
+    pub fn print_summary(&self, action: &str) {
+        if self.failed.is_empty() {
+            return;
+        }
+        ui::print_error(&format!(
+            "{} finished with {} failure(s) ({} succeeded):",
+            action,
+            self.failed.len(),
+            self.succeeded
+        ));
+        for failure in &self.failed {
+            eprintln!("  {} {}", ui::dim(&format!("{}:", failure.item)), failure.error);
+        }
+        eprintln!();
+    }
+
+    /// Exit non-zero if any item failed
+    pub fn finish(self, action: &str) -> Result<()> {
+        if self.failed.is_empty() {
+            return Ok(());
+        }
+        anyhow::bail!("{} finished with {} failure(s)", action, self.failed.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fail_fast_returns_first_error() {
+        let mut outcome = BulkOutcome::new(ErrorMode::FailFast);
+        outcome.success();
+        let err = outcome.failure("item 2", anyhow::anyhow!("boom")).unwrap_err();
+        assert_eq!(format!("{:#}", err), "item 2 failed: boom");
+    }
+
+    #[test]
+    fn test_collect_errors_accumulates() {
+        let mut outcome = BulkOutcome::new(ErrorMode::CollectErrors);
+        outcome.success();
+        outcome.failure("a", anyhow::anyhow!("first")).unwrap();
+        outcome.failure("b", anyhow::anyhow!("second")).unwrap();
+        assert!(outcome.has_failures());
+        assert_eq!(outcome.failed.len(), 2);
+        let err = outcome.finish("Import").unwrap_err();
+        assert_eq!(err.to_string(), "Import finished with 2 failure(s)");
+    }
+
+    #[test]
+    fn test_run_ordered_keeps_input_order() {
+        let items: Vec<u64> = (0..20).collect();
+        let mut seen = Vec::new();
+        run_ordered(
+            &items,
+            4,
+            |n| {
+                // Later items finish first
+                std::thread::sleep(std::time::Duration::from_millis(20 - n));
+                n * 10
+            },
+            |n, result| {
+                seen.push((*n, result));
+                Ok(())
+            },
+        )
+        .unwrap();
+        let expected: Vec<(u64, u64)> = items.iter().map(|n| (*n, n * 10)).collect();
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn test_run_ordered_stops_on_error() {
+        let items: Vec<u32> = (0..100).collect();
+        let started = AtomicUsize::new(0);
+        let err = run_ordered(
+            &items,
+            2,
+            |_| {
+                std::thread::sleep(std::time::Duration::from_millis(5));
+                started.fetch_add(1, Ordering::Relaxed)
+            },
+            |n, _| if *n == 3 { anyhow::bail!("stop at {}", n) } else { Ok(()) },
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "stop at 3");
+        assert!(started.load(Ordering::Relaxed) < items.len());
+    }
+
+    #[test]
+    fn test_retry_budget_is_shared_across_items() {
+        let budget = RetryBudget::new(Duration::ZERO);
+        budget.configure(2, 3);
+        let calls = AtomicUsize::new(0);
+        let down = || -> Result<()> {
+            calls.fetch_add(1, Ordering::Relaxed);
+            anyhow::bail!("Communication Error: error sending request")
+        };
+
+        // The first item uses both of its retries, the second the last one left
+        assert!(budget.run(down).unwrap_err().downcast_ref::<RetryBudgetExhausted>().is_none());
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+        let err = budget.run(down).unwrap_err();
+        assert!(err.downcast_ref::<RetryBudgetExhausted>().is_some());
+        assert_eq!(calls.load(Ordering::Relaxed), 5);
+
+        // Other failures are not retried
+        let rejected = || -> Result<()> {
+            calls.fetch_add(1, Ordering::Relaxed);
+            anyhow::bail!("Error Response: status: 400 Bad Request")
+        };
+        budget.configure(2, 3);
+        assert!(budget.run(rejected).is_err());
+        assert_eq!(calls.load(Ordering::Relaxed), 6);
+    }
+
+    #[test]
+    fn test_exhausted_budget_stops_collect_errors() {
+        let mut outcome = BulkOutcome::new(ErrorMode::CollectErrors);
+        let error = anyhow::anyhow!("connection refused").context(RetryBudgetExhausted(20)).context("more context");
+        assert!(outcome.failure("item 1", error).is_err());
+    }
+
+    #[test]
+    fn test_finish_ok_without_failures() {
+        let mut outcome = BulkOutcome::new(ErrorMode::CollectErrors);
+        outcome.success();
+        assert!(outcome.finish("Import").is_ok());
+    }
+}
@@ -0,0 +1,229 @@
+//! On-disk cache for read-only responses (bank list, bank profiles).
+//!
+//! Entries live under `~/.hindsight/cache/<server>-<fingerprint>/`, where the
+//! fingerprint hashes the base URL (tenant included) and the credentials sent with
+//! each request, so switching servers, tenants, API keys or auth headers never
+//! serves another identity's data. Writes to a bank invalidate the server's entries.
+//! Expired entries are kept with their `ETag`, so they can be revalidated with a
+//! conditional request instead of downloaded again.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+
+/// How long cached responses stay fresh
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+const CACHE_DIR_NAME: &str = "cache";
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry<T> {
+    stored_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    value: T,
+}
+
+/// A cached value, fresh or not
+pub struct Cached<T> {
+    pub value: T,
+    pub etag: Option<String>,
+    /// Still within the TTL, so usable without asking the server
+    pub fresh: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+/// Root directory holding the caches for all servers
+pub fn cache_root() -> Option<PathBuf> {
+    Config::config_dir().map(|dir| dir.join(CACHE_DIR_NAME))
+}
+
+/// Remove every cached response; returns the number of files deleted
+pub fn clear_all() -> anyhow::Result<usize> {
+    match cache_root() {
+        Some(root) => remove_dir_counting(&root),
+        None => Ok(0),
+    }
+}
+
+fn remove_dir_counting(dir: &Path) -> anyhow::Result<usize> {
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let mut count = 0;
+    for entry in walkdir::WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+        if entry.file_type().is_file() {
+            count += 1;
+        }
+    }
+    fs::remove_dir_all(dir)?;
+    Ok(count)
+}
+
+/// Turn a base URL into a filesystem-safe directory name
+fn server_key(base_url: &str) -> String {
+    base_url
+        .trim_end_matches('/')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect()
+}
+
+/// Short hash of the base URL and the request identity (API key, extra headers)
+fn fingerprint(base_url: &str, identity: &[&str]) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    hasher.update(base_url.trim_end_matches('/').as_bytes());
+    for part in identity {
+        // Separate the parts so ["ab", "c"] and ["a", "bc"] differ
+        hasher.update([0u8]);
+        hasher.update(part.as_bytes());
+    }
+    hasher.finalize().iter().take(8).map(|b| format!("{:02x}", b)).collect()
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl ResponseCache {
+    /// Cache for requests to `base_url` sent with `identity` (the API key and any
+    /// extra headers), under the default cache root
+    pub fn for_server(base_url: &str, identity: &[&str]) -> Option<Self> {
+        cache_root().map(|root| Self::in_dir(root, base_url, identity, DEFAULT_TTL))
+    }
+
+    pub(crate) fn in_dir(root: PathBuf, base_url: &str, identity: &[&str], ttl: Duration) -> Self {
+        Self {
+            dir: root.join(format!("{}-{}", server_key(base_url), fingerprint(base_url, identity))),
+            ttl,
+        }
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", server_key(key)))
+    }
+
+    /// Fresh cached value for `key`, if any
+    #[cfg(test)]
+    fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.lookup(key).filter(|entry| entry.fresh).map(|entry| entry.value)
+    }
+
+    /// Cached value for `key`, including expired ones
+    pub fn lookup<T: DeserializeOwned>(&self, key: &str) -> Option<Cached<T>> {
+        let content = fs::read_to_string(self.entry_path(key)).ok()?;
+        let entry: CacheEntry<T> = serde_json::from_str(&content).ok()?;
+        Some(Cached {
+            fresh: now_secs().saturating_sub(entry.stored_at) < self.ttl.as_secs(),
+            etag: entry.etag,
+            value: entry.value,
+        })
+    }
+
+    /// Store a value, restarting its TTL; failures are ignored since the cache is only an optimization
+    pub fn put<T: Serialize>(&self, key: &str, value: &T, etag: Option<&str>) {
+        let entry = CacheEntry {
+            stored_at: now_secs(),
+            etag: etag.map(str::to_string),
+            value,
+        };
+        if let Ok(json) = serde_json::to_string(&entry) {
+            let _ = fs::create_dir_all(&self.dir);
+            let _ = fs::write(self.entry_path(key), json);
+        }
+    }
+
+    /// Drop all cached responses for this server
+    pub fn invalidate(&self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hindsight-cache-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_server_key_is_filesystem_safe() {
+        assert_eq!(server_key("http://localhost:8888/"), "http___localhost_8888");
+        assert_ne!(server_key("http://a:8888"), server_key("http://b:8888"));
+    }
+
+    #[test]
+    fn test_put_get_roundtrip() {
+        let root = temp_root("roundtrip");
+        let cache = ResponseCache::in_dir(root.clone(), "http://localhost:8888", &[], DEFAULT_TTL);
+        cache.put("banks", &vec!["a".to_string(), "b".to_string()], None);
+        assert_eq!(cache.get::<Vec<String>>("banks"), Some(vec!["a".to_string(), "b".to_string()]));
+
+        // Another server never sees this entry
+        let other = ResponseCache::in_dir(root.clone(), "http://remote:8888", &[], DEFAULT_TTL);
+        assert_eq!(other.get::<Vec<String>>("banks"), None);
+
+        cache.invalidate();
+        assert_eq!(cache.get::<Vec<String>>("banks"), None);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_identities_do_not_share_entries() {
+        let root = temp_root("identity");
+        let alice = ResponseCache::in_dir(root.clone(), "http://localhost:8888", &["key-a"], DEFAULT_TTL);
+        alice.put("banks", &1, None);
+        assert_eq!(alice.get::<i32>("banks"), Some(1));
+
+        let bob = ResponseCache::in_dir(root.clone(), "http://localhost:8888", &["key-b"], DEFAULT_TTL);
+        assert_eq!(bob.get::<i32>("banks"), None);
+        let tenant = ResponseCache::in_dir(root.clone(), "http://localhost:8888/tenants/acme", &["key-a"], DEFAULT_TTL);
+        assert_eq!(tenant.get::<i32>("banks"), None);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_fingerprint_separates_identity_parts() {
+        assert_ne!(fingerprint("http://h", &["ab", "c"]), fingerprint("http://h", &["a", "bc"]));
+        assert_eq!(fingerprint("http://h/", &["k"]), fingerprint("http://h", &["k"]));
+    }
+
+    #[test]
+    fn test_expired_entries_are_ignored() {
+        let root = temp_root("expired");
+        let cache = ResponseCache::in_dir(root.clone(), "http://localhost:8888", &[], Duration::ZERO);
+        cache.put("banks", &1, None);
+        assert_eq!(cache.get::<i32>("banks"), None);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_expired_entries_keep_their_etag() {
+        let root = temp_root("etag");
+        let cache = ResponseCache::in_dir(root.clone(), "http://localhost:8888", &[], Duration::ZERO);
+        cache.put("profile-b", &1, Some("\"abc\""));
+
+        let entry = cache.lookup::<i32>("profile-b").unwrap();
+        assert!(!entry.fresh);
+        assert_eq!(entry.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(entry.value, 1);
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -3,21 +3,23 @@ use crate::api::ApiClient;
 use crate::output::{self, OutputFormat};
 use crate::ui;
 
-pub fn list(client: &ApiClient, verbose: bool, output_format: OutputFormat) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching banks..."))
+pub fn list(client: &ApiClient, count_only: bool, verbose: bool, output_format: OutputFormat) -> Result<()> {
+    let mut spinner = if !count_only {
+        ui::spinner_guard(output_format, "Fetching banks...")
     } else {
-        None
+        ui::SpinnerGuard::none()
     };
 
     let response = client.list_agents(verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(banks_list) => {
+            if count_only {
+                return output::print_count(banks_list.len() as i64, output_format);
+            }
+
             if output_format == OutputFormat::Pretty {
                 if banks_list.is_empty() {
                     ui::print_warning("No banks found");
@@ -37,17 +39,11 @@ pub fn list(client: &ApiClient, verbose: bool, output_format: OutputFormat) -> R
 }
 
 pub fn disposition(client: &ApiClient, bank_id: &str, verbose: bool, output_format: OutputFormat) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching disposition..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Fetching disposition...");
 
     let response = client.get_profile(bank_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(profile) => {
@@ -63,57 +59,51 @@ pub fn disposition(client: &ApiClient, bank_id: &str, verbose: bool, output_form
 }
 
 pub fn stats(client: &ApiClient, bank_id: &str, verbose: bool, output_format: OutputFormat) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching statistics..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Fetching statistics...");
 
     let response = client.get_stats(bank_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(stats) => {
             if output_format == OutputFormat::Pretty {
                 ui::print_section_header(&format!("Statistics: {}", bank_id));
 
-                println!("  {} {}", ui::dim("memory units:"), ui::gradient_start(&stats.total_nodes.to_string()));
-                println!("  {} {}", ui::dim("links:"), ui::gradient_mid(&stats.total_links.to_string()));
-                println!("  {} {}", ui::dim("documents:"), ui::gradient_end(&stats.total_documents.to_string()));
+                println!("  {} {}", ui::dim("memory units:"), ui::gradient_start(&ui::format_count(stats.total_nodes as i64)));
+                println!("  {} {}", ui::dim("links:"), ui::gradient_mid(&ui::format_count(stats.total_links as i64)));
+                println!("  {} {}", ui::dim("documents:"), ui::gradient_end(&ui::format_count(stats.total_documents as i64)));
                 println!();
 
-                println!("{}", ui::gradient_text("─── Memory Units by Type ───"));
+                println!("{}", ui::gradient_text(&ui::rule("Memory Units by Type")));
                 let mut fact_types: Vec<_> = stats.nodes_by_fact_type.iter().collect();
                 fact_types.sort_by_key(|(k, _)| *k);
                 for (i, (fact_type, count)) in fact_types.iter().enumerate() {
                     let t = i as f32 / fact_types.len().max(1) as f32;
-                    println!("  {:<10} {}", fact_type, ui::gradient(&count.to_string(), t));
+                    println!("  {:<10} {}", fact_type, ui::gradient(&ui::format_count(**count as i64), t));
                 }
                 println!();
 
-                println!("{}", ui::gradient_text("─── Links by Type ───"));
+                println!("{}", ui::gradient_text(&ui::rule("Links by Type")));
                 let mut link_types: Vec<_> = stats.links_by_link_type.iter().collect();
                 link_types.sort_by_key(|(k, _)| *k);
                 for (i, (link_type, count)) in link_types.iter().enumerate() {
                     let t = i as f32 / link_types.len().max(1) as f32;
-                    println!("  {:<10} {}", link_type, ui::gradient(&count.to_string(), t));
+                    println!("  {:<10} {}", link_type, ui::gradient(&ui::format_count(**count as i64), t));
                 }
                 println!();
 
-                println!("{}", ui::gradient_text("─── Links by Fact Type ───"));
+                println!("{}", ui::gradient_text(&ui::rule("Links by Fact Type")));
                 let mut fact_type_links: Vec<_> = stats.links_by_fact_type.iter().collect();
                 fact_type_links.sort_by_key(|(k, _)| *k);
                 for (i, (fact_type, count)) in fact_type_links.iter().enumerate() {
                     let t = i as f32 / fact_type_links.len().max(1) as f32;
-                    println!("  {:<10} {}", fact_type, ui::gradient(&count.to_string(), t));
+                    println!("  {:<10} {}", fact_type, ui::gradient(&ui::format_count(**count as i64), t));
                 }
                 println!();
 
                 if !stats.links_breakdown.is_empty() {
-                    println!("{}", ui::gradient_text("─── Detailed Link Breakdown ───"));
+                    println!("{}", ui::gradient_text(&ui::rule("Detailed Link Breakdown")));
                     let mut fact_types: Vec<_> = stats.links_breakdown.iter().collect();
                     fact_types.sort_by_key(|(k, _)| *k);
                     for (fact_type, link_types) in fact_types {
@@ -121,19 +111,19 @@ pub fn stats(client: &ApiClient, bank_id: &str, verbose: bool, output_format: Ou
                         let mut sorted_links: Vec<_> = link_types.iter().collect();
                         sorted_links.sort_by_key(|(k, _)| *k);
                         for (link_type, count) in sorted_links {
-                            println!("    {:<10} {}", ui::dim(link_type), count);
+                            println!("    {:<10} {}", ui::dim(link_type), ui::format_count(*count as i64));
                         }
                     }
                     println!();
                 }
 
                 if stats.pending_operations > 0 || stats.failed_operations > 0 {
-                    println!("{}", ui::gradient_text("─── Operations ───"));
+                    println!("{}", ui::gradient_text(&ui::rule("Operations")));
                     if stats.pending_operations > 0 {
-                        println!("  {} {}", ui::dim("pending:"), stats.pending_operations);
+                        println!("  {} {}", ui::dim("pending:"), ui::format_count(stats.pending_operations as i64));
                     }
                     if stats.failed_operations > 0 {
-                        println!("  {} {}", ui::dim("failed:"), stats.failed_operations);
+                        println!("  {} {}", ui::dim("failed:"), ui::format_count(stats.failed_operations as i64));
                     }
                 }
             } else {
@@ -146,17 +136,11 @@ pub fn stats(client: &ApiClient, bank_id: &str, verbose: bool, output_format: Ou
 }
 
 pub fn update_name(client: &ApiClient, bank_id: &str, name: &str, verbose: bool, output_format: OutputFormat) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Updating bank name..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Updating bank name...");
 
     let response = client.update_agent_name(bank_id, name, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(profile) => {
@@ -185,17 +169,11 @@ pub fn update_background(
         None
     };
 
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Merging background..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Merging background...");
 
     let response = client.add_background(bank_id, content, !no_update_disposition, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(profile) => {
@@ -208,9 +186,9 @@ pub fn update_background(
                         (current_profile.as_ref().map(|p| p.disposition.clone()), &profile.disposition)
                     {
                         println!("\nDisposition changes:");
-                        println!("  Skepticism:  {} → {}", old_p.skepticism, new_p.skepticism);
-                        println!("  Literalism:  {} → {}", old_p.literalism, new_p.literalism);
-                        println!("  Empathy:     {} → {}", old_p.empathy, new_p.empathy);
+                        println!("  Skepticism:  {} {} {}", old_p.skepticism, ui::glyph(ui::Glyph::Arrow), new_p.skepticism);
+                        println!("  Literalism:  {} {} {}", old_p.literalism, ui::glyph(ui::Glyph::Arrow), new_p.literalism);
+                        println!("  Empathy:     {} {} {}", old_p.empathy, ui::glyph(ui::Glyph::Arrow), new_p.empathy);
                     }
                 }
             } else {
@@ -230,17 +208,11 @@ pub fn mission(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Setting mission..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Setting mission...");
 
     let response = client.set_mission(bank_id, mission_text, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(profile) => {
@@ -257,36 +229,172 @@ pub fn mission(
     }
 }
 
-/// Create a new bank
+/// Starting disposition for a new bank; explicit trait flags override single traits
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum DispositionPreset {
+    /// Skepticism 3, literalism 3, empathy 3
+    Balanced,
+    /// Skepticism 5, literalism 4, empathy 2
+    Skeptical,
+    /// Skepticism 3, literalism 5, empathy 2
+    Literal,
+    /// Skepticism 2, literalism 2, empathy 5
+    Empathetic,
+}
+
+impl DispositionPreset {
+    /// (skepticism, literalism, empathy)
+    fn traits(self) -> (i64, i64, i64) {
+        match self {
+            DispositionPreset::Balanced => (3, 3, 3),
+            DispositionPreset::Skeptical => (5, 4, 2),
+            DispositionPreset::Literal => (3, 5, 2),
+            DispositionPreset::Empathetic => (2, 2, 5),
+        }
+    }
+}
+
+/// A trait `bank patch-disposition --set` can change
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DispositionTrait {
+    Skepticism,
+    Literalism,
+    Empathy,
+}
+
+impl DispositionTrait {
+    const ALL: [(&'static str, DispositionTrait); 3] = [
+        ("skepticism", DispositionTrait::Skepticism),
+        ("literalism", DispositionTrait::Literalism),
+        ("empathy", DispositionTrait::Empathy),
+    ];
+
+    fn name(self) -> &'static str {
+        Self::ALL.iter().find(|(_, t)| *t == self).map(|(name, _)| *name).unwrap_or_default()
+    }
+
+    fn slot(self, traits: &mut hindsight_client::types::DispositionTraits) -> &mut std::num::NonZeroU64 {
+        match self {
+            DispositionTrait::Skepticism => &mut traits.skepticism,
+            DispositionTrait::Literalism => &mut traits.literalism,
+            DispositionTrait::Empathy => &mut traits.empathy,
+        }
+    }
+}
+
+/// Parse a `--set TRAIT=VALUE` argument: a known trait and a value from 1 to 5
+pub fn parse_trait_setting(raw: &str) -> Result<(DispositionTrait, u64), String> {
+    let (key, value) = raw
+        .split_once('=')
+        .ok_or_else(|| format!("expected TRAIT=VALUE, got '{}'", raw))?;
+    let key = key.trim().to_lowercase();
+    let known: Vec<&str> = DispositionTrait::ALL.iter().map(|(name, _)| *name).collect();
+    let (_, disposition_trait) = DispositionTrait::ALL
+        .into_iter()
+        .find(|(name, _)| *name == key)
+        .ok_or_else(|| format!("unknown trait '{}' (expected one of: {})", key, known.join(", ")))?;
+    let value = value
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|v| (1..=5).contains(v))
+        .ok_or_else(|| format!("{} must be a whole number from 1 to 5, got '{}'", key, value.trim()))?;
+    Ok((disposition_trait, value))
+}
+
+/// Overwrite the chosen traits, returning (trait, old, new) for each one set.
+/// A trait set twice keeps the last value.
+fn apply_trait_settings(
+    traits: &mut hindsight_client::types::DispositionTraits,
+    settings: &[(DispositionTrait, u64)],
+) -> Vec<(DispositionTrait, u64, u64)> {
+    let mut changes: Vec<(DispositionTrait, u64, u64)> = Vec::new();
+    for &(disposition_trait, value) in settings {
+        let slot = disposition_trait.slot(traits);
+        let old = slot.get();
+        *slot = std::num::NonZeroU64::new(value).expect("parse_trait_setting only accepts 1-5");
+        match changes.iter_mut().find(|(t, _, _)| *t == disposition_trait) {
+            Some(change) => change.2 = value,
+            None => changes.push((disposition_trait, old, value)),
+        }
+    }
+    changes
+}
+
+/// Change only the given disposition traits: read the stored profile, merge, and
+/// write the full set back
+pub fn patch_disposition(
+    client: &ApiClient,
+    bank_id: &str,
+    settings: &[(DispositionTrait, u64)],
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let mut spinner = ui::spinner_guard(output_format, "Updating disposition...");
+
+    let response = client.get_profile_uncached(bank_id, verbose).and_then(|profile| {
+        let mut disposition = profile.disposition;
+        let changes = apply_trait_settings(&mut disposition, settings);
+        let profile = client.update_disposition(bank_id, disposition, verbose)?;
+        Ok((profile, changes))
+    });
+
+    spinner.finish();
+
+    let (profile, changes) = response?;
+    if output_format == OutputFormat::Pretty {
+        ui::print_success(&format!("Disposition of bank '{}' updated", bank_id));
+        for (disposition_trait, old, new) in changes {
+            println!("  {} {} -> {}", ui::dim(&format!("{}:", disposition_trait.name())), old, new);
+        }
+        println!();
+        ui::print_disposition(&profile);
+    } else {
+        output::print_output(&profile, output_format)?;
+    }
+    Ok(())
+}
+
+/// Merge a preset with explicit trait values; None when nothing was chosen
+fn resolve_disposition(
+    preset: Option<DispositionPreset>,
+    skepticism: Option<i64>,
+    literalism: Option<i64>,
+    empathy: Option<i64>,
+) -> Option<(i64, i64, i64)> {
+    if preset.is_none() && skepticism.is_none() && literalism.is_none() && empathy.is_none() {
+        return None;
+    }
+    let (s, l, e) = preset.unwrap_or(DispositionPreset::Balanced).traits();
+    Some((skepticism.unwrap_or(s), literalism.unwrap_or(l), empathy.unwrap_or(e)))
+}
+
+/// Create a new bank. A background is merged in after creation (the inline
+/// field is deprecated), then the resulting profile is reported.
 pub fn create(
     client: &ApiClient,
     bank_id: &str,
     name: Option<String>,
     mission_text: Option<String>,
+    preset: Option<DispositionPreset>,
     skepticism: Option<i64>,
     literalism: Option<i64>,
     empathy: Option<i64>,
+    background: Option<String>,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Creating bank..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Creating bank...");
 
     use hindsight_client::types;
     use std::num::NonZeroU64;
 
-    let disposition = if skepticism.is_some() || literalism.is_some() || empathy.is_some() {
-        Some(types::DispositionTraits {
-            skepticism: NonZeroU64::new(skepticism.unwrap_or(3) as u64).unwrap(),
-            literalism: NonZeroU64::new(literalism.unwrap_or(3) as u64).unwrap(),
-            empathy: NonZeroU64::new(empathy.unwrap_or(3) as u64).unwrap(),
-        })
-    } else {
-        None
-    };
+    let traits = resolve_disposition(preset, skepticism, literalism, empathy);
+    let disposition = traits.map(|(skepticism, literalism, empathy)| types::DispositionTraits {
+        skepticism: NonZeroU64::new(skepticism as u64).unwrap(),
+        literalism: NonZeroU64::new(literalism as u64).unwrap(),
+        empathy: NonZeroU64::new(empathy as u64).unwrap(),
+    });
 
     let request = types::CreateBankRequest {
         name,
@@ -295,12 +403,19 @@ pub fn create(
         disposition,
     };
 
-    let response = client.create_bank(bank_id, &request, verbose);
+    let mut response = client.create_bank(bank_id, &request, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
+    if let (Ok(_), Some(background)) = (&response, &background) {
+        // Keep a chosen disposition; otherwise let the background shape it
+        let infer_disposition = traits.is_none();
+        response = client
+            .add_background(bank_id, background, infer_disposition, verbose)
+            .and_then(|_| client.get_profile(bank_id, verbose))
+            .map_err(|e| e.context(format!("Bank '{}' was created, but adding the background failed", bank_id)));
     }
 
+    spinner.finish();
+
     match response {
         Ok(profile) => {
             if output_format == OutputFormat::Pretty {
@@ -332,11 +447,7 @@ pub fn update(
         anyhow::bail!("At least one field must be provided (--name, --mission, --skepticism, --literalism, --empathy)");
     }
 
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Updating bank..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Updating bank...");
 
     use hindsight_client::types;
     use std::num::NonZeroU64;
@@ -360,9 +471,7 @@ pub fn update(
 
     let response = client.update_bank(bank_id, &request, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(profile) => {
@@ -388,17 +497,11 @@ pub fn graph(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching graph data..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Fetching graph data...");
 
     let response = client.get_graph(bank_id, type_filter.as_deref(), Some(limit), verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(result) => {
@@ -411,7 +514,7 @@ pub fn graph(
 
                 // Show sample of nodes
                 if !result.nodes.is_empty() {
-                    println!("{}", ui::gradient_text("─── Sample Nodes ───"));
+                    println!("{}", ui::gradient_text(&ui::rule("Sample Nodes")));
                     for node in result.nodes.iter().take(5) {
                         let fact_type = node.get("type")
                             .and_then(|v| v.as_str())
@@ -449,14 +552,18 @@ pub fn delete(
     verbose: bool,
     output_format: OutputFormat
 ) -> Result<()> {
-    // Confirmation prompt unless -y flag is used
+    // Confirmation prompt unless -y flag is used; the bank id must be typed back
     if !yes && output_format == OutputFormat::Pretty {
+        let scale = match client.get_stats(bank_id, verbose) {
+            Ok(stats) => format!(" ({} memories, {} documents)", ui::format_count(stats.total_nodes as i64), ui::format_count(stats.total_documents as i64)),
+            Err(_) => String::new(),
+        };
         let message = format!(
-            "Are you sure you want to delete bank '{}' and ALL its data? This cannot be undone.",
-            bank_id
+            "This deletes bank '{}'{} and ALL its data. This cannot be undone.",
+            bank_id, scale
         );
 
-        let confirmed = ui::prompt_confirmation(&message)?;
+        let confirmed = ui::prompt_typed_confirmation(&message, bank_id)?;
 
         if !confirmed {
             ui::print_info("Operation cancelled");
@@ -464,17 +571,11 @@ pub fn delete(
         }
     }
 
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Deleting bank..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Deleting bank...");
 
     let response = client.delete_bank(bank_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(result) => {
@@ -501,21 +602,15 @@ pub fn consolidate(
     client: &ApiClient,
     bank_id: &str,
     wait: bool,
-    poll_interval: u64,
+    poll_interval: std::time::Duration,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Triggering consolidation..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Triggering consolidation...");
 
     let response = client.trigger_consolidation(bank_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(result) => {
@@ -527,7 +622,7 @@ pub fn consolidate(
                 if result.deduplicated {
                     println!("  {} {}", ui::dim("Note:"), "Reusing existing pending consolidation task");
                 }
-            } else {
+            } else if !wait {
                 output::print_output(&result, output_format)?;
             }
 
@@ -539,15 +634,14 @@ pub fn consolidate(
                 return Ok(());
             }
 
-            // Poll for completion
-            if output_format == OutputFormat::Pretty {
-                println!();
-                println!("{}", ui::dim(&format!("Polling every {}s for completion...", poll_interval)));
-            }
+            // Progress goes to stderr in every format. With --wait, stdout carries the
+            // same trigger response as without it, printed once the operation is done
+            eprintln!();
+            eprintln!("{}", ui::dim(&format!("Polling every {}s for completion...", poll_interval.as_secs())));
 
             let start = std::time::Instant::now();
             loop {
-                std::thread::sleep(std::time::Duration::from_secs(poll_interval));
+                std::thread::sleep(poll_interval);
                 let elapsed = start.elapsed().as_secs();
 
                 let ops_result = client.list_operations(bank_id, verbose);
@@ -560,6 +654,8 @@ pub fn consolidate(
                             Some("completed") => {
                                 if output_format == OutputFormat::Pretty {
                                     ui::print_success(&format!("Consolidation completed ({}s)", elapsed));
+                                } else {
+                                    output::print_output(&result, output_format)?;
                                 }
                                 break;
                             }
@@ -568,19 +664,19 @@ pub fn consolidate(
                                     .and_then(|o| o.error_message.as_ref())
                                     .map(|s| s.as_str())
                                     .unwrap_or("Unknown error");
-                                if output_format == OutputFormat::Pretty {
-                                    ui::print_error(&format!("Consolidation failed: {}", error_msg));
-                                }
-                                std::process::exit(1);
+                                anyhow::bail!("Consolidation failed: {}", error_msg);
                             }
                             Some(status) => {
-                                if output_format == OutputFormat::Pretty {
-                                    println!("  ⏳ {} ({}s elapsed)", status, elapsed);
+                                eprintln!("  {} {} ({}s elapsed)", ui::glyph(ui::Glyph::Pending), status, elapsed);
+                                if start.elapsed() >= client.wait_timeout() {
+                                    return Err(client.wait_timed_out(bank_id, &operation_id, status, start.elapsed()));
                                 }
                             }
                             None => {
-                                if output_format == OutputFormat::Pretty {
-                                    ui::print_warning(&format!("Operation {} not found in list", operation_id));
+                                // Finished operations can drop out of the list
+                                ui::print_warning(&format!("Operation {} not found in list", operation_id));
+                                if output_format != OutputFormat::Pretty {
+                                    output::print_output(&result, output_format)?;
                                 }
                                 break;
                             }
@@ -624,17 +720,11 @@ pub fn clear_observations(
         }
     }
 
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Clearing observations..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Clearing observations...");
 
     let response = client.clear_observations(bank_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(result) => {
@@ -655,3 +745,38 @@ pub fn clear_observations(
         Err(e) => Err(e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_trait_setting() {
+        assert_eq!(parse_trait_setting("empathy=4"), Ok((DispositionTrait::Empathy, 4)));
+        assert_eq!(parse_trait_setting(" Skepticism = 1 "), Ok((DispositionTrait::Skepticism, 1)));
+        assert!(parse_trait_setting("openness=3").unwrap_err().contains("unknown trait 'openness'"));
+        assert!(parse_trait_setting("literalism=6").unwrap_err().contains("from 1 to 5"));
+        assert!(parse_trait_setting("literalism").unwrap_err().contains("TRAIT=VALUE"));
+    }
+
+    #[test]
+    fn test_apply_trait_settings_keeps_other_traits() {
+        let mut traits: hindsight_client::types::DispositionTraits =
+            serde_json::from_value(serde_json::json!({ "skepticism": 2, "literalism": 4, "empathy": 5 })).unwrap();
+        let changes = apply_trait_settings(
+            &mut traits,
+            &[(DispositionTrait::Empathy, 1), (DispositionTrait::Empathy, 3)],
+        );
+        assert_eq!(changes, vec![(DispositionTrait::Empathy, 5, 3)]);
+        assert_eq!((traits.skepticism.get(), traits.literalism.get(), traits.empathy.get()), (2, 4, 3));
+    }
+
+    #[test]
+    fn test_resolve_disposition() {
+        assert_eq!(resolve_disposition(None, None, None, None), None);
+        assert_eq!(resolve_disposition(Some(DispositionPreset::Skeptical), None, None, None), Some((5, 4, 2)));
+        // Explicit traits override the preset, and fill from balanced without one
+        assert_eq!(resolve_disposition(Some(DispositionPreset::Empathetic), Some(4), None, None), Some((4, 2, 5)));
+        assert_eq!(resolve_disposition(None, None, None, Some(1)), Some((3, 3, 1)));
+    }
+}
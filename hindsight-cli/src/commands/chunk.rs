@@ -13,17 +13,11 @@ pub fn get(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching chunk..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Fetching chunk...");
 
     let response = client.get_chunk(chunk_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(result) => {
@@ -34,10 +28,10 @@ pub fn get(
                 println!("  {} {}", ui::dim("Index:"), result.chunk_index);
                 println!("  {} {}", ui::dim("Document:"), result.document_id);
                 println!("  {} {}", ui::dim("Bank:"), result.bank_id);
-                println!("  {} {}", ui::dim("Created:"), result.created_at);
+                println!("  {} {}", ui::dim("Created:"), ui::format_timestamp(&result.created_at));
 
                 println!();
-                println!("{}", ui::gradient_text("─── Content ───"));
+                println!("{}", ui::gradient_text(&ui::rule("Content")));
                 println!();
                 println!("{}", result.chunk_text);
 
@@ -1,34 +1,79 @@
 //! Directive commands for managing behavioral rules.
 
 use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
 
 use crate::api::ApiClient;
+use crate::bulk::{run_ordered, with_retries, BulkOutcome, ErrorMode};
 use crate::output::{self, OutputFormat};
 use crate::ui;
 
 use hindsight_client::types;
 
+/// Ordering for `directive list`
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum DirectiveSort {
+    /// Highest priority first
+    Priority,
+    /// Alphabetical by name
+    Name,
+    /// Newest first
+    Created,
+}
+
+fn sort_directives(directives: &mut [types::DirectiveResponse], sort: DirectiveSort) {
+    match sort {
+        DirectiveSort::Priority => {
+            directives.sort_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.name.cmp(&b.name)))
+        }
+        DirectiveSort::Name => directives.sort_by_key(|d| d.name.to_lowercase()),
+        // RFC 3339 timestamps sort chronologically as strings; missing ones go last
+        DirectiveSort::Created => directives.sort_by_key(|d| std::cmp::Reverse(d.created_at.clone())),
+    }
+}
+
+/// Color a priority by severity band so important rules stand out
+fn format_priority(priority: i64) -> String {
+    let text = priority.to_string();
+    if priority >= 10 {
+        ui::gradient_text(&text)
+    } else if priority > 0 {
+        ui::gradient_mid(&text)
+    } else {
+        ui::dim(&text)
+    }
+}
+
 /// List directives for a bank
 pub fn list(
     client: &ApiClient,
     bank_id: &str,
+    sort: DirectiveSort,
+    count_only: bool,
+    page: Option<output::PageRequest>,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching directives..."))
+    let mut spinner = if !count_only {
+        ui::spinner_guard(output_format, "Fetching directives...")
     } else {
-        None
+        ui::SpinnerGuard::none()
     };
 
     let response = client.list_directives(bank_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
-        Ok(result) => {
+        Ok(mut result) => {
+            if count_only {
+                return output::print_count(result.items.len() as i64, output_format);
+            }
+
+            sort_directives(&mut result.items, sort);
+            let page_info = page.map(|p| output::paginate(&mut result.items, p)).transpose()?;
+
             if output_format == OutputFormat::Pretty {
                 ui::print_section_header(&format!("Directives: {}", bank_id));
 
@@ -42,10 +87,12 @@ pub fn list(
                             ui::dim("inactive")
                         };
                         println!(
-                            "  {} {} [{}]",
+                            "  {} {} [{}] {} {}",
                             ui::gradient_start(&directive.id),
                             directive.name,
-                            status
+                            status,
+                            ui::dim("priority"),
+                            format_priority(directive.priority)
                         );
 
                         // Show content preview
@@ -56,6 +103,9 @@ pub fn list(
                         println!();
                     }
                 }
+                if let Some(info) = page_info {
+                    output::print_page_footer(&info);
+                }
             } else {
                 output::print_output(&result, output_format)?;
             }
@@ -73,22 +123,18 @@ pub fn get(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching directive..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Fetching directive...");
 
     let response = client.get_directive(bank_id, directive_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(directive) => {
             if output_format == OutputFormat::Pretty {
                 print_directive_detail(&directive);
+            } else if output_format == OutputFormat::Raw {
+                output::print_raw(&directive.content);
             } else {
                 output::print_output(&directive, output_format)?;
             }
@@ -98,21 +144,75 @@ pub fn get(
     }
 }
 
+/// Expand `\n`, `\t` and `\\` in a `--separator` typed on the command line
+fn unescape_separator(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('\\') => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Active directives in the order they should appear in a prompt
+fn active_by_priority(directives: Vec<types::DirectiveResponse>) -> Vec<types::DirectiveResponse> {
+    let mut active: Vec<_> = directives.into_iter().filter(|d| d.is_active).collect();
+    sort_directives(&mut active, DirectiveSort::Priority);
+    active
+}
+
+/// Print the active directives' content as one block, highest priority first
+pub fn render(
+    client: &ApiClient,
+    bank_id: &str,
+    separator: &str,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let mut spinner = ui::spinner_guard(output_format, "Fetching directives...");
+
+    let response = client.list_directives(bank_id, verbose);
+
+    spinner.finish();
+
+    let directives = active_by_priority(response?.items);
+    match output_format {
+        OutputFormat::Pretty | OutputFormat::Raw => {
+            if directives.is_empty() {
+                ui::print_warning(&format!("No active directives in bank '{}'", bank_id));
+                return Ok(());
+            }
+            let contents: Vec<&str> = directives.iter().map(|d| d.content.as_str()).collect();
+            output::print_raw(&contents.join(&unescape_separator(separator)));
+        }
+        _ => output::print_output(&directives, output_format)?,
+    }
+    Ok(())
+}
+
 /// Create a new directive
 pub fn create(
     client: &ApiClient,
     bank_id: &str,
     name: &str,
     content: &str,
+    dry_run: bool,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Creating directive..."))
-    } else {
-        None
-    };
-
     let request = types::CreateDirectiveRequest {
         name: name.to_string(),
         content: content.to_string(),
@@ -121,11 +221,16 @@ pub fn create(
         tags: vec![],
     };
 
+    if dry_run {
+        output::print_dry_run("POST", &client.url(&["v1", "default", "banks", bank_id, "directives"])?, &request);
+        return Ok(());
+    }
+
+    let mut spinner = ui::spinner_guard(output_format, "Creating directive...");
+
     let response = client.create_directive(bank_id, &request, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(directive) => {
@@ -149,6 +254,7 @@ pub fn update(
     directive_id: &str,
     name: Option<String>,
     content: Option<String>,
+    dry_run: bool,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
@@ -156,12 +262,6 @@ pub fn update(
         anyhow::bail!("At least one of --name or --content must be provided");
     }
 
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Updating directive..."))
-    } else {
-        None
-    };
-
     let request = types::UpdateDirectiveRequest {
         name,
         content,
@@ -170,11 +270,17 @@ pub fn update(
         tags: None,
     };
 
+    if dry_run {
+        let url = client.url(&["v1", "default", "banks", bank_id, "directives", directive_id])?;
+        output::print_dry_run("PATCH", &url, &request);
+        return Ok(());
+    }
+
+    let mut spinner = ui::spinner_guard(output_format, "Updating directive...");
+
     let response = client.update_directive(bank_id, directive_id, &request, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(directive) => {
@@ -215,24 +321,18 @@ pub fn delete(
         }
     }
 
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Deleting directive..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Deleting directive...");
 
     let response = client.delete_directive(bank_id, directive_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
-        Ok(_) => {
+        Ok(delete_response) => {
             if output_format == OutputFormat::Pretty {
                 ui::print_success(&format!("Directive '{}' deleted successfully", directive_id));
             } else {
-                println!("{{\"success\": true}}");
+                output::print_output(&delete_response, output_format)?;
             }
             Ok(())
         }
@@ -240,6 +340,104 @@ pub fn delete(
     }
 }
 
+/// Per-directive outcome reported by `set_active`
+#[derive(Debug, Serialize)]
+struct ToggleOutcome {
+    directive_id: String,
+    name: String,
+    is_active: bool,
+    error: Option<String>,
+    /// The request error, kept so an exhausted retry budget still stops the command
+    #[serde(skip)]
+    failure: Option<anyhow::Error>,
+}
+
+/// Directives that `enable`/`disable` must update: those matching any of `tags`
+/// (every directive when empty) that aren't already in the wanted state
+fn toggle_targets(directives: Vec<types::DirectiveResponse>, tags: &[String], active: bool) -> (Vec<types::DirectiveResponse>, usize) {
+    let (already, targets): (Vec<_>, Vec<_>) = directives
+        .into_iter()
+        .filter(|d| tags.is_empty() || d.tags.iter().any(|tag| tags.contains(tag)))
+        .partition(|d| d.is_active == active);
+    (targets, already.len())
+}
+
+/// Activate or deactivate every directive, or those with one of `tags`,
+/// continuing past failed updates and summarizing them at the end
+pub fn set_active(
+    client: &ApiClient,
+    bank_id: &str,
+    tags: &[String],
+    active: bool,
+    concurrency: usize,
+    error_mode: ErrorMode,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let (verb, state) = if active { ("Enabled", "active") } else { ("Disabled", "inactive") };
+
+    let mut spinner = ui::spinner_guard(output_format, "Fetching directives...");
+
+    let response = client.list_directives(bank_id, verbose);
+
+    spinner.finish();
+
+    let (targets, unchanged) = toggle_targets(response?.items, tags, active);
+
+    let mut bulk = BulkOutcome::new(error_mode);
+    let mut outcomes = Vec::with_capacity(targets.len());
+    let toggle = |directive: &types::DirectiveResponse| {
+        let request = types::UpdateDirectiveRequest {
+            name: None,
+            content: None,
+            is_active: Some(active),
+            priority: None,
+            tags: None,
+        };
+        let result = with_retries(|| client.update_directive(bank_id, &directive.id, &request, verbose));
+        ToggleOutcome {
+            directive_id: directive.id.clone(),
+            name: directive.name.clone(),
+            is_active: result.as_ref().map_or(directive.is_active, |updated| updated.is_active),
+            error: result.as_ref().err().map(|e| e.to_string()),
+            failure: result.err(),
+        }
+    };
+    run_ordered(&targets, concurrency, toggle, |_, mut outcome| {
+        if output_format == OutputFormat::Pretty {
+            match &outcome.error {
+                None => println!("  {} {}", ui::gradient_start(&verb.to_lowercase()), outcome.name),
+                Some(error) => println!("  {} {} {}", ui::gradient_end("failed"), outcome.name, ui::dim(error)),
+            }
+        }
+
+        match outcome.failure.take() {
+            None => bulk.success(),
+            Some(error) => bulk.failure(format!("Directive '{}'", outcome.name), error)?,
+        }
+        outcomes.push(outcome);
+        Ok(())
+    })?;
+
+    if output_format == OutputFormat::Pretty {
+        if !outcomes.is_empty() {
+            println!();
+        }
+        let summary = format!("{} {} directive(s), {} already {}", verb, bulk.succeeded, unchanged, state);
+        if bulk.has_failures() {
+            ui::print_warning(&format!("{}, {} failed", summary, bulk.failed.len()));
+        } else {
+            ui::print_success(&summary);
+        }
+    } else {
+        output::print_output(&outcomes, output_format)?;
+    }
+
+    let action = if active { "Enable" } else { "Disable" };
+    bulk.print_summary(action);
+    bulk.finish(action)
+}
+
 // Helper function to print directive details
 fn print_directive_detail(directive: &types::DirectiveResponse) {
     ui::print_section_header(&directive.name);
@@ -252,15 +450,85 @@ fn print_directive_detail(directive: &types::DirectiveResponse) {
         ui::dim("inactive")
     };
     println!("  {} {}", ui::dim("Status:"), status);
-    println!("  {} {}", ui::dim("Priority:"), directive.priority);
+    println!("  {} {}", ui::dim("Priority:"), format_priority(directive.priority));
 
     if !directive.tags.is_empty() {
         println!("  {} {}", ui::dim("Tags:"), directive.tags.join(", "));
     }
 
     println!();
-    println!("{}", ui::gradient_text("─── Content ───"));
+    println!("{}", ui::gradient_text(&ui::rule("Content")));
     println!();
     println!("{}", &directive.content);
     println!();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn directive(name: &str, priority: i64, created_at: Option<&str>) -> types::DirectiveResponse {
+        types::DirectiveResponse {
+            bank_id: "bank".to_string(),
+            content: String::new(),
+            created_at: created_at.map(str::to_string),
+            id: name.to_string(),
+            is_active: true,
+            name: name.to_string(),
+            priority,
+            tags: Vec::new(),
+            updated_at: None,
+        }
+    }
+
+    fn names(directives: &[types::DirectiveResponse]) -> Vec<&str> {
+        directives.iter().map(|d| d.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_unescape_separator() {
+        assert_eq!(unescape_separator("\\n---\\n"), "\n---\n");
+        assert_eq!(unescape_separator("a\\tb\\\\n\\x\\"), "a\tb\\n\\x\\");
+    }
+
+    #[test]
+    fn test_active_by_priority() {
+        let mut off = directive("off", 9, None);
+        off.is_active = false;
+        let directives = vec![directive("low", 1, None), off, directive("high", 5, None)];
+        assert_eq!(names(&active_by_priority(directives)), vec!["high", "low"]);
+    }
+
+    #[test]
+    fn test_toggle_targets() {
+        let mut tagged = directive("tagged", 0, None);
+        tagged.tags = vec!["exp".to_string()];
+        let mut inactive = directive("inactive", 0, None);
+        inactive.is_active = false;
+        let directives = vec![tagged, inactive, directive("plain", 0, None)];
+
+        let (targets, unchanged) = toggle_targets(directives.clone(), &[], false);
+        assert_eq!((names(&targets), unchanged), (vec!["tagged", "plain"], 1));
+
+        let (targets, unchanged) = toggle_targets(directives, &["exp".to_string()], false);
+        assert_eq!((names(&targets), unchanged), (vec!["tagged"], 0));
+    }
+
+    #[test]
+    fn test_sort_directives() {
+        let mut directives = vec![
+            directive("beta", 1, Some("2024-01-02T00:00:00Z")),
+            directive("alpha", 5, Some("2024-01-01T00:00:00Z")),
+            directive("gamma", 5, None),
+        ];
+
+        sort_directives(&mut directives, DirectiveSort::Priority);
+        assert_eq!(names(&directives), ["alpha", "gamma", "beta"]);
+
+        sort_directives(&mut directives, DirectiveSort::Name);
+        assert_eq!(names(&directives), ["alpha", "beta", "gamma"]);
+
+        sort_directives(&mut directives, DirectiveSort::Created);
+        assert_eq!(names(&directives), ["beta", "alpha", "gamma"]);
+    }
+}
@@ -1,8 +1,13 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{Duration as ChronoDuration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use crate::api::ApiClient;
-use crate::output::{self, OutputFormat};
+use crate::bulk::{run_ordered, with_retries, BulkOutcome, ErrorMode};
+use crate::errors;
+use crate::output::{self, csv_field, OutputFormat};
+use crate::pager;
 use crate::ui;
 
 pub fn list(
@@ -12,50 +17,113 @@ pub fn list(
     date: Option<String>,
     limit: i32,
     offset: i32,
+    all: bool,
+    template: Option<String>,
+    count_only: bool,
+    fail_if_empty: bool,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    // If date filter is provided, use the date-aware listing
-    if date.is_some() {
-        return list_with_date(client, agent_id, date.as_deref(), verbose, output_format);
-    }
-
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching documents..."))
+    let listed = if date.is_some() {
+        // If date filter is provided, use the date-aware listing
+        list_with_date(client, agent_id, date.as_deref(), template, count_only, verbose, output_format)?
+    } else if all {
+        list_all(client, agent_id, query.as_deref(), template, verbose, output_format)?
     } else {
-        None
+        let mut spinner = if template.is_none() && !count_only {
+            ui::spinner_guard(output_format, "Fetching documents...")
+        } else {
+            ui::SpinnerGuard::none()
+        };
+
+        let response = client.list_documents(agent_id, query.as_deref(), Some(limit), Some(offset), verbose);
+
+        spinner.finish();
+
+        let docs_response = response?;
+        if count_only {
+            output::print_count(docs_response.total, output_format)?;
+        } else if let Some(template) = &template {
+            output::print_template(template, &docs_response.items)?;
+        } else if output_format == OutputFormat::Pretty {
+            ui::print_info(&format!("Documents for bank '{}' (total: {})", agent_id, docs_response.total));
+            for doc in &docs_response.items {
+                print_document_summary(doc);
+            }
+        } else {
+            output::print_output(&docs_response, output_format)?;
+        }
+        usize::try_from(docs_response.total).unwrap_or(0)
     };
 
-    let response = client.list_documents(agent_id, query.as_deref(), Some(limit), Some(offset), verbose);
+    if fail_if_empty && listed == 0 {
+        errors::report_empty_results();
+    }
+    Ok(())
+}
+
+fn print_document_summary(doc: &serde_json::Map<String, serde_json::Value>) {
+    let id = doc.get("id").and_then(|v| v.as_str()).unwrap_or("unknown");
+    let created = doc.get("created_at").and_then(|v| v.as_str()).unwrap_or("unknown");
+    let updated = doc.get("updated_at").and_then(|v| v.as_str()).unwrap_or("unknown");
+    let text_len = doc.get("text_length").and_then(|v| v.as_i64()).unwrap_or(0);
+    let mem_count = doc.get("memory_unit_count").and_then(|v| v.as_i64()).unwrap_or(0);
+
+    println!("\n  Document ID: {}", id);
+    println!("    Created: {}", ui::format_timestamp(created));
+    println!("    Updated: {}", ui::format_timestamp(updated));
+    println!("    Text Length: {}", text_len);
+    println!("    Memory Units: {}", mem_count);
+}
+
+/// Page size used when listing every document
+const ALL_DOCUMENTS_PAGE_SIZE: i32 = 500;
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
+/// List every document, printing each page as soon as it arrives
+fn list_all(
+    client: &ApiClient,
+    bank_id: &str,
+    query: Option<&str>,
+    template: Option<String>,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<usize> {
+    let pages = client.iter_documents(bank_id, query, ALL_DOCUMENTS_PAGE_SIZE, verbose);
+    let mut shown = 0;
+
+    if let Some(template) = &template {
+        for page in pages {
+            let page = page?;
+            shown += page.len();
+            output::print_template(template, &page)?;
+        }
+        return Ok(shown);
     }
 
-    match response {
-        Ok(docs_response) => {
-            if output_format == OutputFormat::Pretty {
-                ui::print_info(&format!("Documents for bank '{}' (total: {})", agent_id, docs_response.total));
-                for doc in &docs_response.items {
-                    let id = doc.get("id").and_then(|v| v.as_str()).unwrap_or("unknown");
-                    let created = doc.get("created_at").and_then(|v| v.as_str()).unwrap_or("unknown");
-                    let updated = doc.get("updated_at").and_then(|v| v.as_str()).unwrap_or("unknown");
-                    let text_len = doc.get("text_length").and_then(|v| v.as_i64()).unwrap_or(0);
-                    let mem_count = doc.get("memory_unit_count").and_then(|v| v.as_i64()).unwrap_or(0);
-
-                    println!("\n  Document ID: {}", id);
-                    println!("    Created: {}", created);
-                    println!("    Updated: {}", updated);
-                    println!("    Text Length: {}", text_len);
-                    println!("    Memory Units: {}", mem_count);
-                }
-            } else {
-                output::print_output(&docs_response, output_format)?;
+    if output_format == OutputFormat::Pretty {
+        ui::print_info(&format!("Documents for bank '{}'", bank_id));
+        for page in pages {
+            let page = page?;
+            shown += page.len();
+            for doc in &page {
+                print_document_summary(doc);
             }
-            Ok(())
         }
-        Err(e) => Err(e)
+        println!();
+        println!("  {} {}", ui::dim("Total:"), shown);
+        return Ok(shown);
     }
+
+    let mut stream = output::ListStream::new(std::io::stdout().lock(), output_format);
+    for page in pages {
+        let page = page?;
+        shown += page.len();
+        for doc in &page {
+            stream.item(doc)?;
+        }
+    }
+    stream.finish()?;
+    Ok(shown)
 }
 
 /// List documents with date filtering
@@ -63,36 +131,39 @@ fn list_with_date(
     client: &ApiClient,
     bank_id: &str,
     date_filter: Option<&str>,
+    template: Option<String>,
+    count_only: bool,
     verbose: bool,
     output_format: OutputFormat,
-) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching all documents..."))
+) -> Result<usize> {
+    let mut spinner = if template.is_none() && !count_only {
+        ui::spinner_guard(output_format, "Fetching all documents...")
     } else {
-        None
+        ui::SpinnerGuard::none()
     };
 
     // Fetch all documents with pagination
     let all_docs = fetch_all_documents(client, bank_id, verbose)?;
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     // Parse the date filter
     let target_date = parse_date_filter(date_filter)?;
 
     // Filter and group documents by date
     let mut by_date: BTreeMap<String, Vec<serde_json::Value>> = BTreeMap::new();
-    let mut filtered_count = 0;
+    let mut filtered_count: usize = 0;
 
     for doc in all_docs {
         let created_at = doc.get("created_at")
             .and_then(|v| v.as_str())
             .unwrap_or("");
 
-        // Parse the date part (YYYY-MM-DD) from created_at
-        let doc_date = created_at.split('T').next().unwrap_or("");
+        // Group by the UTC date of created_at
+        let doc_date = crate::timestamp::parse(created_at)
+            .as_utc()
+            .map(|dt| dt.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "unknown".to_string());
 
         // Apply date filter if specified
         if let Some(ref target) = target_date {
@@ -107,7 +178,16 @@ fn list_with_date(
     }
 
     // Output
-    if output_format == OutputFormat::Pretty {
+    if count_only {
+        output::print_count(filtered_count as i64, output_format)?;
+        return Ok(filtered_count);
+    }
+
+    if let Some(template) = &template {
+        for docs in by_date.values().rev() {
+            output::print_template(template, docs)?;
+        }
+    } else if output_format == OutputFormat::Pretty {
         let filter_desc = match date_filter {
             None | Some("yesterday") => "yesterday".to_string(),
             Some("today") => "today".to_string(),
@@ -137,7 +217,7 @@ fn list_with_date(
         output::print_output(&output, output_format)?;
     }
 
-    Ok(())
+    Ok(filtered_count)
 }
 
 /// Fetch all documents with pagination
@@ -147,29 +227,10 @@ fn fetch_all_documents(
     verbose: bool,
 ) -> Result<Vec<serde_json::Value>> {
     let mut all_docs = Vec::new();
-    let mut offset = 0;
-    let limit = 500;
-
-    loop {
-        let response = client.list_documents(bank_id, None, Some(limit), Some(offset), verbose)?;
-
-        if response.items.is_empty() {
-            break;
-        }
-
+    for page in client.iter_documents(bank_id, None, ALL_DOCUMENTS_PAGE_SIZE, verbose) {
         // Convert Map<String, Value> to Value for each item
-        for item in response.items {
-            all_docs.push(serde_json::Value::Object(item));
-        }
-
-        offset += limit;
-
-        // Check if we've fetched everything
-        if all_docs.len() >= response.total as usize {
-            break;
-        }
+        all_docs.extend(page?.into_iter().map(serde_json::Value::Object));
     }
-
     Ok(all_docs)
 }
 
@@ -198,27 +259,24 @@ pub fn get(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching document..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Fetching document...");
 
     let response = client.get_document(agent_id, document_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(doc) => {
             if output_format == OutputFormat::Pretty {
+                pager::start(output_format);
                 ui::print_info(&format!("Document: {}", doc.id));
                 println!("  Bank ID: {}", doc.bank_id);
-                println!("  Created: {}", doc.created_at);
-                println!("  Updated: {}", doc.updated_at);
+                println!("  Created: {}", ui::format_timestamp(&doc.created_at));
+                println!("  Updated: {}", ui::format_timestamp(&doc.updated_at));
                 println!("  Memory Units: {}", doc.memory_unit_count);
                 println!("\n  Text:\n{}", doc.original_text);
+            } else if output_format == OutputFormat::Raw {
+                output::print_raw(&doc.original_text);
             } else {
                 output::print_output(&doc, output_format)?;
             }
@@ -228,6 +286,308 @@ pub fn get(
     }
 }
 
+/// One document in a `document manifest`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ManifestEntry {
+    id: String,
+    content_hash: Option<String>,
+    updated_at: Option<String>,
+    memory_unit_count: i64,
+}
+
+impl ManifestEntry {
+    fn from_document(doc: &serde_json::Map<String, serde_json::Value>) -> Option<Self> {
+        let text = |key: &str| doc.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty()).map(str::to_string);
+        Some(Self {
+            id: text("id")?,
+            content_hash: text("content_hash"),
+            updated_at: text("updated_at"),
+            memory_unit_count: doc.get("memory_unit_count").and_then(|v| v.as_i64()).unwrap_or(0),
+        })
+    }
+}
+
+/// Document ids that differ between two manifests
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct ManifestDiff {
+    added: Vec<String>,
+    changed: Vec<String>,
+    removed: Vec<String>,
+}
+
+/// A document changed when its content hash differs, or (without hashes) its update time
+fn diff_manifests(previous: &[ManifestEntry], current: &[ManifestEntry]) -> ManifestDiff {
+    let before: BTreeMap<&str, &ManifestEntry> = previous.iter().map(|e| (e.id.as_str(), e)).collect();
+    let after: BTreeMap<&str, &ManifestEntry> = current.iter().map(|e| (e.id.as_str(), e)).collect();
+
+    let mut diff = ManifestDiff::default();
+    for (id, entry) in &after {
+        match before.get(id) {
+            None => diff.added.push(id.to_string()),
+            Some(old) => {
+                let changed = match (&old.content_hash, &entry.content_hash) {
+                    (Some(a), Some(b)) => a != b,
+                    _ => old.updated_at != entry.updated_at,
+                };
+                if changed {
+                    diff.changed.push(id.to_string());
+                }
+            }
+        }
+    }
+    diff.removed = before.keys().filter(|id| !after.contains_key(*id)).map(|id| id.to_string()).collect();
+    diff
+}
+
+fn manifest_csv(entries: &[ManifestEntry]) -> String {
+    let mut csv = String::from("id,content_hash,updated_at,memory_unit_count\n");
+    for entry in entries {
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            csv_field(&entry.id),
+            csv_field(entry.content_hash.as_deref().unwrap_or_default()),
+            csv_field(entry.updated_at.as_deref().unwrap_or_default()),
+            entry.memory_unit_count
+        ));
+    }
+    csv
+}
+
+/// Current manifest of a bank, sorted by id
+fn fetch_manifest(client: &ApiClient, bank_id: &str, verbose: bool) -> Result<Vec<ManifestEntry>> {
+    let mut entries = Vec::new();
+    for page in client.iter_documents(bank_id, None, ALL_DOCUMENTS_PAGE_SIZE, verbose) {
+        entries.extend(page?.iter().filter_map(ManifestEntry::from_document));
+    }
+    entries.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(entries)
+}
+
+fn load_manifest(path: &Path) -> Result<Vec<ManifestEntry>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read manifest: {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse manifest (expected the JSON output of 'document manifest'): {}", path.display()))
+}
+
+/// Print `{id, content_hash, updated_at, memory_unit_count}` for every document, sorted
+/// by id so manifests from different runs diff cleanly. With `since`, print the ids
+/// added, changed and removed relative to that earlier JSON manifest instead.
+pub fn manifest(
+    client: &ApiClient,
+    bank_id: &str,
+    csv: bool,
+    since: Option<PathBuf>,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let previous = since.as_deref().map(load_manifest).transpose()?;
+
+    let mut spinner = ui::spinner_guard(output_format, "Fetching documents...");
+    let entries = fetch_manifest(client, bank_id, verbose);
+    spinner.finish();
+    let entries = entries?;
+
+    if let Some(previous) = previous {
+        let diff = diff_manifests(&previous, &entries);
+        if output_format == OutputFormat::Pretty {
+            ui::print_info(&format!(
+                "{} added, {} changed, {} removed",
+                diff.added.len(),
+                diff.changed.len(),
+                diff.removed.len()
+            ));
+            for (label, ids) in [("+", &diff.added), ("~", &diff.changed), ("-", &diff.removed)] {
+                for id in ids {
+                    println!("  {} {}", label, id);
+                }
+            }
+        } else {
+            output::print_output(&diff, output_format)?;
+        }
+        return Ok(());
+    }
+
+    if csv {
+        print!("{}", manifest_csv(&entries));
+    } else if output_format == OutputFormat::Yaml {
+        output::print_output(&entries, output_format)?;
+    } else {
+        // The manifest is meant for machines, so Pretty prints JSON too
+        output::print_output(&entries, OutputFormat::Json)?;
+    }
+    Ok(())
+}
+
+/// Manifest kept in a sync directory, describing the exported documents
+const SYNC_MANIFEST_FILE: &str = "manifest.json";
+
+/// Subdirectory of a sync directory holding the documents, so no id can land on the manifest
+const SYNC_DOCUMENTS_DIR: &str = "documents";
+
+/// File a synced document is written to, inside [`SYNC_DOCUMENTS_DIR`]. Ids that are
+/// not already safe file names are sanitized and suffixed with a hash of the id, so
+/// `a/b` and `a_b` get different files.
+fn sync_file_name(id: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    let safe: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    let safe = safe.trim_start_matches('.');
+    if safe == id {
+        return format!("{}.json", id);
+    }
+    let hash: String = Sha256::digest(id.as_bytes()).iter().take(4).map(|b| format!("{:02x}", b)).collect();
+    format!("{}-{}.json", safe, hash)
+}
+
+/// Pairs of ids whose sync files would still clash, comparing names case-insensitively
+/// as some filesystems do
+fn sync_file_collisions<'a>(ids: impl IntoIterator<Item = &'a str>) -> Vec<(&'a str, &'a str)> {
+    let mut seen: BTreeMap<String, &str> = BTreeMap::new();
+    let mut collisions = Vec::new();
+    for id in ids {
+        match seen.entry(sync_file_name(id).to_lowercase()) {
+            std::collections::btree_map::Entry::Occupied(entry) => collisions.push((*entry.get(), id)),
+            std::collections::btree_map::Entry::Vacant(entry) => {
+                entry.insert(id);
+            }
+        }
+    }
+    collisions
+}
+
+/// What `document sync` did
+#[derive(Debug, Serialize)]
+struct SyncReport {
+    directory: String,
+    added: Vec<String>,
+    changed: Vec<String>,
+    /// In the previous manifest but no longer in the bank; their files are kept
+    removed: Vec<String>,
+    unchanged: usize,
+    #[serde(flatten)]
+    outcome: BulkOutcome,
+}
+
+/// Export the documents that changed since the last sync into `dir`, one JSON
+/// file each, then record the new manifest there. Without `since`, the previous
+/// manifest is the one left in `dir` by the last run; without either, every
+/// document is exported. Documents that fail to export keep their previous
+/// manifest entry, so the next run retries them.
+pub fn sync(
+    client: &ApiClient,
+    bank_id: &str,
+    dir: PathBuf,
+    since: Option<PathBuf>,
+    concurrency: usize,
+    error_mode: ErrorMode,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let manifest_path = dir.join(SYNC_MANIFEST_FILE);
+    let documents_dir = dir.join(SYNC_DOCUMENTS_DIR);
+    let previous = match since {
+        Some(path) => load_manifest(&path)?,
+        // A directory synced before documents had their own subdirectory is exported afresh
+        None if manifest_path.exists() && documents_dir.exists() => load_manifest(&manifest_path)?,
+        None => Vec::new(),
+    };
+
+    let mut spinner = ui::spinner_guard(output_format, "Fetching documents...");
+    let current = fetch_manifest(client, bank_id, verbose);
+    spinner.finish();
+    let current = current?;
+
+    if let Some((first, second)) = sync_file_collisions(current.iter().map(|entry| entry.id.as_str())).first() {
+        anyhow::bail!(
+            "Documents '{}' and '{}' would be written to the same file in {}; nothing was synced",
+            first,
+            second,
+            documents_dir.display()
+        );
+    }
+
+    let diff = diff_manifests(&previous, &current);
+    std::fs::create_dir_all(&documents_dir)
+        .with_context(|| format!("Failed to create directory: {}", documents_dir.display()))?;
+
+    let to_export: Vec<&String> = diff.added.iter().chain(&diff.changed).collect();
+    let pb = ui::create_progress_bar(to_export.len() as u64, "Exporting documents");
+    let mut outcome = BulkOutcome::new(error_mode);
+    let mut failed = std::collections::HashSet::new();
+    let export = |id: &&String| -> Result<()> {
+        let document = with_retries(|| client.get_document(bank_id, id, verbose))?;
+        let path = documents_dir.join(sync_file_name(id));
+        std::fs::write(&path, output::to_json(&document)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    };
+    run_ordered(&to_export, concurrency, export, |id, result| {
+        pb.inc(1);
+        match result {
+            Ok(()) => outcome.success(),
+            Err(e) => {
+                failed.insert(id.as_str());
+                outcome.failure(format!("Document {}", id), e)?;
+            }
+        }
+        Ok(())
+    })?;
+    pb.finish_and_clear();
+
+    // Failed documents keep their old entry (or none), so they count as changed next time
+    let previous_by_id: BTreeMap<&str, &ManifestEntry> = previous.iter().map(|e| (e.id.as_str(), e)).collect();
+    let recorded: Vec<&ManifestEntry> = current
+        .iter()
+        .filter_map(|entry| {
+            if failed.contains(entry.id.as_str()) {
+                previous_by_id.get(entry.id.as_str()).copied()
+            } else {
+                Some(entry)
+            }
+        })
+        .collect();
+    std::fs::write(&manifest_path, output::to_json(&recorded)?)
+        .with_context(|| format!("Failed to write manifest: {}", manifest_path.display()))?;
+
+    let report = SyncReport {
+        directory: dir.display().to_string(),
+        unchanged: current.len() - diff.added.len() - diff.changed.len(),
+        added: diff.added,
+        changed: diff.changed,
+        removed: diff.removed,
+        outcome,
+    };
+
+    if output_format == OutputFormat::Pretty {
+        let message = format!(
+            "Synced {} into {}: {} added, {} changed, {} unchanged",
+            bank_id,
+            report.directory,
+            report.added.len(),
+            report.changed.len(),
+            report.unchanged
+        );
+        if report.outcome.has_failures() {
+            ui::print_warning(&message);
+        } else {
+            ui::print_success(&message);
+        }
+        if !report.removed.is_empty() {
+            println!("  {}", ui::dim(&format!("Removed from the bank since last sync (files kept): {}", report.removed.len())));
+            for id in &report.removed {
+                println!("    {}", id);
+            }
+        }
+    } else {
+        output::print_output(&report, output_format)?;
+    }
+    report.outcome.print_summary("Sync");
+    report.outcome.finish("Sync")
+}
+
 pub fn delete(
     client: &ApiClient,
     agent_id: &str,
@@ -235,17 +595,11 @@ pub fn delete(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Deleting document..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Deleting document...");
 
     let response = client.delete_document(agent_id, document_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(result) => {
@@ -263,3 +617,78 @@ pub fn delete(
         Err(e) => Err(e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, hash: Option<&str>, updated_at: &str) -> ManifestEntry {
+        ManifestEntry {
+            id: id.to_string(),
+            content_hash: hash.map(str::to_string),
+            updated_at: Some(updated_at.to_string()),
+            memory_unit_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_diff_manifests() {
+        let previous = vec![
+            entry("kept", Some("h1"), "2024-01-01"),
+            entry("edited", Some("h2"), "2024-01-01"),
+            entry("touched", None, "2024-01-01"),
+            entry("gone", Some("h3"), "2024-01-01"),
+        ];
+        let current = vec![
+            entry("kept", Some("h1"), "2024-02-01"),
+            entry("edited", Some("h2b"), "2024-01-01"),
+            entry("touched", None, "2024-02-01"),
+            entry("new", Some("h4"), "2024-02-01"),
+        ];
+
+        assert_eq!(
+            diff_manifests(&previous, &current),
+            ManifestDiff {
+                added: vec!["new".to_string()],
+                changed: vec!["edited".to_string(), "touched".to_string()],
+                removed: vec!["gone".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_sync_file_name_stays_in_directory() {
+        assert_eq!(sync_file_name("notes-2024.md"), "notes-2024.md.json");
+        assert_eq!(sync_file_name("manifest"), "manifest.json");
+
+        let escaped = sync_file_name("../etc/passwd");
+        assert!(escaped.starts_with("_etc_passwd-") && escaped.ends_with(".json"));
+        assert!(!escaped.contains('/'));
+        assert!(sync_file_name("a/b c").starts_with("a_b_c-"));
+    }
+
+    #[test]
+    fn test_sync_file_names_do_not_collide() {
+        assert_ne!(sync_file_name("a/b"), sync_file_name("a_b"));
+        assert_ne!(sync_file_name("a/b"), sync_file_name("a?b"));
+        assert!(sync_file_collisions(["a/b", "a_b", "a?b", "manifest"]).is_empty());
+
+        // Case-insensitive filesystems would merge these
+        assert_eq!(sync_file_collisions(["Notes", "notes", "other"]), vec![("Notes", "notes")]);
+    }
+
+    #[test]
+    fn test_manifest_csv() {
+        let entries = vec![ManifestEntry {
+            id: "notes, 2024".to_string(),
+            content_hash: None,
+            updated_at: Some("2024-01-01T00:00:00".to_string()),
+            memory_unit_count: 3,
+        }];
+
+        assert_eq!(
+            manifest_csv(&entries),
+            "id,content_hash,updated_at,memory_unit_count\n\"notes, 2024\",,2024-01-01T00:00:00,3\n"
+        );
+    }
+}
@@ -7,19 +7,22 @@ pub fn list(
     client: &ApiClient,
     bank_id: &str,
     limit: i64,
+    count_only: bool,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching entities..."))
+    let mut spinner = if !count_only {
+        ui::spinner_guard(output_format, "Fetching entities...")
     } else {
-        None
+        ui::SpinnerGuard::none()
     };
 
     let response = client.list_entities(bank_id, Some(limit), None, verbose)?;
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
+    spinner.finish();
+
+    if count_only {
+        return output::print_count(response.total, output_format);
     }
 
     if output_format == OutputFormat::Pretty {
@@ -58,17 +61,11 @@ pub fn get(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching entity details..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Fetching entity details...");
 
     let response = client.get_entity(bank_id, entity_id, verbose)?;
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     if output_format == OutputFormat::Pretty {
         ui::print_section_header(&format!("Entity: {}", entity_id));
@@ -99,17 +96,11 @@ pub fn regenerate(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Regenerating entity observations..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Regenerating entity observations...");
 
     let response = client.regenerate_entity(bank_id, entity_id, verbose)?;
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     if output_format == OutputFormat::Pretty {
         ui::print_success(&format!("Successfully regenerated observations for entity: {}", entity_id));
@@ -0,0 +1,205 @@
+//! `hindsight env`: effective settings and where each one came from, for support requests.
+//!
+//! Secrets (API key, sensitive headers, proxy credentials) are never printed.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::env;
+use std::time::Duration;
+
+use crate::api::{self, CustomHeader};
+use crate::bulk;
+use crate::cache;
+use crate::config::{Config, ConfigSource};
+use crate::output::{self, OutputFormat};
+use crate::ui;
+
+/// Proxy variables honoured by the HTTP client, in lookup order
+const PROXY_VARS: &[&str] = &[
+    "HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy", "NO_PROXY", "no_proxy",
+];
+
+const FLAG: &str = "flag";
+const ENVIRONMENT: &str = "environment variable";
+const DEFAULT: &str = "default";
+const BUILT_IN: &str = "built-in";
+
+/// Global flags that affect the effective settings
+pub struct EnvFlags<'a> {
+    pub tenant: Option<&'a str>,
+    pub bank: Option<&'a str>,
+    pub request_id: Option<&'a str>,
+    pub headers: &'a [CustomHeader],
+    pub wait_timeout: Duration,
+    pub cancel_on_timeout: bool,
+    pub no_cache: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Setting {
+    pub name: &'static str,
+    pub value: String,
+    pub source: String,
+}
+
+fn setting(name: &'static str, value: impl Into<String>, source: impl ToString) -> Setting {
+    Setting {
+        name,
+        value: value.into(),
+        source: source.to_string(),
+    }
+}
+
+fn collect(config: &Config, flags: &EnvFlags) -> Vec<Setting> {
+    let mut settings = vec![
+        setting("version", env!("CARGO_PKG_VERSION"), BUILT_IN),
+        setting("api_url", &config.api_url, &config.source),
+    ];
+
+    let tenant_source = if flags.tenant.is_some() {
+        FLAG
+    } else if env::var("HINDSIGHT_TENANT").is_ok() {
+        ENVIRONMENT
+    } else if config.tenant.is_some() {
+        "config file"
+    } else {
+        DEFAULT
+    };
+    let tenant = flags.tenant.or(config.tenant.as_deref());
+    settings.push(setting("tenant", tenant.unwrap_or("(none)"), tenant_source));
+    settings.push(setting("base_url", api::tenant_base_url(config.api_url(), tenant), "api_url + tenant"));
+
+    let bank_source = if flags.bank.is_some() {
+        FLAG
+    } else if env::var("HINDSIGHT_BANK").is_ok() {
+        ENVIRONMENT
+    } else if config.bank.is_some() {
+        "config file"
+    } else {
+        DEFAULT
+    };
+    let bank = flags.bank.or(config.bank.as_deref());
+    settings.push(setting("bank", bank.unwrap_or("(none)"), bank_source));
+
+    let api_key_source = if config.api_key.is_none() {
+        DEFAULT.to_string()
+    } else if env::var("HINDSIGHT_API_KEY").is_ok() {
+        ENVIRONMENT.to_string()
+    } else {
+        ConfigSource::LocalFile.to_string()
+    };
+    let api_key = if config.api_key.is_some() { "<redacted>" } else { "(not set)" };
+    settings.push(setting("api_key", api_key, api_key_source));
+
+    let config_file = match Config::config_file_path() {
+        Some(path) if path.exists() => path.display().to_string(),
+        Some(path) => format!("{} (missing)", path.display()),
+        None => "(no home directory)".to_string(),
+    };
+    settings.push(setting("config_file", config_file, BUILT_IN));
+
+    settings.push(setting("request_timeout", format!("{}s", api::REQUEST_TIMEOUT.as_secs()), BUILT_IN));
+    settings.push(setting("retries", "1 retry when a GET response fails to parse", BUILT_IN));
+    settings.push(setting(
+        "bulk_retries",
+        format!(
+            "{} per item, {} per command (--retries, --max-total-retries)",
+            bulk::DEFAULT_RETRIES,
+            bulk::DEFAULT_MAX_TOTAL_RETRIES
+        ),
+        DEFAULT,
+    ));
+
+    let wait_source = if flags.wait_timeout == api::DEFAULT_WAIT_TIMEOUT { DEFAULT } else { FLAG };
+    settings.push(setting("wait_timeout", ui::format_elapsed(flags.wait_timeout), wait_source));
+    settings.push(setting(
+        "cancel_on_timeout",
+        flags.cancel_on_timeout.to_string(),
+        if flags.cancel_on_timeout { FLAG } else { DEFAULT },
+    ));
+
+    let cache = match (flags.no_cache, cache::cache_root()) {
+        (true, _) => "disabled".to_string(),
+        (false, Some(root)) => format!("{} (ttl {}s)", root.display(), cache::DEFAULT_TTL.as_secs()),
+        (false, None) => "unavailable (no home directory)".to_string(),
+    };
+    settings.push(setting("cache", cache, if flags.no_cache { FLAG } else { DEFAULT }));
+
+    let proxies: Vec<Setting> = PROXY_VARS
+        .iter()
+        .filter_map(|var| {
+            let value = env::var(var).ok()?;
+            Some(setting("proxy", format!("{}={}", var, api::redact_url_credentials(&value)), ENVIRONMENT))
+        })
+        .collect();
+    if proxies.is_empty() {
+        settings.push(setting("proxy", "(none)", DEFAULT));
+    }
+    settings.extend(proxies);
+
+    for header in flags.headers {
+        settings.push(setting("header", header.redacted(), FLAG));
+    }
+
+    settings.push(match flags.request_id {
+        Some(id) => setting("request_id", id, FLAG),
+        None => setting("request_id", "(new UUID per invocation)", DEFAULT),
+    });
+
+    settings
+}
+
+/// Print the effective settings and their sources
+pub fn run(flags: &EnvFlags, output_format: OutputFormat) -> Result<()> {
+    let config = Config::load()?;
+    let settings = collect(&config, flags);
+
+    if output_format != OutputFormat::Pretty {
+        return output::print_output(&settings, output_format);
+    }
+
+    ui::print_section_header("Environment");
+    let width = settings.iter().map(|s| s.name.len()).max().unwrap_or(0);
+    for s in &settings {
+        println!(
+            "  {:<width$}  {}  {}",
+            s.name,
+            ui::gradient_start(&s.value),
+            ui::dim(&format!("({})", s.source)),
+            width = width
+        );
+    }
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_redacts_api_key() {
+        let config = Config {
+            api_url: "http://host:8888".to_string(),
+            api_key: Some("sk-secret".to_string()),
+            tenant: None,
+            bank: None,
+            source: ConfigSource::Default,
+        };
+        let flags = EnvFlags {
+            tenant: Some("acme"),
+            bank: None,
+            request_id: None,
+            headers: &[],
+            wait_timeout: api::DEFAULT_WAIT_TIMEOUT,
+            cancel_on_timeout: false,
+            no_cache: true,
+        };
+        let settings = collect(&config, &flags);
+        assert!(settings.iter().all(|s| !s.value.contains("sk-secret")));
+        let base = settings.iter().find(|s| s.name == "base_url").unwrap();
+        assert_eq!(base.value, "http://host:8888/tenants/acme");
+        let tenant = settings.iter().find(|s| s.name == "tenant").unwrap();
+        assert_eq!(tenant.source, "flag");
+    }
+}
@@ -0,0 +1,203 @@
+//! Runnable example invocations for each command.
+//!
+//! The registry below is checked against the real clap definitions in the tests,
+//! so an example that uses a renamed or removed flag fails the build.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::output::{self, OutputFormat};
+use crate::ui;
+
+#[derive(Debug, Serialize)]
+pub struct Example {
+    pub command: &'static str,
+    pub description: &'static str,
+    pub invocation: &'static str,
+}
+
+const fn example(command: &'static str, description: &'static str, invocation: &'static str) -> Example {
+    Example { command, description, invocation }
+}
+
+/// Example registry, grouped by command
+pub const EXAMPLES: &[Example] = &[
+    example("bank list", "List all banks", "hindsight bank list"),
+    example(
+        "bank create",
+        "Create a bank with a mission and disposition",
+        "hindsight bank create my-bank --name \"Support Bot\" --mission \"Remember customer conversations\" --skepticism 4",
+    ),
+    example("bank stats", "Show memory and link counts", "hindsight bank stats my-bank"),
+    example("bank consolidate", "Consolidate and wait for completion", "hindsight bank consolidate my-bank --wait"),
+    example(
+        "memory retain",
+        "Store a single memory with context",
+        "hindsight memory retain my-bank \"Alice works at Google\" --context \"team notes\"",
+    ),
+    example(
+        "memory retain-files",
+        "Queue every text file in a directory",
+        "hindsight memory retain-files my-bank ./notes --async",
+    ),
+    example(
+        "memory import",
+        "Import a JSONL file as one document, reporting all failures",
+        "hindsight memory import my-bank memories.jsonl --auto-document-id",
+    ),
+    example(
+        "memory recall",
+        "Recall world and experience facts with a larger budget",
+        "hindsight memory recall my-bank \"where does alice work\" --budget high --fact-type world,experience",
+    ),
+    example(
+        "memory recall",
+        "Print one line per fact for scripting",
+        "hindsight memory recall my-bank \"alice\" --format '{{id}} {{text}}'",
+    ),
+    example(
+        "memory recall",
+        "Recall only facts extracted from one document",
+        "hindsight memory recall my-bank \"deadlines\" --document-id doc-123",
+    ),
+    example(
+        "memory recall-batch",
+        "Run a CSV of evaluation queries and keep the top 3 results of each",
+        "hindsight memory recall-batch my-bank --input queries.csv --results results.csv --top 3",
+    ),
+    example(
+        "memory reflect",
+        "Reflect on a question",
+        "hindsight memory reflect my-bank \"what should I get alice for her birthday\"",
+    ),
+    example(
+        "memory reflect",
+        "Show only the memories a reflection used",
+        "hindsight memory reflect my-bank \"why did we pick postgres\" --sources-only",
+    ),
+    example("document list", "List documents created today", "hindsight document list my-bank --date today"),
+    example("document get", "Show a document and its text", "hindsight document get my-bank doc-123"),
+    example("operation list", "List background operations", "hindsight operation list my-bank"),
+    example(
+        "operation list",
+        "Count background operations for a dashboard",
+        "hindsight -o json operation list my-bank --count-only",
+    ),
+    example(
+        "operation cancel",
+        "Cancel every pending operation without prompting",
+        "hindsight operation cancel my-bank --all -y",
+    ),
+    example(
+        "mental-model refresh",
+        "Refresh a mental model and review the changes",
+        "hindsight mental-model refresh my-bank model-123 --wait",
+    ),
+    example("directive list", "List directives alphabetically", "hindsight directive list my-bank --sort name"),
+    example("repl", "Start an interactive recall/reflect session", "hindsight repl my-bank"),
+    example("cache clear", "Drop cached bank lists and profiles", "hindsight cache clear"),
+    example("bank list", "Get machine-readable output", "hindsight -o json bank list"),
+];
+
+/// Examples whose command starts with `filter` (e.g. "memory" or "memory recall")
+fn matching(filter: Option<&str>) -> Vec<&'static Example> {
+    let filter = filter.map(|f| f.split_whitespace().collect::<Vec<_>>());
+    EXAMPLES
+        .iter()
+        .filter(|ex| match &filter {
+            Some(words) => {
+                let command: Vec<&str> = ex.command.split_whitespace().collect();
+                command.starts_with(words)
+            }
+            None => true,
+        })
+        .collect()
+}
+
+/// Print example invocations, optionally limited to one command
+pub fn run(command: Option<String>, output_format: OutputFormat) -> Result<()> {
+    let examples = matching(command.as_deref());
+    if examples.is_empty() {
+        anyhow::bail!(
+            "No examples for '{}'. Run 'hindsight examples' to see all commands with examples",
+            command.unwrap_or_default()
+        );
+    }
+
+    if output_format != OutputFormat::Pretty {
+        return output::print_output(&examples, output_format);
+    }
+
+    let mut current = "";
+    for ex in examples {
+        if ex.command != current {
+            ui::print_section_header(ex.command);
+            current = ex.command;
+        }
+        println!("  {}", ui::dim(&format!("# {}", ex.description)));
+        println!("  {}", ui::gradient_start(ex.invocation));
+        println!();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// Minimal shell-style splitting: whitespace-separated, with single or double quotes
+    fn split_args(line: &str) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut current = String::new();
+        let mut quote = None;
+        let mut in_arg = false;
+        for ch in line.chars() {
+            match (quote, ch) {
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), c) => current.push(c),
+                (None, '"' | '\'') => {
+                    quote = Some(ch);
+                    in_arg = true;
+                }
+                (None, c) if c.is_whitespace() => {
+                    if in_arg {
+                        args.push(std::mem::take(&mut current));
+                        in_arg = false;
+                    }
+                }
+                (None, c) => {
+                    current.push(c);
+                    in_arg = true;
+                }
+            }
+        }
+        if in_arg {
+            args.push(current);
+        }
+        args
+    }
+
+    #[test]
+    fn test_examples_parse_with_current_flags() {
+        for ex in EXAMPLES {
+            let args = split_args(ex.invocation);
+            if let Err(e) = crate::Cli::try_parse_from(&args) {
+                panic!("example '{}' no longer parses: {}", ex.invocation, e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matching_filters_by_command_prefix() {
+        assert!(matching(Some("memory")).iter().all(|ex| ex.command.starts_with("memory ")));
+        assert_eq!(matching(Some("memory recall")).len(), 3);
+        assert!(matching(Some("mem")).is_empty());
+        assert_eq!(matching(None).len(), EXAMPLES.len());
+    }
+
+    #[test]
+    fn test_split_args_quotes() {
+        assert_eq!(split_args(r#"a "b c" 'd e'"#), ["a", "b c", "d e"]);
+    }
+}
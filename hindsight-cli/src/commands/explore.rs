@@ -1,4 +1,4 @@
-use crate::api::{ApiClient, RecallRequest, ReflectRequest};
+use crate::api::{ApiClient, RecallRequest, ReflectRequest, Sampling};
 use anyhow::Result;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
@@ -361,9 +361,12 @@ impl App {
                             response_schema: None,
                             tags: None,
                             tags_match: TagsMatch::Any,
+                            temperature: None,
+                            top_p: None,
+                            seed: None,
                         };
 
-                        let result = client.reflect(&bank_id, &request, false)
+                        let result = client.reflect(&bank_id, &request, Sampling::default(), false)
                             .map(|r| r.text)
                             .map_err(|e| e.to_string());
 
@@ -1028,12 +1031,10 @@ fn render_memories(f: &mut Frame, app: &mut App, area: Rect) {
                 .unwrap_or("unknown");
             let mentioned = memory.get("mentioned_at")
                 .and_then(|v| v.as_str())
-                .and_then(|s| s.split('T').next())
-                .unwrap_or("-");
+                .map_or_else(|| "-".to_string(), crate::ui::format_date);
             let occurred = memory.get("occurred_start")
                 .and_then(|v| v.as_str())
-                .and_then(|s| s.split('T').next())
-                .unwrap_or("-");
+                .map_or_else(|| "-".to_string(), crate::ui::format_date);
             let text = memory.get("text").and_then(|v| v.as_str()).unwrap_or("");
 
             // Apply horizontal scroll
@@ -1178,8 +1179,7 @@ fn render_documents(f: &mut Frame, app: &mut App, area: Rect) {
                 .unwrap_or("unknown");
             let created = doc.get("created_at")
                 .and_then(|v| v.as_str())
-                .and_then(|s| s.split('T').next())
-                .unwrap_or("unknown");
+                .map_or_else(|| "unknown".to_string(), crate::ui::format_date);
 
             let content = format!("{:<40} {:<20} {}", scrolled_id, content_type, created);
             items.push(ListItem::new(content).style(Style::default().fg(Color::White)));
@@ -1307,11 +1307,9 @@ fn render_query(f: &mut Frame, app: &mut App, area: Rect) {
                 for result in &app.query_results {
                     let mem_type = result.type_.as_deref().unwrap_or("unknown");
                     let occurred_start = result.occurred_start.as_deref()
-                        .and_then(|s| s.split('T').next())
-                        .unwrap_or("-");
+                        .map_or_else(|| "-".to_string(), crate::ui::format_date);
                     let occurred_end = result.occurred_end.as_deref()
-                        .and_then(|s| s.split('T').next())
-                        .unwrap_or("-");
+                        .map_or_else(|| "-".to_string(), crate::ui::format_date);
                     let text = &result.text;
 
                     // Apply horizontal scroll
@@ -21,17 +21,11 @@ pub fn health(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Checking health..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Checking health...");
 
     let response = client.health(verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(value) => {
@@ -81,17 +75,11 @@ pub fn version(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching version..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Fetching version...");
 
     let response = client.get_version(verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(result) => {
@@ -101,9 +89,9 @@ pub fn version(
 
                 println!();
                 println!("  {}", ui::dim("Features:"));
-                println!("    {} MCP Server: {}", ui::gradient_start("•"), if result.features.mcp { "enabled" } else { "disabled" });
-                println!("    {} Observations: {}", ui::gradient_start("•"), if result.features.observations { "enabled" } else { "disabled" });
-                println!("    {} Background Worker: {}", ui::gradient_start("•"), if result.features.worker { "enabled" } else { "disabled" });
+                println!("    {} MCP Server: {}", ui::gradient_start(ui::glyph(ui::Glyph::Bullet)), if result.features.mcp { "enabled" } else { "disabled" });
+                println!("    {} Observations: {}", ui::gradient_start(ui::glyph(ui::Glyph::Bullet)), if result.features.observations { "enabled" } else { "disabled" });
+                println!("    {} Background Worker: {}", ui::gradient_start(ui::glyph(ui::Glyph::Bullet)), if result.features.worker { "enabled" } else { "disabled" });
                 println!();
             } else {
                 output::print_output(&result, output_format)?;
@@ -114,23 +102,57 @@ pub fn version(
     }
 }
 
+/// Show what the connected server supports
+pub fn capabilities(
+    client: &ApiClient,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let mut spinner = ui::spinner_guard(output_format, "Discovering capabilities...");
+
+    let response = client.capabilities(verbose);
+
+    spinner.finish();
+
+    let result = response?;
+    if output_format == OutputFormat::Pretty {
+        let supported = |on: Option<bool>| match on {
+            Some(true) => ui::gradient_start("supported"),
+            Some(false) => ui::dim("not supported"),
+            None => ui::dim("unknown, sent as given"),
+        };
+        ui::print_section_header("Server Capabilities");
+        println!("  {} {}", ui::dim("Version:"), result.api_version.as_deref().unwrap_or("unknown"));
+        let source = if result.source == "endpoint" { "capabilities endpoint" } else { "inferred from version" };
+        println!("  {} {}", ui::dim("Source:"), source);
+
+        println!();
+        println!("  {}", ui::dim("Features:"));
+        println!("    {} MCP Server: {}", ui::gradient_start(ui::glyph(ui::Glyph::Bullet)), if result.mcp { "enabled" } else { "disabled" });
+        println!("    {} Observations: {}", ui::gradient_start(ui::glyph(ui::Glyph::Bullet)), if result.observations { "enabled" } else { "disabled" });
+        println!("    {} Background Worker: {}", ui::gradient_start(ui::glyph(ui::Glyph::Bullet)), if result.worker { "enabled" } else { "disabled" });
+
+        println!();
+        println!("  {}", ui::dim("Optional request fields:"));
+        println!("    {} Reflect sampling (--temperature, --top-p, --seed): {}", ui::gradient_start(ui::glyph(ui::Glyph::Bullet)), supported(result.reflect_sampling));
+        println!();
+    } else {
+        output::print_output(&result, output_format)?;
+    }
+    Ok(())
+}
+
 /// Get Prometheus metrics
 pub fn metrics(
     client: &ApiClient,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching metrics..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Fetching metrics...");
 
     let response = client.metrics(verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(result) => {
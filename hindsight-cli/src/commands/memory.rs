@@ -1,16 +1,25 @@
 use anyhow::{Context, Result};
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
-use crate::api::{ApiClient, RecallRequest, ReflectRequest, MemoryItem, RetainRequest};
+use crate::api::{ApiClient, MemoryItem, MemoryPutResult, RecallRequest, RecallResult, ReflectRequest, RetainRequest, Sampling, TraceInfo};
+use crate::bulk::{run_ordered, with_retries, BulkOutcome, ErrorMode};
 use crate::config;
-use crate::output::{self, OutputFormat};
+use crate::errors;
+use crate::lenient;
+use crate::output::{self, csv_field, OutputFormat};
+use crate::pager;
 use crate::ui;
 
 // Import types from generated client
-use hindsight_client::types::{Budget, ChunkIncludeOptions, IncludeOptions, TagsMatch};
-use serde::Deserialize;
+use hindsight_client::types::{
+    Budget, ChunkIncludeOptions, FactsIncludeOptions, IncludeOptions, ReflectIncludeOptions, TagsMatch,
+    ToolCallsIncludeOptions,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use serde_json;
 
 // Local types for serde_json::Value deserialization
@@ -43,6 +52,34 @@ fn parse_budget(budget: &str) -> Budget {
     }
 }
 
+/// Token limit for a `mid` budget; matches the server's own default
+const BASE_MAX_TOKENS: i64 = 4096;
+const MIN_DERIVED_MAX_TOKENS: i64 = 1024;
+const MAX_DERIVED_MAX_TOKENS: i64 = 16384;
+
+/// `--max-tokens` when omitted: BASE_MAX_TOKENS scaled by the budget
+/// (low ×½, mid ×1, high ×2), clamped to [1024, 16384]. A larger budget explores
+/// more and needs room to return it, otherwise results come back truncated.
+fn default_max_tokens(budget: &Budget) -> i64 {
+    let scaled = match budget {
+        Budget::Low => BASE_MAX_TOKENS / 2,
+        Budget::Mid => BASE_MAX_TOKENS,
+        Budget::High => BASE_MAX_TOKENS * 2,
+    };
+    scaled.clamp(MIN_DERIVED_MAX_TOKENS, MAX_DERIVED_MAX_TOKENS)
+}
+
+/// An explicit `--max-tokens` wins; otherwise derive it from the budget
+fn resolve_max_tokens(max_tokens: Option<i64>, budget: &Budget, verbose: bool) -> i64 {
+    max_tokens.unwrap_or_else(|| {
+        let derived = default_max_tokens(budget);
+        if verbose {
+            eprintln!("Using max_tokens {} derived from budget '{}'", derived, budget);
+        }
+        derived
+    })
+}
+
 /// List memory units with pagination and optional filters
 pub fn list(
     client: &ApiClient,
@@ -51,13 +88,14 @@ pub fn list(
     query: Option<String>,
     limit: i64,
     offset: i64,
+    count_only: bool,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching memories..."))
+    let mut spinner = if !count_only {
+        ui::spinner_guard(output_format, "Fetching memories...")
     } else {
-        None
+        ui::SpinnerGuard::none()
     };
 
     let response = client.list_memories(
@@ -69,12 +107,14 @@ pub fn list(
         verbose,
     );
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(result) => {
+            if count_only {
+                return output::print_count(result.total, output_format);
+            }
+
             if output_format == OutputFormat::Pretty {
                 ui::print_section_header(&format!("Memories: {} (showing {}-{})", bank_id, offset + 1, offset + result.items.len() as i64));
 
@@ -104,9 +144,10 @@ pub fn list(
 
                         // Truncate text if too long
                         if let Some(text) = item.get("text").and_then(|v| v.as_str()) {
-                            let text_preview: String = text.chars().take(100).collect();
-                            let ellipsis = if text.len() > 100 { "..." } else { "" };
-                            println!("    {}{}", text_preview, ellipsis);
+                            match ui::preview_width(4) {
+                                Some(max) => println!("    {}", ui::truncate_text(text, max)),
+                                None => println!("    {}", text),
+                            }
                         }
 
                         if let Some(doc_id) = item.get("document_id").and_then(|v| v.as_str()) {
@@ -134,17 +175,11 @@ pub fn get(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching memory..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Fetching memory...");
 
     let response = client.get_memory(bank_id, memory_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(value) => {
@@ -169,19 +204,19 @@ pub fn get(
                     println!("  {} {}", ui::dim("Document:"), doc_id);
                 }
 
-                if let Some(context) = &result.context {
+                if let Some(context) = result.context.as_ref().filter(|_| ui::show_fact_context()) {
                     println!("  {} {}", ui::dim("Context:"), context);
                 }
 
                 println!();
-                println!("{}", ui::gradient_text("─── Content ───"));
+                println!("{}", ui::gradient_text(&ui::rule("Content")));
                 println!();
                 println!("{}", result.text);
 
                 // Show temporal info if available
                 if result.occurred_start.is_some() || result.occurred_end.is_some() {
                     println!();
-                    println!("{}", ui::gradient_text("─── Temporal ───"));
+                    println!("{}", ui::gradient_text(&ui::rule("Temporal")));
                     if let Some(start) = &result.occurred_start {
                         println!("  {} {}", ui::dim("Start:"), start);
                     }
@@ -194,9 +229,9 @@ pub fn get(
                 if let Some(entities) = &result.entities {
                     if !entities.is_empty() {
                         println!();
-                        println!("{}", ui::gradient_text("─── Entities ───"));
+                        println!("{}", ui::gradient_text(&ui::rule("Entities")));
                         for entity in entities {
-                            println!("  • {} ({})", entity.name, entity.id);
+                            println!("  {} {} ({})", ui::glyph(ui::Glyph::Bullet), entity.name, entity.id);
                         }
                     }
                 }
@@ -205,7 +240,7 @@ pub fn get(
                 if let Some(tags) = &result.tags {
                     if !tags.is_empty() {
                         println!();
-                        println!("{}", ui::gradient_text("─── Tags ───"));
+                        println!("{}", ui::gradient_text(&ui::rule("Tags")));
                         println!("  {}", tags.join(", "));
                     }
                 }
@@ -231,24 +266,333 @@ fn is_text_file(path: &std::path::Path) -> bool {
         .unwrap_or(false)
 }
 
-pub fn recall(
+/// Latency summary in milliseconds for `recall --benchmark`
+#[derive(Debug, PartialEq, Serialize)]
+struct LatencyStats {
+    min: f64,
+    mean: f64,
+    median: f64,
+    p95: f64,
+    max: f64,
+}
+
+impl LatencyStats {
+    /// Summarize samples, or None when there are none. p95 uses the nearest-rank method.
+    fn from_samples(samples: &[f64]) -> Option<Self> {
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let n = sorted.len();
+        if n == 0 {
+            return None;
+        }
+        let median = if n.is_multiple_of(2) { (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0 } else { sorted[n / 2] };
+        let p95_rank = (n as f64 * 0.95).ceil() as usize;
+        Some(Self {
+            min: sorted[0],
+            mean: sorted.iter().sum::<f64>() / n as f64,
+            median,
+            p95: sorted[p95_rank.max(1) - 1],
+            max: sorted[n - 1],
+        })
+    }
+}
+
+/// `recall --benchmark` report
+#[derive(Debug, Serialize)]
+struct BenchmarkReport {
+    query: String,
+    runs: usize,
+    warmup: usize,
+    client_ms: LatencyStats,
+    /// From the trace's `total_time`; None when the server reported no timings
+    server_ms: Option<LatencyStats>,
+}
+
+impl BenchmarkReport {
+    /// Table columns in reading order (rows serialize with sorted keys)
+    fn columns() -> Vec<String> {
+        ["timing", "min", "mean", "median", "p95", "max"].map(String::from).to_vec()
+    }
+
+    /// One row per timing source, for table output
+    fn rows(&self) -> Result<serde_json::Value> {
+        let mut rows = Vec::new();
+        for (timing, stats) in [("client", Some(&self.client_ms)), ("server", self.server_ms.as_ref())] {
+            let Some(stats) = stats else { continue };
+            let mut row = serde_json::json!({ "timing": timing });
+            if let (Some(row), serde_json::Value::Object(fields)) = (row.as_object_mut(), serde_json::to_value(stats)?) {
+                for (key, value) in fields {
+                    let ms = value.as_f64().map(ui::format_decimal).unwrap_or_default();
+                    row.insert(key, serde_json::Value::String(ms));
+                }
+            }
+            rows.push(row);
+        }
+        Ok(serde_json::Value::Array(rows))
+    }
+}
+
+/// Run one recall `warmup + runs` times and report the latency of the measured runs
+fn benchmark_recall(
     client: &ApiClient,
     agent_id: &str,
-    query: String,
-    fact_type: Vec<String>,
-    budget: String,
-    max_tokens: i64,
-    trace: bool,
-    include_chunks: bool,
-    chunk_max_tokens: i64,
+    mut request: RecallRequest,
+    runs: usize,
+    warmup: usize,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Recalling memories..."))
-    } else {
-        None
+    // The trace carries the server-side timing
+    request.trace = true;
+
+    let mut spinner = ui::spinner_guard(
+        output_format,
+        &format!("Benchmarking recall ({} runs, {} warmup)...", runs, warmup),
+    );
+    let mut client_ms = Vec::with_capacity(runs);
+    let mut server_ms = Vec::with_capacity(runs);
+    for run in 0..warmup + runs {
+        let started = std::time::Instant::now();
+        let result = client.recall_uncached(agent_id, &request, verbose);
+        let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+        let result = result.with_context(|| format!("Benchmark run {} failed", run + 1))?;
+        if run < warmup {
+            continue;
+        }
+        client_ms.push(elapsed_ms);
+        let total_time = result.trace.as_ref().and_then(|t| t.get("total_time")).and_then(|v| v.as_f64());
+        server_ms.extend(total_time);
+    }
+    spinner.finish();
+
+    let report = BenchmarkReport {
+        query: request.query,
+        runs,
+        warmup,
+        client_ms: LatencyStats::from_samples(&client_ms).expect("--benchmark runs at least once"),
+        server_ms: LatencyStats::from_samples(&server_ms),
     };
+    match output_format {
+        OutputFormat::Pretty => {
+            ui::print_section_header(&format!("Benchmark: {}", report.query));
+            println!(
+                "  {} {} measured, {} warmup",
+                ui::dim("Runs:"),
+                report.runs,
+                report.warmup
+            );
+            println!();
+            println!("{}", crate::table::render(&report.rows()?, &BenchmarkReport::columns())?);
+            if report.server_ms.is_none() {
+                println!();
+                println!("{}", ui::dim("The server returned no trace timings."));
+            }
+            println!();
+        }
+        OutputFormat::Table if crate::table::columns().is_empty() => {
+            println!("{}", crate::table::render(&report.rows()?, &BenchmarkReport::columns())?)
+        }
+        OutputFormat::Table => crate::table::print(&report.rows()?)?,
+        _ => output::print_output(&report, output_format)?,
+    }
+    Ok(())
+}
+
+/// One query's results in batch recall output
+#[derive(Debug, Serialize)]
+struct QueryResults {
+    query: String,
+    results: Vec<RecallResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score_explanation: Option<Vec<ScoreExplanation>>,
+}
+
+/// A result's place and relative strength for `recall --explain-scores`
+#[derive(Debug, PartialEq, Serialize)]
+struct ScoreExplanation {
+    rank: usize,
+    id: String,
+    activation: Option<f64>,
+    /// Activation as a percentage of the highest activation among the results
+    percent_of_top: Option<f64>,
+}
+
+/// Rank results in display order and relate each activation to the highest one.
+/// The highest need not come first, e.g. after --rerank.
+fn explain(results: &[RecallResult]) -> Vec<ScoreExplanation> {
+    let top = results
+        .iter()
+        .filter_map(ui::fact_activation)
+        .fold(None, |top: Option<f64>, activation| Some(top.map_or(activation, |t| t.max(activation))))
+        .filter(|top| *top > 0.0);
+    results
+        .iter()
+        .enumerate()
+        .map(|(i, fact)| {
+            let activation = ui::fact_activation(fact);
+            ScoreExplanation {
+                rank: i + 1,
+                id: fact.id.clone(),
+                activation,
+                percent_of_top: activation.zip(top).map(|(activation, top)| activation / top * 100.0),
+            }
+        })
+        .collect()
+}
+
+fn print_score_explanations(explanations: &[ScoreExplanation]) -> Result<()> {
+    if explanations.is_empty() {
+        return Ok(());
+    }
+    ui::print_section_header("Scores");
+    let rows: Vec<serde_json::Value> = explanations
+        .iter()
+        .map(|e| {
+            serde_json::json!({
+                "rank": e.rank,
+                "id": e.id,
+                "activation": e.activation.map(ui::format_decimal),
+                "relative": e.percent_of_top.map(|p| format!("{:.1}%", p)),
+            })
+        })
+        .collect();
+    let columns = ["rank", "id", "activation", "relative"].map(String::from);
+    println!("{}", crate::table::render(&serde_json::Value::Array(rows), &columns)?);
+    println!();
+    if explanations.iter().all(|e| e.activation.is_none()) {
+        println!("{}", ui::dim("The server reported no activation scores for these results."));
+        println!();
+    }
+    Ok(())
+}
+
+/// Read a complete request object piped on stdin (`--stdin-json`)
+fn read_stdin_request<T: DeserializeOwned>() -> Result<T> {
+    use std::io::IsTerminal;
+
+    let stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        anyhow::bail!("--stdin-json expects a JSON request piped on stdin");
+    }
+    let body = std::io::read_to_string(stdin).context("Failed to read the request from stdin")?;
+    parse_request(&body)
+}
+
+/// Deserialize a request, naming the field that failed to parse
+fn parse_request<T: DeserializeOwned>(body: &str) -> Result<T> {
+    serde_json::from_str(body).map_err(|e| match lenient::locate::<T>(body.as_bytes()) {
+        Some(field) => anyhow::anyhow!("Invalid request on stdin at '{}': {}", field, e),
+        None => anyhow::anyhow!("Invalid request on stdin: {}", e),
+    })
+}
+
+/// Queries from a `--queries-file`: one per line, skipping blank lines and `#` comments
+fn load_queries_file(path: &Path) -> Result<Vec<String>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read queries file: {}", path.display()))?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Fact types `memory recall` searches unless `--fact-type` says otherwise
+pub const DEFAULT_FACT_TYPES: &[&str] = &["world", "experience", "opinion"];
+
+/// `--budget` unless given
+pub const DEFAULT_BUDGET: &str = "mid";
+
+/// `--chunk-max-tokens` unless given
+pub const DEFAULT_CHUNK_MAX_TOKENS: i64 = 8192;
+
+/// Everything about a `memory recall` besides the bank and queries. The default is
+/// what the command does with no flags.
+#[derive(Debug, Clone)]
+pub struct RecallOptions {
+    pub queries_file: Option<PathBuf>,
+    pub fact_type: Vec<String>,
+    pub budget: String,
+    pub max_tokens: Option<i64>,
+    pub trace: bool,
+    pub include_chunks: bool,
+    pub chunk_max_tokens: i64,
+    pub context_filter: Option<String>,
+    pub document_id: Option<String>,
+    /// Collapse results at or above this similarity (`--dedupe`)
+    pub dedupe_similarity: Option<f64>,
+    /// Client-side order and its recency weight
+    pub rerank: Option<(RecallRerank, f64)>,
+    pub template: Option<String>,
+    pub show_overrides: bool,
+    pub trace_csv: Option<PathBuf>,
+    pub stdin_json: bool,
+    /// Measured runs and warmup runs for `--benchmark`
+    pub benchmark: Option<(usize, usize)>,
+    pub explain_scores: bool,
+    pub fail_if_empty: bool,
+}
+
+impl Default for RecallOptions {
+    fn default() -> Self {
+        Self {
+            queries_file: None,
+            fact_type: DEFAULT_FACT_TYPES.iter().map(|t| t.to_string()).collect(),
+            budget: DEFAULT_BUDGET.to_string(),
+            max_tokens: None,
+            trace: false,
+            include_chunks: false,
+            chunk_max_tokens: DEFAULT_CHUNK_MAX_TOKENS,
+            context_filter: None,
+            document_id: None,
+            dedupe_similarity: None,
+            rerank: None,
+            template: None,
+            show_overrides: false,
+            trace_csv: None,
+            stdin_json: false,
+            benchmark: None,
+            explain_scores: false,
+            fail_if_empty: false,
+        }
+    }
+}
+
+pub fn recall(
+    client: &ApiClient,
+    agent_id: &str,
+    mut queries: Vec<String>,
+    options: RecallOptions,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let RecallOptions {
+        queries_file,
+        fact_type,
+        budget,
+        max_tokens,
+        trace,
+        include_chunks,
+        chunk_max_tokens,
+        context_filter,
+        document_id,
+        dedupe_similarity,
+        rerank,
+        template,
+        show_overrides,
+        trace_csv,
+        stdin_json,
+        benchmark,
+        explain_scores,
+        fail_if_empty,
+    } = options;
+    if let Some(path) = &queries_file {
+        queries.extend(load_queries_file(path)?);
+    }
+    if queries.is_empty() && !stdin_json {
+        anyhow::bail!("No queries to run");
+    }
 
     // Build include options if chunks are requested
     let include = if include_chunks {
@@ -262,34 +606,523 @@ pub fn recall(
         None
     };
 
-    let request = RecallRequest {
-        query,
-        types: if fact_type.is_empty() { None } else { Some(fact_type) },
-        budget: Some(parse_budget(&budget)),
-        max_tokens,
-        trace,
-        query_timestamp: None,
-        include,
-        tags: None,
-        tags_match: TagsMatch::Any,
+    let budget = parse_budget(&budget);
+    let max_tokens = resolve_max_tokens(max_tokens, &budget, verbose);
+    let mut trace = trace;
+    let mut include_chunks = include_chunks;
+    let requests: Vec<RecallRequest> = if stdin_json {
+        // The piped request decides what to show, plus whatever the flags ask for
+        let mut request: RecallRequest = read_stdin_request()?;
+        trace |= request.trace;
+        include_chunks |= request.include.as_ref().is_some_and(|i| i.chunks.is_some());
+        request.trace = trace || trace_csv.is_some();
+        vec![request]
+    } else {
+        queries
+            .into_iter()
+            .map(|query| RecallRequest {
+                query,
+                types: if fact_type.is_empty() { None } else { Some(fact_type.clone()) },
+                max_tokens,
+                budget: Some(budget),
+                // The CSV row needs the server-side timings
+                trace: trace || trace_csv.is_some(),
+                query_timestamp: None,
+                include: include.clone(),
+                tags: None,
+                tags_match: TagsMatch::Any,
+            })
+            .collect()
     };
+    let batch = requests.len() > 1;
+    if let Some((runs, warmup)) = benchmark {
+        let [request] = <[RecallRequest; 1]>::try_from(requests)
+            .map_err(|_| anyhow::anyhow!("--benchmark runs a single query; pass one QUERY"))?;
+        return benchmark_recall(client, agent_id, request, runs, warmup, verbose, output_format);
+    }
+    let mut responses = Vec::with_capacity(requests.len());
+
+    for request in requests {
+        let mut spinner = match (&template, batch) {
+            (Some(_), _) => ui::SpinnerGuard::none(),
+            (None, true) => ui::spinner_guard(output_format, &format!("Recalling memories for '{}'...", request.query)),
+            (None, false) => ui::spinner_guard(output_format, "Recalling memories..."),
+        };
+
+        if show_overrides {
+            print_overrides(&request_overrides(&request, serde_json::json!({ "query": request.query }))?);
+        }
+
+        let started = std::time::Instant::now();
+        let response = client.recall(agent_id, &request, verbose);
+        let client_elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+        spinner.finish();
+
+        let mut result = response?;
+
+        if let Some(path) = &trace_csv {
+            let trace: TraceInfo = result
+                .trace
+                .clone()
+                .and_then(|t| serde_json::from_value(serde_json::Value::Object(t)).ok())
+                .unwrap_or(TraceInfo { total_time: None, activation_count: None });
+            let row = TraceRow {
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                bank_id: agent_id,
+                query: &request.query,
+                budget: request.budget.map(|b| b.to_string()).unwrap_or_default(),
+                trace,
+                client_elapsed_ms,
+            };
+            append_trace_csv(path, &row)
+                .with_context(|| format!("Failed to write trace CSV: {}", path.display()))?;
+        }
+
+        if let Some(needle) = &context_filter {
+            let removed = filter_by_context(&mut result.results, needle);
+            // Reported on stderr so JSON/YAML/template output stays clean
+            eprintln!(
+                "{}",
+                ui::dim(&format!("Filtered out {} result(s) not matching context '{}'", removed, needle))
+            );
+        }
+
+        if let Some(document_id) = &document_id {
+            let removed = filter_by_document(&mut result.results, document_id);
+            eprintln!(
+                "{}",
+                ui::dim(&format!("Filtered out {} result(s) from other documents than '{}'", removed, document_id))
+            );
+        }
+
+        if let Some(threshold) = dedupe_similarity {
+            let merged = dedupe_results(&mut result.results, threshold);
+            eprintln!("{}", ui::dim(&format!("Merged {} duplicate result(s)", merged)));
+        }
 
-    let response = client.recall(agent_id, &request, verbose);
+        if let Some((order, recency_weight)) = rerank {
+            rerank_results(&mut result.results, order, recency_weight);
+        }
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
+        responses.push((request.query, result));
     }
 
-    match response {
-        Ok(result) => {
-            if output_format == OutputFormat::Pretty {
-                ui::print_search_results(&result, trace, include_chunks);
+    if fail_if_empty && responses.iter().all(|(_, result)| result.results.is_empty()) {
+        errors::report_empty_results();
+    }
+
+    if let Some(template) = &template {
+        for (_, result) in &responses {
+            output::print_template(template, &result.results)?;
+        }
+    } else if output_format == OutputFormat::Raw {
+        for fact in responses.iter().flat_map(|(_, result)| &result.results) {
+            output::print_raw(&fact.text);
+        }
+    } else if output_format == OutputFormat::Pretty {
+        pager::start(output_format);
+        for (query, result) in &responses {
+            if batch {
+                ui::print_section_header(&format!("Query: {}", query));
+            }
+            ui::print_search_results(result, trace, include_chunks);
+            if explain_scores {
+                print_score_explanations(&explain(&result.results))?;
+            }
+        }
+    } else if batch {
+        let grouped: Vec<QueryResults> = responses
+            .into_iter()
+            .map(|(query, result)| QueryResults {
+                query,
+                score_explanation: explain_scores.then(|| explain(&result.results)),
+                results: result.results,
+            })
+            .collect();
+        output::print_output(&grouped, output_format)?;
+    } else if let Some((_, result)) = responses.first() {
+        if explain_scores {
+            let mut value = serde_json::to_value(result)?;
+            if let Some(fields) = value.as_object_mut() {
+                fields.insert("score_explanation".to_string(), serde_json::to_value(explain(&result.results))?);
+            }
+            output::print_output(&value, output_format)?;
+        } else {
+            output::print_output(result, output_format)?;
+        }
+    }
+    Ok(())
+}
+
+const TRACE_CSV_HEADER: &str = "timestamp,bank_id,query,budget,total_time_ms,activation_count,client_elapsed_ms";
+
+/// One recall's timings for `--trace-csv`
+struct TraceRow<'a> {
+    timestamp: String,
+    bank_id: &'a str,
+    query: &'a str,
+    budget: String,
+    trace: TraceInfo,
+    client_elapsed_ms: f64,
+}
+
+/// Append a row to the trace CSV, writing the header first when the file is new or empty
+fn append_trace_csv(path: &Path, row: &TraceRow) -> Result<()> {
+    use std::io::Write;
+
+    let needs_header = fs::metadata(path).map(|m| m.len() == 0).unwrap_or(true);
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+    if needs_header {
+        writeln!(file, "{}", TRACE_CSV_HEADER)?;
+    }
+    writeln!(
+        file,
+        "{},{},{},{},{},{},{:.1}",
+        row.timestamp,
+        csv_field(row.bank_id),
+        csv_field(row.query),
+        row.budget,
+        row.trace.total_time.map(|t| t.to_string()).unwrap_or_default(),
+        row.trace.activation_count.map(|c| c.to_string()).unwrap_or_default(),
+        row.client_elapsed_ms
+    )?;
+    Ok(())
+}
+
+const BATCH_CSV_HEADER: &str = "query,rank,result_id,activation,result_count";
+
+/// Read a CSV file into records, honouring quoted fields (with `""` escapes and
+/// embedded newlines). Blank lines are skipped.
+fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if field.is_empty() => quoted = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                }
+                record.clear();
+            }
+            (c, _) => field.push(c),
+        }
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push(record);
+    }
+    records
+}
+
+/// The non-blank values of `column` in a CSV file with a header row
+fn load_batch_queries(path: &Path, column: &str) -> Result<Vec<String>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read queries file: {}", path.display()))?;
+    let mut records = parse_csv(&content).into_iter();
+    let header = records.next().unwrap_or_default();
+    let index = header
+        .iter()
+        .position(|name| name.trim() == column)
+        .with_context(|| format!("{} has no '{}' column (columns: {})", path.display(), column, header.join(", ")))?;
+    Ok(records
+        .filter_map(|record| record.get(index).map(|q| q.trim().to_string()))
+        .filter(|query| !query.is_empty())
+        .collect())
+}
+
+/// Rows for one query: its top `top` results, or a single empty row when nothing matched
+fn batch_rows(query: &str, results: &[RecallResult], top: usize) -> Vec<String> {
+    let count = results.len();
+    if results.is_empty() {
+        return vec![format!("{},,,,0", csv_field(query))];
+    }
+    results
+        .iter()
+        .take(top)
+        .enumerate()
+        .map(|(i, fact)| {
+            format!(
+                "{},{},{},{},{}",
+                csv_field(query),
+                i + 1,
+                csv_field(&fact.id),
+                ui::fact_activation(fact).map(|a| a.to_string()).unwrap_or_default(),
+                count
+            )
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+struct RecallBatchReport {
+    output: String,
+    queries: usize,
+    rows: usize,
+    #[serde(flatten)]
+    outcome: BulkOutcome,
+}
+
+/// Run every query in the `column` of a CSV file and write one CSV row per
+/// (query, result) to `output_path`, keeping the input order. Failed queries
+/// get no rows and are reported at the end.
+pub fn recall_batch(
+    client: &ApiClient,
+    agent_id: &str,
+    input: PathBuf,
+    output_path: PathBuf,
+    column: String,
+    top: usize,
+    fact_type: Vec<String>,
+    budget: String,
+    max_tokens: Option<i64>,
+    concurrency: usize,
+    error_mode: ErrorMode,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let queries = load_batch_queries(&input, &column)?;
+    if queries.is_empty() {
+        anyhow::bail!("No queries found in the '{}' column of {}", column, input.display());
+    }
+
+    let budget = parse_budget(&budget);
+    let max_tokens = resolve_max_tokens(max_tokens, &budget, verbose);
+    let recall_one = |query: &String| {
+        let request = RecallRequest {
+            query: query.clone(),
+            types: if fact_type.is_empty() { None } else { Some(fact_type.clone()) },
+            max_tokens,
+            budget: Some(budget),
+            trace: false,
+            query_timestamp: None,
+            include: None,
+            tags: None,
+            tags_match: TagsMatch::Any,
+        };
+        with_retries(|| client.recall(agent_id, &request, verbose))
+    };
+
+    let pb = ui::create_progress_bar(queries.len() as u64, "Recalling");
+    let mut outcome = BulkOutcome::new(error_mode);
+    let mut lines = vec![BATCH_CSV_HEADER.to_string()];
+    run_ordered(&queries, concurrency, recall_one, |query, result| {
+        pb.inc(1);
+        match result {
+            Ok(response) => {
+                lines.extend(batch_rows(query, &response.results, top));
+                outcome.success();
+            }
+            Err(e) => outcome.failure(format!("Query '{}'", query), e)?,
+        }
+        Ok(())
+    })?;
+    pb.finish_and_clear();
+
+    fs::write(&output_path, lines.join("\n") + "\n")
+        .with_context(|| format!("Failed to write results CSV: {}", output_path.display()))?;
+
+    let report = RecallBatchReport {
+        output: output_path.display().to_string(),
+        queries: queries.len(),
+        rows: lines.len() - 1,
+        outcome,
+    };
+    if output_format == OutputFormat::Pretty {
+        let message = format!("Wrote {} row(s) for {} queries to {}", report.rows, report.queries, report.output);
+        if report.outcome.has_failures() {
+            ui::print_warning(&message);
+        } else {
+            ui::print_success(&message);
+        }
+    } else {
+        output::print_output(&report, output_format)?;
+    }
+    report.outcome.print_summary("Batch recall");
+    report.outcome.finish("Batch recall")
+}
+
+/// Describe the fields of `request` that differ from the defaults the server would apply
+/// to a request built from only its `required` fields, e.g. "max_tokens: 2000 (default 4096)"
+fn request_overrides<T: Serialize + DeserializeOwned>(request: &T, required: serde_json::Value) -> Result<Vec<String>> {
+    let actual = serde_json::to_value(request)?;
+    let defaults = serde_json::to_value(serde_json::from_value::<T>(required.clone())?)?;
+
+    let empty = serde_json::Map::new();
+    let actual = actual.as_object().unwrap_or(&empty);
+    let defaults = defaults.as_object().unwrap_or(&empty);
+    let required = required.as_object().unwrap_or(&empty);
+
+    Ok(actual
+        .iter()
+        .filter(|(name, _)| !required.contains_key(*name))
+        .filter(|(name, value)| defaults.get(*name) != Some(*value))
+        .map(|(name, value)| match defaults.get(name) {
+            Some(default) => format!("{}: {} (default {})", name, value, default),
+            None => format!("{}: {} (default unset)", name, value),
+        })
+        .collect())
+}
+
+fn print_overrides(overrides: &[String]) {
+    // Diagnostic output goes to stderr, like --verbose
+    eprintln!("{}", ui::dim("Request overrides:"));
+    if overrides.is_empty() {
+        eprintln!("  {}", ui::dim("(none)"));
+    }
+    for line in overrides {
+        eprintln!("  {}", line);
+    }
+    eprintln!();
+}
+
+/// Keep only results whose context contains `needle` (case-insensitive); returns how many were dropped
+fn filter_by_context(results: &mut Vec<RecallResult>, needle: &str) -> usize {
+    let needle = needle.to_lowercase();
+    let before = results.len();
+    results.retain(|fact| {
+        fact.context
+            .as_deref()
+            .is_some_and(|context| context.to_lowercase().contains(&needle))
+    });
+    before - results.len()
+}
+
+/// Keep only results derived from `document_id`; returns how many were dropped.
+/// Recall has no server-side document filter, so this narrows the ranked results locally.
+fn filter_by_document(results: &mut Vec<RecallResult>, document_id: &str) -> usize {
+    let before = results.len();
+    results.retain(|fact| fact.document_id.as_deref() == Some(document_id));
+    before - results.len()
+}
+
+/// Lowercased text with whitespace collapsed, so formatting differences don't matter
+fn normalize_fact_text(text: &str) -> Vec<char> {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+        .chars()
+        .collect()
+}
+
+/// 1.0 for identical texts, falling to 0.0 as the Levenshtein distance approaches the longer length
+fn text_similarity(a: &[char], b: &[char]) -> f64 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - crate::suggest::edit_distance(a, b) as f64 / longest as f64
+}
+
+/// Drop results whose text matches an earlier one at `threshold` similarity or above
+/// (1.0 = exact match after normalization). Results arrive ranked, so the best-ranked
+/// copy is the one kept. Returns how many were merged.
+fn dedupe_results(results: &mut Vec<RecallResult>, threshold: f64) -> usize {
+    let before = results.len();
+    let mut kept: Vec<Vec<char>> = Vec::new();
+    results.retain(|fact| {
+        let text = normalize_fact_text(&fact.text);
+        let duplicate = kept.iter().any(|other| {
+            if threshold >= 1.0 {
+                *other == text
             } else {
-                output::print_output(&result, output_format)?;
+                text_similarity(other, &text) >= threshold
             }
-            Ok(())
+        });
+        if !duplicate {
+            kept.push(text);
+        }
+        !duplicate
+    });
+    before - results.len()
+}
+
+/// Client-side ordering for `memory recall --rerank`
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum RecallRerank {
+    /// Server ranking (spreading activation), unchanged
+    Activation,
+    /// Newest event date first
+    Recency,
+    /// Weighted mix of server rank and recency (see --recency-weight)
+    Blend,
+}
+
+/// When a fact happened: its event start, else its end, as a Unix timestamp
+fn event_timestamp(fact: &RecallResult) -> Option<i64> {
+    let raw = fact.occurred_start.as_deref().or(fact.occurred_end.as_deref())?;
+    crate::timestamp::parse(raw).as_utc().map(|dt| dt.timestamp())
+}
+
+/// Reorder dated facts among the positions they already hold; undated facts stay put.
+/// Activation is the server rank normalized to 1.0 (first) .. 0.0 (last), recency the
+/// event date normalized across the dated facts. Ties keep the server order.
+fn rerank_results(results: &mut [RecallResult], order: RecallRerank, recency_weight: f64) {
+    let dated: Vec<(usize, i64)> = results
+        .iter()
+        .enumerate()
+        .filter_map(|(i, fact)| event_timestamp(fact).map(|t| (i, t)))
+        .collect();
+    if order == RecallRerank::Activation || dated.len() < 2 {
+        return;
+    }
+
+    let last = (results.len() - 1) as f64;
+    let oldest = dated.iter().map(|&(_, t)| t).min().unwrap_or(0);
+    let newest = dated.iter().map(|&(_, t)| t).max().unwrap_or(0);
+    let score = |i: usize, t: i64| {
+        let recency = if newest > oldest { (t - oldest) as f64 / (newest - oldest) as f64 } else { 1.0 };
+        let activation = 1.0 - i as f64 / last;
+        match order {
+            RecallRerank::Activation => activation,
+            RecallRerank::Recency => recency,
+            RecallRerank::Blend => recency_weight * recency + (1.0 - recency_weight) * activation,
+        }
+    };
+
+    let mut ranked: Vec<(usize, f64)> = dated.iter().map(|&(i, t)| (i, score(i, t))).collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let reordered: Vec<RecallResult> = ranked.iter().map(|&(i, _)| results[i].clone()).collect();
+    for (&(slot, _), fact) in dated.iter().zip(reordered) {
+        results[slot] = fact;
+    }
+}
+
+/// JSON/YAML shape of `memory reflect`. Unlike the generated response type,
+/// every key is always present (null when the server omitted it), so scripts
+/// can rely on the envelope regardless of which options were requested.
+#[derive(Debug, Serialize)]
+struct ReflectEnvelope<'a> {
+    text: &'a str,
+    based_on: Option<&'a hindsight_client::types::ReflectBasedOn>,
+    structured_output: Option<&'a serde_json::Map<String, serde_json::Value>>,
+    usage: Option<&'a hindsight_client::types::TokenUsage>,
+    trace: Option<&'a hindsight_client::types::ReflectTrace>,
+}
+
+impl<'a> From<&'a crate::api::ReflectResponse> for ReflectEnvelope<'a> {
+    fn from(response: &'a crate::api::ReflectResponse) -> Self {
+        Self {
+            text: &response.text,
+            based_on: response.based_on.as_ref(),
+            structured_output: response.structured_output.as_ref(),
+            usage: response.usage.as_ref(),
+            trace: response.trace.as_ref(),
         }
-        Err(e) => Err(e)
     }
 }
 
@@ -301,14 +1134,18 @@ pub fn reflect(
     context: Option<String>,
     max_tokens: Option<i64>,
     schema_path: Option<PathBuf>,
+    sources_only: bool,
+    trace: bool,
+    sampling: Sampling,
+    show_overrides: bool,
+    stdin_json: bool,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Reflecting..."))
-    } else {
-        None
-    };
+    let stdin_request: Option<ReflectRequest> = if stdin_json { Some(read_stdin_request()?) } else { None };
+    // A piped request that asks for tool calls gets the trace printed
+    let trace = trace || stdin_request.as_ref().and_then(|r| r.include.as_ref()).is_some_and(|i| i.tool_calls.is_some());
+    let mut spinner = ui::spinner_guard(output_format, "Reflecting...");
 
     // Load and parse schema if provided
     let response_schema = if let Some(path) = schema_path {
@@ -321,29 +1158,64 @@ pub fn reflect(
         None
     };
 
-    let request = ReflectRequest {
+    let budget = parse_budget(&budget);
+    let request = stdin_request.unwrap_or_else(|| ReflectRequest {
         query,
-        budget: Some(parse_budget(&budget)),
         context,
-        max_tokens: max_tokens.unwrap_or(4096),
-        include: None,
+        max_tokens: resolve_max_tokens(max_tokens, &budget, verbose),
+        budget: Some(budget),
+        // The server only returns the facts used and the call trace when asked to
+        include: if sources_only || trace {
+            Some(ReflectIncludeOptions {
+                facts: sources_only.then(|| FactsIncludeOptions(serde_json::Map::new())),
+                tool_calls: trace.then_some(ToolCallsIncludeOptions { output: false }),
+            })
+        } else {
+            None
+        },
         response_schema,
         tags: None,
         tags_match: TagsMatch::Any,
-    };
+        temperature: None,
+        top_p: None,
+        seed: None,
+    });
+
+    if show_overrides {
+        let mut overrides = request_overrides(&request, serde_json::json!({ "query": request.query }))?;
+        if let serde_json::Value::Object(fields) = serde_json::to_value(sampling)? {
+            overrides.extend(fields.iter().map(|(name, value)| format!("{}: {} (default unset)", name, value)));
+        }
+        print_overrides(&overrides);
+    }
 
-    let response = client.reflect(agent_id, &request, verbose);
+    let response = client.reflect(agent_id, &request, sampling, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(result) => {
-            if output_format == OutputFormat::Pretty {
+            if sources_only {
+                if output_format == OutputFormat::Pretty {
+                    pager::start(output_format);
+                    ui::print_reflect_sources(result.based_on.as_ref());
+                } else if output_format == OutputFormat::Raw {
+                    for memory in result.based_on.iter().flat_map(|b| &b.memories) {
+                        output::print_raw(&memory.text);
+                    }
+                } else {
+                    output::print_output(&serde_json::json!({ "based_on": result.based_on }), output_format)?;
+                }
+            } else if output_format == OutputFormat::Raw {
+                output::print_raw(&result.text);
+            } else if output_format == OutputFormat::Pretty {
+                pager::start(output_format);
                 ui::print_think_response(&result);
+                if trace {
+                    ui::print_reflect_trace(result.trace.as_ref(), sampling.seed);
+                }
             } else {
-                output::print_output(&result, output_format)?;
+                output::print_output(&ReflectEnvelope::from(&result), output_format)?;
             }
             Ok(())
         }
@@ -358,16 +1230,13 @@ pub fn retain(
     doc_id: Option<String>,
     context: Option<String>,
     r#async: bool,
+    idempotency_key: Option<String>,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
     let doc_id = doc_id.unwrap_or_else(config::generate_doc_id);
 
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Retaining memory..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Retaining memory...");
 
     let item = MemoryItem {
         content: content.clone(),
@@ -385,25 +1254,14 @@ pub fn retain(
         document_tags: None,
     };
 
-    let response = client.retain(agent_id, &request, r#async, verbose);
+    let response = client.retain(agent_id, &request, idempotency_key.as_deref(), r#async, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(result) => {
             if output_format == OutputFormat::Pretty {
-                ui::print_success(&format!(
-                    "Memory retained successfully (document: {})",
-                    doc_id
-                ));
-                if result.is_async {
-                    println!("  Status: queued for background processing");
-                    println!("  Items: {}", result.items_count);
-                } else {
-                    println!("  Stored count: {}", result.items_count);
-                }
+                print_retain_result(&result, agent_id, &doc_id);
             } else {
                 output::print_output(&result, output_format)?;
             }
@@ -413,21 +1271,347 @@ pub fn retain(
     }
 }
 
-pub fn retain_files(
-    client: &ApiClient,
-    agent_id: &str,
-    path: PathBuf,
-    recursive: bool,
-    context: Option<String>,
-    r#async: bool,
-    verbose: bool,
-    output_format: OutputFormat,
-) -> Result<()> {
-    if !path.exists() {
-        anyhow::bail!("Path does not exist: {}", path.display());
-    }
+/// How `memory retain --from-markdown` splits a file into memory units
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum MarkdownSplit {
+    /// One unit per H2 (`## `) section
+    Heading,
+    /// One unit per blank-line separated paragraph
+    Paragraph,
+}
 
-    let mut files = Vec::new();
+/// One memory unit cut from a Markdown file
+#[derive(Debug, PartialEq)]
+struct MarkdownUnit {
+    /// Nearest heading above the unit (its H2 section in heading mode)
+    heading: Option<String>,
+    content: String,
+}
+
+/// Heading text if `line` is an ATX heading of `level` (any level when None)
+fn markdown_heading(line: &str, level: Option<usize>) -> Option<String> {
+    let trimmed = line.trim_start();
+    let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+    let rest = &trimmed[hashes..];
+    let matches_level = level.map_or(hashes <= 6, |level| hashes == level);
+    if hashes == 0 || !matches_level || !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+    Some(rest.trim().trim_end_matches('#').trim().to_string())
+}
+
+/// Split Markdown into units. Headings and blank lines inside fenced code blocks
+/// are treated as content, so code samples are never cut in half.
+fn split_markdown(text: &str, split_by: MarkdownSplit) -> Vec<MarkdownUnit> {
+    let mut units = Vec::new();
+    let mut heading: Option<String> = None;
+    let mut current: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+
+    fn flush(units: &mut Vec<MarkdownUnit>, heading: &Option<String>, current: &mut Vec<&str>) {
+        let content = current.join("\n").trim().to_string();
+        current.clear();
+        if !content.is_empty() {
+            units.push(MarkdownUnit { heading: heading.clone(), content });
+        }
+    }
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            current.push(line);
+            continue;
+        }
+        if in_fence {
+            current.push(line);
+            continue;
+        }
+
+        match split_by {
+            MarkdownSplit::Heading => {
+                if let Some(title) = markdown_heading(line, Some(2)) {
+                    flush(&mut units, &heading, &mut current);
+                    heading = Some(title);
+                } else {
+                    current.push(line);
+                }
+            }
+            MarkdownSplit::Paragraph => {
+                if let Some(title) = markdown_heading(line, None) {
+                    flush(&mut units, &heading, &mut current);
+                    heading = Some(title);
+                } else if line.trim().is_empty() {
+                    flush(&mut units, &heading, &mut current);
+                } else {
+                    current.push(line);
+                }
+            }
+        }
+    }
+    flush(&mut units, &heading, &mut current);
+
+    units
+}
+
+/// Retain a Markdown file as one document, split into a unit per section or paragraph
+pub fn retain_markdown(
+    client: &ApiClient,
+    agent_id: &str,
+    path: PathBuf,
+    split_by: MarkdownSplit,
+    doc_id: Option<String>,
+    context: Option<String>,
+    r#async: bool,
+    idempotency_key: Option<String>,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let text = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read Markdown file: {}", path.display()))?;
+    let units = split_markdown(&text, split_by);
+    if units.is_empty() {
+        ui::print_warning(&format!("No content found in {}", path.display()));
+        return Ok(());
+    }
+
+    let doc_id = doc_id.unwrap_or_else(config::generate_doc_id);
+    let unit_count = units.len();
+    let mut items: Vec<MemoryItem> = units
+        .into_iter()
+        .map(|unit| MemoryItem {
+            content: unit.content,
+            context: unit.heading,
+            metadata: None,
+            timestamp: None,
+            document_id: Some(doc_id.clone()),
+            entities: None,
+            tags: None,
+        })
+        .collect();
+    // --context covers text that sits above the first heading
+    if let Some(context) = &context {
+        apply_default_context(&mut items, context);
+    }
+
+    let mut spinner = ui::spinner_guard(output_format, &format!("Retaining {} unit(s)...", unit_count));
+
+    let request = RetainRequest {
+        items,
+        async_: r#async,
+        document_tags: None,
+    };
+
+    let response = client.retain(agent_id, &request, idempotency_key.as_deref(), r#async, verbose);
+
+    spinner.finish();
+
+    let result = response?;
+    if output_format == OutputFormat::Pretty {
+        ui::print_info(&format!("Split {} into {} unit(s)", path.display(), unit_count));
+        print_retain_result(&result, agent_id, &doc_id);
+    } else {
+        output::print_output(&result, output_format)?;
+    }
+    Ok(())
+}
+
+/// Aggregated outcome of a batch retain that may span several requests. The
+/// single-request result fields come first, so existing consumers keep working;
+/// `operation_id` is the first chunk's operation and `operation_ids` has them all.
+#[derive(Debug, Serialize)]
+struct ChunkedRetainReport {
+    success: bool,
+    items_count: i64,
+    message: String,
+    is_async: bool,
+    operation_id: Option<String>,
+    chunks: usize,
+    operation_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    document_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_context_applied: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duplicates_removed: Option<usize>,
+}
+
+/// Render a retain result so sync and async outcomes can't be confused
+fn print_retain_result(result: &MemoryPutResult, bank_id: &str, doc_id: &str) {
+    match (&result.operation_id, result.is_async) {
+        (Some(operation_id), true) => {
+            ui::print_success(&format!("Submitted, operation id: {}", operation_id));
+            println!("  {} {}", ui::dim("Document:"), doc_id);
+            println!("  {} {}", ui::dim("Items queued:"), result.items_count);
+            println!();
+            println!(
+                "{}",
+                ui::dim(&format!("Use 'hindsight operation get {} {}' to check progress.", bank_id, operation_id))
+            );
+        }
+        _ => {
+            ui::print_success(&format!("Stored {} memories", result.items_count));
+            println!("  {} {}", ui::dim("Document:"), doc_id);
+        }
+    }
+}
+
+impl ChunkedRetainReport {
+    fn new(chunks: usize, is_async: bool) -> Self {
+        ChunkedRetainReport {
+            success: true,
+            items_count: 0,
+            message: "Stored 0 memory units".to_string(),
+            is_async,
+            operation_id: None,
+            chunks,
+            operation_ids: Vec::new(),
+            document_id: None,
+            default_context_applied: None,
+            duplicates_removed: None,
+        }
+    }
+
+    /// Add one chunk's result
+    fn record(&mut self, result: MemoryPutResult) {
+        self.success &= result.success;
+        self.items_count += result.items_count;
+        self.operation_ids.extend(result.operation_id);
+        self.operation_id = self.operation_ids.first().cloned();
+        self.message = self.summary();
+    }
+
+    /// Note a chunk that was not submitted
+    fn record_failure(&mut self) {
+        self.success = false;
+        self.message = self.summary();
+    }
+
+    fn summary(&self) -> String {
+        if self.success {
+            format!("Stored {} memory units", self.items_count)
+        } else {
+            format!("Stored {} memory units; not every chunk was accepted", self.items_count)
+        }
+    }
+}
+
+/// 1-based inclusive item ranges covered by each chunk
+fn chunk_ranges(total: usize, chunk_size: usize) -> Vec<(usize, usize)> {
+    let chunk_size = chunk_size.max(1);
+    (0..total)
+        .step_by(chunk_size)
+        .map(|start| (start + 1, (start + chunk_size).min(total)))
+        .collect()
+}
+
+/// Set `context` on every item that lacks its own; returns how many were updated
+fn apply_default_context(items: &mut [MemoryItem], context: &str) -> usize {
+    let mut applied = 0;
+    for item in items.iter_mut().filter(|item| item.context.is_none()) {
+        item.context = Some(context.to_string());
+        applied += 1;
+    }
+    applied
+}
+
+/// Drop items whose content and context repeat an earlier item, keeping the first;
+/// returns how many were dropped
+fn dedupe_items(items: &mut Vec<MemoryItem>) -> usize {
+    let mut seen = HashSet::new();
+    let before = items.len();
+    items.retain(|item| seen.insert((item.content.clone(), item.context.clone())));
+    before - items.len()
+}
+
+/// Submit `items` sequentially in chunks of at most `chunk_size`, so large imports
+/// stay under server body limits. Each item keeps its own `document_id`, so a
+/// document split across chunks still coalesces server-side.
+fn retain_in_chunks(
+    client: &ApiClient,
+    agent_id: &str,
+    items: Vec<MemoryItem>,
+    chunk_size: usize,
+    r#async: bool,
+    idempotency_key: Option<&str>,
+    outcome: &mut BulkOutcome,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<ChunkedRetainReport> {
+    let total_items = items.len();
+    let ranges = chunk_ranges(total_items, chunk_size);
+    let total_chunks = ranges.len();
+
+    let mut report = ChunkedRetainReport::new(total_chunks, r#async);
+
+    let mut remaining = items.into_iter();
+    for (i, (first, last)) in ranges.into_iter().enumerate() {
+        let chunk_index = i + 1;
+        let chunk: Vec<MemoryItem> = remaining.by_ref().take(last - first + 1).collect();
+
+        let message = if total_chunks > 1 {
+            format!("Submitting chunk {}/{} (items {}-{})...", chunk_index, total_chunks, first, last)
+        } else {
+            format!("Submitting {} item(s)...", chunk.len())
+        };
+        let mut spinner = ui::spinner_guard(output_format, &message);
+
+        let request = RetainRequest {
+            items: chunk,
+            async_: r#async,
+            document_tags: None,
+        };
+
+        // A user-supplied key must still differ per chunk, or later chunks would be dropped
+        let chunk_key = match idempotency_key {
+            Some(key) if total_chunks > 1 => Some(format!("{}-{}", key, chunk_index)),
+            other => other.map(str::to_string),
+        };
+        // Safe to retry: the Idempotency-Key deduplicates a chunk the server already took
+        let response = with_retries(|| client.retain(agent_id, &request, chunk_key.as_deref(), r#async, verbose));
+
+        spinner.finish();
+
+        match response {
+            Ok(result) => {
+                outcome.success();
+                report.record(result);
+            }
+            Err(e) => {
+                report.record_failure();
+                let e = e.context(format!(
+                    "{} item(s) from earlier chunks were already submitted",
+                    report.items_count
+                ));
+                outcome.failure(
+                    format!("Chunk {}/{} (items {}-{} of {})", chunk_index, total_chunks, first, last, total_items),
+                    e,
+                )?;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+pub fn retain_files(
+    client: &ApiClient,
+    agent_id: &str,
+    path: PathBuf,
+    recursive: bool,
+    context: Option<String>,
+    r#async: bool,
+    chunk_size: usize,
+    idempotency_key: Option<String>,
+    error_mode: ErrorMode,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    if !path.exists() {
+        anyhow::bail!("Path does not exist: {}", path.display());
+    }
+
+    let mut files = Vec::new();
 
     if path.is_file() {
         files.push(path);
@@ -459,15 +1643,23 @@ pub fn retain_files(
         return Ok(());
     }
 
-    ui::print_info(&format!("Found {} files to import", files.len()));
+    // On stderr: with -o json/yaml, stdout is reserved for the final report
+    eprintln!("{}", ui::dim(&format!("Found {} files to import", files.len())));
 
     let pb = ui::create_progress_bar(files.len() as u64, "Processing files");
 
     let mut items = Vec::new();
+    let mut outcome = BulkOutcome::new(error_mode);
 
     for file_path in &files {
-        let content = fs::read_to_string(file_path)
-            .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+        let content = match fs::read_to_string(file_path) {
+            Ok(content) => content,
+            Err(e) => {
+                outcome.failure(file_path.display().to_string(), anyhow::Error::new(e).context("Failed to read file"))?;
+                pb.inc(1);
+                continue;
+            }
+        };
 
         let doc_id = file_path
             .file_stem()
@@ -491,80 +1683,291 @@ pub fn retain_files(
     pb.finish_with_message("Files processed");
 
     // Always use async mode for the API call
-    let request = RetainRequest {
-        items,
-        async_: true,
-        document_tags: None,
-    };
+    let report = retain_in_chunks(client, agent_id, items, chunk_size, true, idempotency_key.as_deref(), &mut outcome, verbose, output_format)?;
+
+    if r#async {
+        // User requested async mode - return immediately
+        if output_format == OutputFormat::Pretty {
+            ui::print_success("Files queued for processing");
+            println!("  Items: {}", report.items_count);
+            for op_id in &report.operation_ids {
+                println!("  Operation ID: {}", op_id);
+            }
+        } else {
+            output::print_output(&report, output_format)?;
+        }
+        outcome.print_summary("Retain");
+        return outcome.finish("Retain");
+    }
 
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Submitting retain request..."))
+    // Poll until every chunk's operation completes
+    for operation_id in &report.operation_ids {
+        let mut poll_spinner = ui::spinner_guard(output_format, "Processing memories...");
+
+        let (success, error_msg) = client.poll_operation(agent_id, operation_id, verbose)?;
+
+        poll_spinner.finish();
+
+        if !success {
+            let msg = error_msg.unwrap_or_else(|| "Unknown error".to_string());
+            outcome.failure(format!("Retain operation {}", operation_id), anyhow::anyhow!(msg))?;
+        }
+    }
+
+    if output_format == OutputFormat::Pretty {
+        if outcome.has_failures() {
+            ui::print_warning("Some files were not retained");
+        } else {
+            ui::print_success("Files retained successfully");
+        }
+        println!("  Items processed: {}", report.items_count);
+        if report.chunks > 1 {
+            println!("  Chunks: {}", report.chunks);
+        }
     } else {
-        None
-    };
+        output::print_output(&report, output_format)?;
+    }
+    outcome.print_summary("Retain");
+    outcome.finish("Retain")
+}
+
+/// Fields accepted on an imported memory record (mirrors `MemoryItem`)
+const IMPORT_FIELDS: &[&str] = &["content", "context", "document_id", "timestamp", "tags", "metadata", "entities"];
 
-    let response = client.retain(agent_id, &request, true, verbose);
+/// A problem with one record of an import file
+#[derive(Debug)]
+struct ImportIssue {
+    /// "line N" for JSONL files, "index N" for JSON arrays
+    location: String,
+    message: String,
+}
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
+/// Check a raw JSON record against the `MemoryItem` shape and convert it
+fn validate_import_record(mut value: serde_json::Value) -> std::result::Result<MemoryItem, String> {
+    if let Some(metadata) = value.get_mut("metadata").and_then(|m| m.as_object_mut()) {
+        stringify_metadata(metadata)?;
     }
+    let obj = value.as_object().ok_or("expected a JSON object")?;
 
-    match response {
-        Ok(result) => {
-            if r#async {
-                // User requested async mode - return immediately
-                if output_format == OutputFormat::Pretty {
-                    ui::print_success("Files queued for processing");
-                    println!("  Items: {}", result.items_count);
-                    if let Some(op_id) = &result.operation_id {
-                        println!("  Operation ID: {}", op_id);
-                    }
-                } else {
-                    output::print_output(&result, output_format)?;
-                }
-            } else {
-                // Poll until completion
-                if let Some(operation_id) = &result.operation_id {
-                    let poll_spinner = if output_format == OutputFormat::Pretty {
-                        Some(ui::create_spinner("Processing memories..."))
-                    } else {
-                        None
-                    };
+    for key in obj.keys() {
+        if !IMPORT_FIELDS.contains(&key.as_str()) {
+            return Err(format!("unknown field '{}' (expected one of: {})", key, IMPORT_FIELDS.join(", ")));
+        }
+    }
 
-                    let (success, error_msg) = client.poll_operation(agent_id, operation_id, verbose)?;
+    match obj.get("content") {
+        Some(serde_json::Value::String(content)) if !content.trim().is_empty() => {}
+        Some(serde_json::Value::String(_)) => return Err("'content' must not be empty".to_string()),
+        Some(_) => return Err("'content' must be a string".to_string()),
+        None => return Err("missing required field 'content'".to_string()),
+    }
 
-                    if let Some(mut sp) = poll_spinner {
-                        sp.finish();
-                    }
+    for key in ["context", "document_id"] {
+        if let Some(v) = obj.get(key) {
+            if !v.is_null() && !v.is_string() {
+                return Err(format!("'{}' must be a string", key));
+            }
+        }
+    }
 
-                    if success {
-                        if output_format == OutputFormat::Pretty {
-                            ui::print_success("Files retained successfully");
-                            println!("  Items processed: {}", result.items_count);
-                        } else {
-                            output::print_output(&result, output_format)?;
-                        }
-                    } else {
-                        let msg = error_msg.unwrap_or_else(|| "Unknown error".to_string());
-                        if output_format == OutputFormat::Pretty {
-                            ui::print_error(&format!("Retain operation failed: {}", msg));
-                        }
-                        anyhow::bail!("Retain operation failed: {}", msg);
-                    }
-                } else {
-                    // No operation ID returned, shouldn't happen with async=true
-                    if output_format == OutputFormat::Pretty {
-                        ui::print_success("Files retained successfully");
-                        println!("  Items processed: {}", result.items_count);
-                    } else {
-                        output::print_output(&result, output_format)?;
-                    }
+    if let Some(v) = obj.get("timestamp").filter(|v| !v.is_null()) {
+        let ts = v.as_str().ok_or("'timestamp' must be an RFC 3339 string")?;
+        crate::timestamp::parse(ts)
+            .as_utc()
+            .ok_or_else(|| format!("'timestamp' is not a valid date: {}", ts))?;
+    }
+
+    if let Some(v) = obj.get("tags").filter(|v| !v.is_null()) {
+        let ok = v.as_array().map(|tags| tags.iter().all(|t| t.is_string())).unwrap_or(false);
+        if !ok {
+            return Err("'tags' must be an array of strings".to_string());
+        }
+    }
+
+    if let Some(v) = obj.get("metadata").filter(|v| !v.is_null()) {
+        let ok = v.as_object().map(|m| m.values().all(|x| x.is_string())).unwrap_or(false);
+        if !ok {
+            return Err("'metadata' must be an object".to_string());
+        }
+    }
+
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+/// The server stores metadata values as strings, so numbers and booleans are sent
+/// as their text and null values are dropped. Nested values can't be represented.
+fn stringify_metadata(metadata: &mut serde_json::Map<String, serde_json::Value>) -> std::result::Result<(), String> {
+    metadata.retain(|_, value| !value.is_null());
+    for (key, value) in metadata.iter_mut() {
+        match value {
+            serde_json::Value::String(_) => {}
+            serde_json::Value::Number(_) | serde_json::Value::Bool(_) => *value = serde_json::Value::String(value.to_string()),
+            _ => return Err(format!("'metadata.{}' must be a string, number or boolean", key)),
+        }
+    }
+    Ok(())
+}
+
+/// Parse and validate an import file, collecting every invalid record.
+///
+/// `.jsonl`/`.ndjson` files hold one record per line; anything else is read as a
+/// JSON array of records. With `strict`, the first invalid record is an error.
+fn load_import_file(path: &Path, strict: bool) -> Result<(Vec<MemoryItem>, Vec<ImportIssue>)> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+
+    let is_jsonl = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| matches!(ext.to_lowercase().as_str(), "jsonl" | "ndjson"))
+        .unwrap_or(false);
+
+    let mut records: Vec<(String, std::result::Result<serde_json::Value, String>)> = Vec::new();
+    if is_jsonl {
+        for (i, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let parsed = serde_json::from_str(line).map_err(|e| format!("invalid JSON: {}", e));
+            records.push((format!("line {}", i + 1), parsed));
+        }
+    } else {
+        let value: serde_json::Value = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse JSON from: {}", path.display()))?;
+        let array = match value {
+            serde_json::Value::Array(array) => array,
+            _ => anyhow::bail!("Expected a JSON array of memory items in: {}", path.display()),
+        };
+        for (i, value) in array.into_iter().enumerate() {
+            records.push((format!("index {}", i), Ok(value)));
+        }
+    }
+
+    let mut items = Vec::new();
+    let mut issues = Vec::new();
+    for (location, parsed) in records {
+        match parsed.and_then(validate_import_record) {
+            Ok(item) => items.push(item),
+            Err(message) => {
+                if strict {
+                    anyhow::bail!("Invalid record at {}: {}", location, message);
                 }
+                issues.push(ImportIssue { location, message });
             }
-            Ok(())
         }
-        Err(e) => Err(e)
     }
+
+    Ok((items, issues))
+}
+
+/// Import memories from a JSON or JSONL file of memory items
+pub fn import(
+    client: &ApiClient,
+    agent_id: &str,
+    path: PathBuf,
+    strict: bool,
+    yes: bool,
+    r#async: bool,
+    chunk_size: usize,
+    document_id: Option<String>,
+    auto_document_id: bool,
+    default_context: Option<String>,
+    dedupe_within_batch: bool,
+    idempotency_key: Option<String>,
+    error_mode: ErrorMode,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let (mut items, issues) = load_import_file(&path, strict)?;
+
+    if !issues.is_empty() {
+        // Validation problems go to stderr so they never mix with JSON/YAML output
+        ui::print_error(&format!("{} invalid record(s) in {}:", issues.len(), path.display()));
+        for issue in &issues {
+            eprintln!("  {} {}", ui::dim(&format!("{}:", issue.location)), issue.message);
+        }
+        eprintln!();
+
+        if items.is_empty() {
+            anyhow::bail!("No valid records to import");
+        }
+
+        if !yes && output_format == OutputFormat::Pretty {
+            let message = format!(
+                "Proceed with the {} valid record(s) and skip {} invalid?",
+                items.len(),
+                issues.len()
+            );
+
+            let confirmed = ui::prompt_confirmation(&message)?;
+
+            if !confirmed {
+                ui::print_info("Import cancelled");
+                return Ok(());
+            }
+        }
+    }
+
+    if items.is_empty() {
+        ui::print_warning(&format!("No records found in {}", path.display()));
+        return Ok(());
+    }
+
+    // Group every item under one document; the same id is sent with each chunk
+    let document_id = document_id.or_else(|| auto_document_id.then(|| uuid::Uuid::new_v4().to_string()));
+    if let Some(doc_id) = &document_id {
+        for item in &mut items {
+            item.document_id = Some(doc_id.clone());
+        }
+    }
+
+    let context_applied = default_context
+        .as_deref()
+        .map(|context| apply_default_context(&mut items, context));
+    // After the default context, so records it made identical count as duplicates
+    let duplicates_removed = dedupe_within_batch.then(|| dedupe_items(&mut items));
+
+    let mut outcome = BulkOutcome::new(error_mode);
+    let mut report = retain_in_chunks(client, agent_id, items, chunk_size, r#async, idempotency_key.as_deref(), &mut outcome, verbose, output_format)?;
+    report.document_id = document_id;
+    report.default_context_applied = context_applied;
+    report.duplicates_removed = duplicates_removed;
+
+    if output_format == OutputFormat::Pretty {
+        if outcome.has_failures() {
+            ui::print_warning(&format!("Imported memories from {} with errors", path.display()));
+        } else {
+            ui::print_success(&format!("Imported memories from {}", path.display()));
+        }
+        if let Some(doc_id) = &report.document_id {
+            println!("  Document ID: {}", doc_id);
+        }
+        if let Some(count) = report.default_context_applied {
+            println!("  Default context applied to {} item(s)", count);
+        }
+        if let Some(count) = report.duplicates_removed {
+            println!("  Duplicates removed: {}", count);
+        }
+        if report.is_async {
+            println!("  Status: queued for background processing");
+            println!("  Items: {}", report.items_count);
+            for op_id in &report.operation_ids {
+                println!("  Operation ID: {}", op_id);
+            }
+        } else {
+            println!("  Stored count: {}", report.items_count);
+        }
+        if report.chunks > 1 {
+            println!("  Chunks: {}", report.chunks);
+        }
+        if !issues.is_empty() {
+            println!("  Skipped invalid: {}", issues.len());
+        }
+    } else {
+        output::print_output(&report, output_format)?;
+    }
+    outcome.print_summary("Import");
+    outcome.finish("Import")
 }
 
 pub fn delete(
@@ -574,17 +1977,11 @@ pub fn delete(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Deleting memory unit..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Deleting memory unit...");
 
     let response = client.delete_memory(agent_id, unit_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(result) => {
@@ -639,17 +2036,11 @@ pub fn clear(
         "Clearing all memories...".to_string()
     };
 
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner(&spinner_msg))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, &spinner_msg);
 
     let response = client.clear_memories(agent_id, fact_type.as_deref(), verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(result) => {
@@ -678,6 +2069,219 @@ mod tests {
     use super::*;
     use std::path::Path;
 
+    #[test]
+    fn test_recall_options_default_matches_cli() {
+        use clap::Parser;
+
+        let cli = crate::Cli::try_parse_from(["hindsight", "memory", "recall", "bank", "query"]).unwrap();
+        let crate::Commands::Memory(crate::MemoryCommands::Recall { fact_type, budget, chunk_max_tokens, .. }) = cli.command else {
+            panic!("expected memory recall");
+        };
+        let defaults = RecallOptions::default();
+        assert_eq!(fact_type, defaults.fact_type);
+        assert_eq!(budget, defaults.budget);
+        assert_eq!(chunk_max_tokens, defaults.chunk_max_tokens);
+    }
+
+    fn fact(text: &str, context: Option<&str>) -> RecallResult {
+        RecallResult {
+            chunk_id: None,
+            context: context.map(str::to_string),
+            document_id: None,
+            entities: None,
+            id: text.to_string(),
+            mentioned_at: None,
+            metadata: None,
+            occurred_end: None,
+            occurred_start: None,
+            tags: None,
+            text: text.to_string(),
+            type_: None,
+        }
+    }
+
+    #[test]
+    fn test_filter_by_context() {
+        let mut results = vec![
+            fact("a", Some("Work meeting")),
+            fact("b", Some("family dinner")),
+            fact("c", None),
+            fact("d", Some("WORK email")),
+        ];
+        let removed = filter_by_context(&mut results, "work");
+        assert_eq!(removed, 2);
+        let ids: Vec<&str> = results.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, ["a", "d"]);
+    }
+
+    #[test]
+    fn test_append_trace_csv_writes_header_once() {
+        let path = std::env::temp_dir().join(format!("hindsight-trace-{}.csv", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let row = |query| TraceRow {
+            timestamp: "2024-01-15T10:00:00+00:00".to_string(),
+            bank_id: "b",
+            query,
+            budget: "mid".to_string(),
+            trace: TraceInfo { total_time: Some(12.5), activation_count: Some(3) },
+            client_elapsed_ms: 20.04,
+        };
+        append_trace_csv(&path, &row("coffee")).unwrap();
+        append_trace_csv(&path, &row("tea, \"green\"")).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines[0], TRACE_CSV_HEADER);
+        assert_eq!(lines[1], "2024-01-15T10:00:00+00:00,b,coffee,mid,12.5,3,20.0");
+        assert_eq!(lines[2], r#"2024-01-15T10:00:00+00:00,b,"tea, ""green""",mid,12.5,3,20.0"#);
+        assert_eq!(lines.len(), 3);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_default_max_tokens_scales_with_budget() {
+        assert_eq!(default_max_tokens(&Budget::Low), 2048);
+        assert_eq!(default_max_tokens(&Budget::Mid), 4096);
+        assert_eq!(default_max_tokens(&Budget::High), 8192);
+        assert_eq!(resolve_max_tokens(Some(500), &Budget::High, false), 500);
+        assert_eq!(resolve_max_tokens(None, &Budget::Low, false), 2048);
+    }
+
+    #[test]
+    fn test_filter_by_document() {
+        let mut results = vec![fact("a", None), fact("b", None), fact("c", None)];
+        results[0].document_id = Some("doc-1".to_string());
+        results[1].document_id = Some("doc-2".to_string());
+        assert_eq!(filter_by_document(&mut results, "doc-1"), 2);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "a");
+    }
+
+    #[test]
+    fn test_dedupe_results_exact() {
+        let mut results = vec![
+            fact("Alice works at Google", None),
+            fact("alice  works at google", None),
+            fact("Bob works at Google", None),
+        ];
+        results[1].id = "dup".to_string();
+        assert_eq!(dedupe_results(&mut results, 1.0), 1);
+        let ids: Vec<&str> = results.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, ["Alice works at Google", "Bob works at Google"]);
+    }
+
+    #[test]
+    fn test_dedupe_results_similarity() {
+        let mut results = vec![
+            fact("Alice works at Google", None),
+            fact("Alice works at Google.", None),
+            fact("Alice likes hiking", None),
+        ];
+        assert_eq!(dedupe_results(&mut results, 0.9), 1);
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn test_text_similarity() {
+        let a = normalize_fact_text("kitten");
+        let b = normalize_fact_text("sitting");
+        // Distance 3 over 7 characters
+        assert!((text_similarity(&a, &b) - 4.0 / 7.0).abs() < 1e-9);
+        assert_eq!(text_similarity(&a, &a), 1.0);
+        assert_eq!(text_similarity(&[], &[]), 1.0);
+    }
+
+    #[test]
+    fn test_request_overrides() {
+        let request = RecallRequest {
+            query: "coffee".to_string(),
+            types: None,
+            budget: None,
+            max_tokens: 2000,
+            trace: false,
+            query_timestamp: None,
+            include: None,
+            tags: Some(vec!["work".to_string()]),
+            tags_match: TagsMatch::Any,
+        };
+        let overrides = request_overrides(&request, serde_json::json!({ "query": "coffee" })).unwrap();
+        assert_eq!(overrides, [r#"max_tokens: 2000 (default 4096)"#, r#"tags: ["work"] (default unset)"#]);
+    }
+
+    #[test]
+    fn test_dedupe_items_keeps_first_occurrence() {
+        let item = |content: &str, context: Option<&str>, document_id: &str| MemoryItem {
+            content: content.to_string(),
+            context: context.map(str::to_string),
+            document_id: Some(document_id.to_string()),
+            entities: None,
+            metadata: None,
+            tags: None,
+            timestamp: None,
+        };
+        let mut items = vec![
+            item("a", None, "1"),
+            item("a", Some("work"), "2"),
+            item("a", None, "3"),
+            item("b", None, "4"),
+            item("a", Some("work"), "5"),
+        ];
+        assert_eq!(dedupe_items(&mut items), 2);
+        let kept: Vec<_> = items.iter().map(|i| i.document_id.as_deref().unwrap()).collect();
+        assert_eq!(kept, ["1", "2", "4"]);
+    }
+
+    #[test]
+    fn test_apply_default_context_keeps_existing() {
+        let item = |context: Option<&str>| MemoryItem {
+            content: "x".to_string(),
+            context: context.map(str::to_string),
+            document_id: None,
+            entities: None,
+            metadata: None,
+            tags: None,
+            timestamp: None,
+        };
+        let mut items = vec![item(None), item(Some("own")), item(None)];
+        assert_eq!(apply_default_context(&mut items, "shared"), 2);
+        let contexts: Vec<_> = items.iter().map(|i| i.context.as_deref()).collect();
+        assert_eq!(contexts, [Some("shared"), Some("own"), Some("shared")]);
+    }
+
+    #[test]
+    fn test_chunk_ranges() {
+        assert_eq!(chunk_ranges(250, 100), vec![(1, 100), (101, 200), (201, 250)]);
+        assert_eq!(chunk_ranges(100, 100), vec![(1, 100)]);
+        assert_eq!(chunk_ranges(3, 0), vec![(1, 1), (2, 2), (3, 3)]);
+        assert!(chunk_ranges(0, 100).is_empty());
+    }
+
+    #[test]
+    fn test_chunked_retain_report_keeps_single_request_fields() {
+        let result = |items_count: i64, operation_id: &str| MemoryPutResult {
+            success: true,
+            items_count,
+            message: String::new(),
+            is_async: true,
+            operation_id: Some(operation_id.to_string()),
+        };
+        let mut report = ChunkedRetainReport::new(2, true);
+        report.record(result(100, "op-1"));
+        report.record(result(20, "op-2"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["success"], true);
+        assert_eq!(json["items_count"], 120);
+        assert_eq!(json["message"], "Stored 120 memory units");
+        assert_eq!(json["is_async"], true);
+        assert_eq!(json["operation_id"], "op-1");
+        assert_eq!(json["operation_ids"], serde_json::json!(["op-1", "op-2"]));
+
+        report.record_failure();
+        assert!(!report.success);
+        assert!(report.message.contains("not every chunk"));
+    }
+
     #[test]
     fn test_is_text_file_supported_extensions() {
         let supported = [
@@ -730,6 +2334,128 @@ mod tests {
         assert!(!is_text_file(Path::new("/path/to/image.png")));
     }
 
+    fn write_temp_file(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("hindsight-import-{}-{}", std::process::id(), name));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_validate_import_record_valid() {
+        let item = validate_import_record(serde_json::json!({
+            "content": "Alice works at Google",
+            "context": "career",
+            "timestamp": "2024-01-15T10:00:00Z",
+            "tags": ["user:alice"],
+            "metadata": {"source": "slack", "line": 12, "edited": false, "thread": null}
+        }))
+        .unwrap();
+        assert_eq!(item.content, "Alice works at Google");
+        assert_eq!(item.context.as_deref(), Some("career"));
+        assert!(item.timestamp.is_some());
+        let metadata = item.metadata.unwrap();
+        assert_eq!(metadata.len(), 3);
+        assert_eq!(metadata["line"], "12");
+        assert_eq!(metadata["edited"], "false");
+    }
+
+    #[test]
+    fn test_validate_import_record_errors() {
+        let cases = [
+            (serde_json::json!("just a string"), "expected a JSON object"),
+            (serde_json::json!({"context": "x"}), "missing required field 'content'"),
+            (serde_json::json!({"content": "  "}), "must not be empty"),
+            (serde_json::json!({"content": 42}), "'content' must be a string"),
+            (serde_json::json!({"content": "x", "text": "y"}), "unknown field 'text'"),
+            (serde_json::json!({"content": "x", "timestamp": "yesterday"}), "RFC 3339"),
+            (serde_json::json!({"content": "x", "tags": "a,b"}), "'tags' must be an array"),
+            (serde_json::json!({"content": "x", "metadata": "slack"}), "'metadata' must be an object"),
+            (serde_json::json!({"content": "x", "metadata": {"who": {"id": 1}}}), "'metadata.who' must be a string, number or boolean"),
+        ];
+        for (value, expected) in cases {
+            let err = validate_import_record(value).unwrap_err();
+            assert!(err.contains(expected), "expected '{}' in '{}'", expected, err);
+        }
+    }
+
+    #[test]
+    fn test_load_import_file_jsonl_collects_all_issues() {
+        let path = write_temp_file(
+            "collect.jsonl",
+            "{\"content\": \"one\"}\n\nnot json\n{\"context\": \"x\"}\n{\"content\": \"two\"}\n",
+        );
+        let (items, issues) = load_import_file(&path, false).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].location, "line 3");
+        assert_eq!(issues[1].location, "line 4");
+    }
+
+    #[test]
+    fn test_load_import_file_strict_aborts() {
+        let path = write_temp_file("strict.json", r#"[{"content": "ok"}, {"content": ""}]"#);
+        let err = load_import_file(&path, true).unwrap_err().to_string();
+        fs::remove_file(&path).ok();
+
+        assert!(err.contains("index 1"));
+    }
+
+    #[test]
+    fn test_parse_csv_handles_quotes_and_blank_lines() {
+        let records = parse_csv("id,query\r\n1,\"where, exactly\"\n\n2,\"say \"\"hi\"\"\nagain\"\n");
+        assert_eq!(
+            records,
+            vec![
+                vec!["id".to_string(), "query".to_string()],
+                vec!["1".to_string(), "where, exactly".to_string()],
+                vec!["2".to_string(), "say \"hi\"\nagain".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_batch_rows() {
+        let fact = |id: &str| -> RecallResult { serde_json::from_value(serde_json::json!({ "id": id, "text": "t" })).unwrap() };
+        let results = vec![fact("a"), fact("b"), fact("c")];
+
+        assert_eq!(batch_rows("q", &results, 2), vec!["q,1,a,,3", "q,2,b,,3"]);
+        assert_eq!(batch_rows("who, me", &[], 2), vec!["\"who, me\",,,,0"]);
+    }
+
+    #[test]
+    fn test_parse_request_names_the_offending_field() {
+        let request: RecallRequest = parse_request(r#"{"query": "alice", "budget": "low"}"#).unwrap();
+        assert_eq!(request.query, "alice");
+
+        let err = parse_request::<RecallRequest>(r#"{"query": "alice", "budget": "huge"}"#).unwrap_err().to_string();
+        assert!(err.contains("at 'budget'"), "{}", err);
+
+        let err = parse_request::<RecallRequest>(r#"{"budget": "low"}"#).unwrap_err().to_string();
+        assert!(err.contains("missing field `query`"), "{}", err);
+    }
+
+    #[test]
+    fn test_reflect_envelope_keys_are_stable() {
+        let minimal: crate::api::ReflectResponse = serde_json::from_value(serde_json::json!({"text": "hi"})).unwrap();
+        let value = serde_json::to_value(ReflectEnvelope::from(&minimal)).unwrap();
+        let mut keys: Vec<&str> = value.as_object().unwrap().keys().map(|k| k.as_str()).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["based_on", "structured_output", "text", "trace", "usage"]);
+        assert_eq!(value["text"], "hi");
+        assert!(value["based_on"].is_null());
+    }
+
+    #[test]
+    fn test_load_queries_file_skips_blanks_and_comments() {
+        let path = std::env::temp_dir().join(format!("hindsight-queries-{}.txt", std::process::id()));
+        fs::write(&path, "# team questions\nwhere does alice work\n\n  who leads infra  \n").unwrap();
+        let queries = load_queries_file(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(queries, ["where does alice work", "who leads infra"]);
+    }
+
     #[test]
     fn test_parse_budget_valid_values() {
         assert!(matches!(parse_budget("low"), Budget::Low));
@@ -752,4 +2478,111 @@ mod tests {
         assert!(matches!(parse_budget(""), Budget::Mid));
         assert!(matches!(parse_budget("unknown"), Budget::Mid));
     }
+
+    #[test]
+    fn test_split_markdown_by_heading() {
+        let text = "# Notes\nIntro line\n\n## Coffee\nLikes espresso.\n\nNo sugar.\n## Tea\n### Green\nSencha\n";
+        let units = split_markdown(text, MarkdownSplit::Heading);
+
+        assert_eq!(
+            units,
+            vec![
+                MarkdownUnit { heading: None, content: "# Notes\nIntro line".to_string() },
+                MarkdownUnit { heading: Some("Coffee".to_string()), content: "Likes espresso.\n\nNo sugar.".to_string() },
+                MarkdownUnit { heading: Some("Tea".to_string()), content: "### Green\nSencha".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_markdown_by_paragraph() {
+        let text = "## Coffee\nLikes espresso.\n\nNo sugar.\n\n```\n# not a heading\n\nstill code\n```\n";
+        let units = split_markdown(text, MarkdownSplit::Paragraph);
+
+        assert_eq!(units.len(), 3);
+        assert!(units.iter().all(|unit| unit.heading.as_deref() == Some("Coffee")));
+        assert_eq!(units[1].content, "No sugar.");
+        assert_eq!(units[2].content, "```\n# not a heading\n\nstill code\n```");
+    }
+
+    #[test]
+    fn test_markdown_heading() {
+        assert_eq!(markdown_heading("## Coffee ##", Some(2)), Some("Coffee".to_string()));
+        assert_eq!(markdown_heading("### Coffee", Some(2)), None);
+        assert_eq!(markdown_heading("#hashtag", None), None);
+    }
+
+    fn dated(text: &str, occurred_start: Option<&str>) -> RecallResult {
+        RecallResult {
+            occurred_start: occurred_start.map(str::to_string),
+            ..fact(text, None)
+        }
+    }
+
+    fn texts(results: &[RecallResult]) -> Vec<&str> {
+        results.iter().map(|r| r.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_rerank_by_recency_keeps_undated_positions() {
+        let mut results = vec![
+            dated("old", Some("2020-01-01T00:00:00Z")),
+            dated("undated", None),
+            dated("new", Some("2024-01-01T00:00:00Z")),
+            dated("mid", Some("2022-01-01T00:00:00")),
+        ];
+        rerank_results(&mut results, RecallRerank::Recency, 0.5);
+
+        assert_eq!(texts(&results), vec!["new", "undated", "mid", "old"]);
+    }
+
+    #[test]
+    fn test_rerank_blend_weights_server_rank() {
+        let results = vec![
+            dated("first", Some("2020-01-01T00:00:00Z")),
+            dated("second", Some("2021-01-01T00:00:00Z")),
+            dated("third", Some("2024-01-01T00:00:00Z")),
+        ];
+
+        let mut activation_heavy = results.clone();
+        rerank_results(&mut activation_heavy, RecallRerank::Blend, 0.2);
+        assert_eq!(texts(&activation_heavy), vec!["first", "second", "third"]);
+
+        let mut recency_heavy = results;
+        rerank_results(&mut recency_heavy, RecallRerank::Blend, 0.8);
+        assert_eq!(texts(&recency_heavy), vec!["third", "second", "first"]);
+    }
+
+    #[test]
+    fn test_explain_scores_relative_to_top() {
+        let scored = |id: &str, activation: Option<&str>| -> RecallResult {
+            serde_json::from_value(serde_json::json!({
+                "id": id,
+                "text": id,
+                "metadata": activation.map(|a| serde_json::json!({ "activation": a })),
+            }))
+            .unwrap()
+        };
+        let results = vec![scored("a", Some("0.4")), scored("b", Some("0.8")), scored("c", None)];
+        let explained = explain(&results);
+        assert_eq!(
+            explained.iter().map(|e| (e.rank, e.id.as_str(), e.percent_of_top)).collect::<Vec<_>>(),
+            vec![(1, "a", Some(50.0)), (2, "b", Some(100.0)), (3, "c", None)]
+        );
+
+        let unscored = explain(&[scored("a", Some("0")), scored("b", None)]);
+        assert!(unscored.iter().all(|e| e.percent_of_top.is_none()));
+    }
+
+    #[test]
+    fn test_latency_stats() {
+        assert_eq!(LatencyStats::from_samples(&[]), None);
+
+        let samples: Vec<f64> = (1..=20).rev().map(f64::from).collect();
+        let stats = LatencyStats::from_samples(&samples).unwrap();
+        assert_eq!(stats, LatencyStats { min: 1.0, mean: 10.5, median: 10.5, p95: 19.0, max: 20.0 });
+
+        let single = LatencyStats::from_samples(&[7.0]).unwrap();
+        assert_eq!((single.median, single.p95), (7.0, 7.0));
+    }
 }
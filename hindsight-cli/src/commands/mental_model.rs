@@ -1,9 +1,12 @@
 //! Mental model commands for managing user-curated summaries.
 
 use anyhow::Result;
+use colored::Colorize;
+use similar::TextDiff;
 
-use crate::api::ApiClient;
+use crate::api::{ApiClient, MentalModelVersion};
 use crate::output::{self, OutputFormat};
+use crate::pager;
 use crate::ui;
 
 use hindsight_client::types;
@@ -12,23 +15,29 @@ use hindsight_client::types;
 pub fn list(
     client: &ApiClient,
     bank_id: &str,
+    count_only: bool,
+    page: Option<output::PageRequest>,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching mental models..."))
+    let mut spinner = if !count_only {
+        ui::spinner_guard(output_format, "Fetching mental models...")
     } else {
-        None
+        ui::SpinnerGuard::none()
     };
 
     let response = client.list_mental_models(bank_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
-        Ok(result) => {
+        Ok(mut result) => {
+            if count_only {
+                return output::print_count(result.items.len() as i64, output_format);
+            }
+
+            let page_info = page.map(|p| output::paginate(&mut result.items, p)).transpose()?;
+
             if output_format == OutputFormat::Pretty {
                 ui::print_section_header(&format!("Mental Models: {}", bank_id));
 
@@ -50,6 +59,9 @@ pub fn list(
                         println!();
                     }
                 }
+                if let Some(info) = page_info {
+                    output::print_page_footer(&info);
+                }
             } else {
                 output::print_output(&result, output_format)?;
             }
@@ -67,22 +79,19 @@ pub fn get(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching mental model..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Fetching mental model...");
 
     let response = client.get_mental_model(bank_id, mental_model_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(mental_model) => {
             if output_format == OutputFormat::Pretty {
+                pager::start(output_format);
                 print_mental_model_detail(&mental_model);
+            } else if output_format == OutputFormat::Raw {
+                output::print_raw(&mental_model.content);
             } else {
                 output::print_output(&mental_model, output_format)?;
             }
@@ -92,6 +101,92 @@ pub fn get(
     }
 }
 
+/// Versions shown by `get --history`
+#[derive(serde::Serialize)]
+struct MentalModelHistory {
+    id: String,
+    name: String,
+    history_supported: bool,
+    versions: Vec<MentalModelVersion>,
+}
+
+/// Show the stored versions of a mental model, oldest first
+pub fn history(
+    client: &ApiClient,
+    bank_id: &str,
+    mental_model_id: &str,
+    diff: bool,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    if output_format == OutputFormat::Raw {
+        anyhow::bail!("--history does not support -o raw; use -o json to get every version");
+    }
+
+    let mut spinner = ui::spinner_guard(output_format, "Fetching mental model history...");
+
+    // Fetch the model first so a missing ID isn't mistaken for missing history support
+    let response = client.get_mental_model(bank_id, mental_model_id, verbose).and_then(|mental_model| {
+        let versions = client.get_mental_model_history(bank_id, mental_model_id, verbose)?;
+        Ok((mental_model, versions))
+    });
+
+    spinner.finish();
+
+    let (mental_model, versions) = response?;
+    let history_supported = versions.is_some();
+    let versions = versions.filter(|versions| !versions.is_empty()).unwrap_or_else(|| {
+        vec![MentalModelVersion {
+            content: mental_model.content.clone(),
+            timestamp: mental_model.last_refreshed_at.clone().or_else(|| mental_model.created_at.clone()),
+        }]
+    });
+    if !history_supported {
+        ui::print_warning("This server does not keep mental model history; showing the current version only.");
+    }
+
+    let history = MentalModelHistory {
+        id: mental_model.id,
+        name: mental_model.name,
+        history_supported,
+        versions,
+    };
+    if output_format != OutputFormat::Pretty {
+        return output::print_output(&history, output_format);
+    }
+
+    pager::start(output_format);
+    ui::print_section_header(&history.name);
+    println!("  {} {}", ui::dim("ID:"), ui::gradient_start(&history.id));
+    println!("  {} {}", ui::dim("Versions:"), history.versions.len());
+
+    let mut previous: Option<&str> = None;
+    for (i, version) in history.versions.iter().enumerate() {
+        let timestamp = version
+            .timestamp
+            .as_deref()
+            .map(ui::format_timestamp)
+            .unwrap_or_else(|| "unknown time".to_string());
+        let title = format!("Version {} · {}", i + 1, timestamp);
+        match previous {
+            Some(old) if diff => {
+                println!();
+                println!("{}", ui::dim(&title));
+                print_content_diff(old, &version.content);
+            }
+            _ => {
+                println!();
+                println!("{}", ui::gradient_text(&ui::rule(&title)));
+                println!();
+                println!("{}", version.content);
+            }
+        }
+        previous = Some(&version.content);
+    }
+    println!();
+    Ok(())
+}
+
 /// Create a new mental model
 pub fn create(
     client: &ApiClient,
@@ -99,15 +194,11 @@ pub fn create(
     name: &str,
     source_query: &str,
     id: Option<&str>,
+    wait: bool,
+    dry_run: bool,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Creating mental model..."))
-    } else {
-        None
-    };
-
     let request = types::CreateMentalModelRequest {
         id: id.map(|s| s.to_string()),
         name: name.to_string(),
@@ -117,10 +208,20 @@ pub fn create(
         trigger: None,
     };
 
+    if dry_run {
+        output::print_dry_run("POST", &client.url(&["v1", "default", "banks", bank_id, "mental-models"])?, &request);
+        return Ok(());
+    }
+
+    let mut spinner = ui::spinner_guard(output_format, "Creating mental model...");
+
     let response = client.create_mental_model(bank_id, &request, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
+    spinner.finish();
+
+    if wait {
+        let result = response?;
+        return wait_for_created(client, bank_id, &result, id.or(result.mental_model_id.as_deref()), name, verbose, output_format);
     }
 
     match response {
@@ -136,12 +237,70 @@ pub fn create(
     }
 }
 
+/// Wait for a create operation to finish, then print the generated mental model
+fn wait_for_created(
+    client: &ApiClient,
+    bank_id: &str,
+    result: &types::CreateMentalModelResponse,
+    known_id: Option<&str>,
+    name: &str,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let mut spinner = ui::spinner_guard(output_format, "Waiting for mental model to be generated...");
+
+    let poll_result = client.poll_operation(bank_id, &result.operation_id, verbose);
+
+    spinner.finish();
+
+    let (success, error_msg) = poll_result?;
+    if !success {
+        anyhow::bail!(
+            "Mental model creation failed: {}",
+            error_msg.unwrap_or_else(|| "Unknown error".to_string())
+        );
+    }
+
+    // Older servers don't return the new id, so fall back to matching on name
+    let mental_model = match known_id {
+        Some(id) => client.get_mental_model(bank_id, id, verbose)?,
+        None => {
+            let list = client.list_mental_models(bank_id, verbose)?;
+            find_by_name(list.items, name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Operation {} completed but no mental model named '{}' was found",
+                    result.operation_id,
+                    name
+                )
+            })?
+        }
+    };
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_success(&format!("Mental model '{}' created", mental_model.name));
+        pager::start(output_format);
+        print_mental_model_detail(&mental_model);
+    } else {
+        output::print_output(&mental_model, output_format)?;
+    }
+    Ok(())
+}
+
+/// Most recently created mental model with this name
+fn find_by_name(items: Vec<types::MentalModelResponse>, name: &str) -> Option<types::MentalModelResponse> {
+    items
+        .into_iter()
+        .filter(|m| m.name == name)
+        .max_by(|a, b| a.created_at.cmp(&b.created_at))
+}
+
 /// Update a mental model
 pub fn update(
     client: &ApiClient,
     bank_id: &str,
     mental_model_id: &str,
     name: Option<String>,
+    dry_run: bool,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
@@ -149,12 +308,6 @@ pub fn update(
         anyhow::bail!("--name must be provided");
     }
 
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Updating mental model..."))
-    } else {
-        None
-    };
-
     let request = types::UpdateMentalModelRequest {
         name,
         source_query: None,
@@ -163,11 +316,17 @@ pub fn update(
         trigger: None,
     };
 
+    if dry_run {
+        let url = client.url(&["v1", "default", "banks", bank_id, "mental-models", mental_model_id])?;
+        output::print_dry_run("PATCH", &url, &request);
+        return Ok(());
+    }
+
+    let mut spinner = ui::spinner_guard(output_format, "Updating mental model...");
+
     let response = client.update_mental_model(bank_id, mental_model_id, &request, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(mental_model) => {
@@ -208,24 +367,18 @@ pub fn delete(
         }
     }
 
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Deleting mental model..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Deleting mental model...");
 
     let response = client.delete_mental_model(bank_id, mental_model_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
-        Ok(_) => {
+        Ok(delete_response) => {
             if output_format == OutputFormat::Pretty {
                 ui::print_success(&format!("Mental model '{}' deleted successfully", mental_model_id));
             } else {
-                println!("{{\"success\": true}}");
+                output::print_output(&delete_response, output_format)?;
             }
             Ok(())
         }
@@ -238,38 +391,100 @@ pub fn refresh(
     client: &ApiClient,
     bank_id: &str,
     mental_model_id: &str,
+    wait: bool,
+    no_diff: bool,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Submitting mental model refresh..."))
+    // Capture the current content so the regenerated version can be reviewed
+    let before = if wait && !no_diff && output_format == OutputFormat::Pretty {
+        Some(client.get_mental_model(bank_id, mental_model_id, verbose)?)
     } else {
         None
     };
 
+    let mut spinner = ui::spinner_guard(output_format, "Submitting mental model refresh...");
+
     let response = client.refresh_mental_model(bank_id, mental_model_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
+    spinner.finish();
+
+    let operation = response?;
+
+    if !wait {
+        if output_format == OutputFormat::Pretty {
+            ui::print_success(&format!(
+                "Mental model refresh submitted. Operation ID: {}",
+                operation.operation_id
+            ));
+            println!("  {} {}", ui::dim("Status:"), operation.status);
+            println!();
+            println!("{}", ui::dim("Use 'hindsight operations get' to check the operation status."));
+        } else {
+            output::print_output(&operation, output_format)?;
+        }
+        return Ok(());
     }
 
-    match response {
-        Ok(operation) => {
-            if output_format == OutputFormat::Pretty {
-                ui::print_success(&format!(
-                    "Mental model refresh submitted. Operation ID: {}",
-                    operation.operation_id
-                ));
-                println!("  {} {}", ui::dim("Status:"), operation.status);
-                println!();
-                println!("{}", ui::dim("Use 'hindsight operations get' to check the operation status."));
-            } else {
-                output::print_output(&operation, output_format)?;
-            }
-            Ok(())
+    let mut spinner = ui::spinner_guard(output_format, "Waiting for refresh to complete...");
+
+    let poll_result = client.poll_operation(bank_id, &operation.operation_id, verbose);
+
+    spinner.finish();
+
+    let (success, error_msg) = poll_result?;
+    if !success {
+        anyhow::bail!(
+            "Mental model refresh failed: {}",
+            error_msg.unwrap_or_else(|| "Unknown error".to_string())
+        );
+    }
+
+    let after = client.get_mental_model(bank_id, mental_model_id, verbose)?;
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_success(&format!("Mental model '{}' refreshed", after.name));
+        if let Some(before) = before {
+            print_content_diff(&before.content, &after.content);
         }
-        Err(e) => Err(e),
+    } else {
+        output::print_output(&after, output_format)?;
     }
+    Ok(())
+}
+
+/// Unified diff of two content versions, or None if they are identical
+fn content_diff(old: &str, new: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+    let diff = TextDiff::from_lines(old, new);
+    Some(diff.unified_diff().context_radius(3).header("before", "after").to_string())
+}
+
+fn print_content_diff(old: &str, new: &str) {
+    println!();
+    println!("{}", ui::gradient_text(&ui::rule("Changes")));
+    println!();
+
+    let Some(diff) = content_diff(old, new) else {
+        println!("  {}", ui::dim("Content unchanged."));
+        println!();
+        return;
+    };
+
+    for line in diff.lines() {
+        if line.starts_with("+++") || line.starts_with("---") || line.starts_with("@@") {
+            println!("{}", ui::dim(line));
+        } else if line.starts_with('+') {
+            println!("{}", line.green());
+        } else if line.starts_with('-') {
+            println!("{}", line.red());
+        } else {
+            println!("{}", line);
+        }
+    }
+    println!();
 }
 
 // Helper function to print mental model details
@@ -280,8 +495,51 @@ fn print_mental_model_detail(mental_model: &types::MentalModelResponse) {
     println!("  {} {}", ui::dim("Source Query:"), &mental_model.source_query);
 
     println!();
-    println!("{}", ui::gradient_text("─── Content ───"));
+    println!("{}", ui::gradient_text(&ui::rule("Content")));
     println!();
     println!("{}", &mental_model.content);
     println!();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(id: &str, name: &str, created_at: &str) -> types::MentalModelResponse {
+        serde_json::from_value(serde_json::json!({
+            "bank_id": "b",
+            "content": "",
+            "created_at": created_at,
+            "id": id,
+            "max_tokens": 2048,
+            "name": name,
+            "source_query": "q",
+            "tags": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_find_by_name_prefers_newest() {
+        let items = vec![
+            model("old", "prefs", "2026-01-01T00:00:00Z"),
+            model("other", "goals", "2026-03-01T00:00:00Z"),
+            model("new", "prefs", "2026-02-01T00:00:00Z"),
+        ];
+        assert_eq!(find_by_name(items.clone(), "prefs").unwrap().id, "new");
+        assert!(find_by_name(items, "missing").is_none());
+    }
+
+    #[test]
+    fn test_content_diff_unchanged() {
+        assert!(content_diff("same\n", "same\n").is_none());
+    }
+
+    #[test]
+    fn test_content_diff_marks_changes() {
+        let diff = content_diff("alpha\nbeta\n", "alpha\ngamma\n").unwrap();
+        assert!(diff.contains("-beta"));
+        assert!(diff.contains("+gamma"));
+        assert!(diff.contains(" alpha"));
+    }
+}
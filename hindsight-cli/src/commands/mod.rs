@@ -3,9 +3,13 @@ pub mod chunk;
 pub mod directive;
 pub mod document;
 pub mod entity;
+pub mod env;
+pub mod examples;
 pub mod explore;
 pub mod health;
 pub mod memory;
 pub mod operation;
 pub mod mental_model;
+pub mod raw;
+pub mod repl;
 pub mod tag;
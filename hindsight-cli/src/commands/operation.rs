@@ -1,29 +1,37 @@
 use anyhow::Result;
+use serde::Serialize;
 use crate::api::ApiClient;
+use crate::bulk::{run_ordered, with_retries, BulkOutcome, ErrorMode};
 use crate::output::{self, OutputFormat};
 use crate::ui;
 
 pub fn list(
     client: &ApiClient,
     agent_id: &str,
+    template: Option<String>,
+    count_only: bool,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching operations..."))
+    let mut spinner = if template.is_none() && !count_only {
+        ui::spinner_guard(output_format, "Fetching operations...")
     } else {
-        None
+        ui::SpinnerGuard::none()
     };
 
     let response = client.list_operations(agent_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(ops_response) => {
-            if output_format == OutputFormat::Pretty {
+            if count_only {
+                return output::print_count(ops_response.total.unwrap_or(ops_response.operations.len() as i64), output_format);
+            }
+
+            if let Some(template) = &template {
+                output::print_template(template, &ops_response.operations)?;
+            } else if output_format == OutputFormat::Pretty {
                 if ops_response.operations.is_empty() {
                     ui::print_info("No operations found");
                 } else {
@@ -33,6 +41,7 @@ pub fn list(
                         println!("    Type: {}", op.task_type);
                         println!("    Status: {}", op.status);
                         println!("    Items: {}", op.items_count);
+                        println!("    Created: {}", ui::format_timestamp(&op.created_at));
                         if let Some(doc_id) = &op.document_id {
                             println!("    Document ID: {}", doc_id);
                         }
@@ -55,17 +64,11 @@ pub fn get(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching operation status..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Fetching operation status...");
 
     let response = client.get_operation(agent_id, operation_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(result) => {
@@ -82,6 +85,17 @@ pub fn get(
 
                 println!("  {} {}", ui::dim("Status:"), status_str);
 
+                let timestamps = [
+                    ("Created:", &result.created_at),
+                    ("Updated:", &result.updated_at),
+                    ("Completed:", &result.completed_at),
+                ];
+                for (label, value) in timestamps {
+                    if let Some(value) = value {
+                        println!("  {} {}", ui::dim(label), ui::format_timestamp(value));
+                    }
+                }
+
                 if let Some(error) = &result.error_message {
                     println!("  {} {}", ui::dim("Error:"), ui::gradient_end(error));
                 }
@@ -103,17 +117,11 @@ pub fn cancel(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Cancelling operation..."))
-    } else {
-        None
-    };
+    let mut spinner = ui::spinner_guard(output_format, "Cancelling operation...");
 
     let response = client.cancel_operation(agent_id, operation_id, verbose);
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(result) => {
@@ -131,3 +139,138 @@ pub fn cancel(
         Err(e) => Err(e)
     }
 }
+
+/// Per-operation outcome reported by `cancel_all`
+#[derive(Debug, Serialize)]
+struct CancelOutcome {
+    operation_id: String,
+    success: bool,
+    message: Option<String>,
+    /// The request error, kept so an exhausted retry budget still stops the command
+    #[serde(skip)]
+    error: Option<anyhow::Error>,
+}
+
+/// Statuses after which an operation can no longer be cancelled
+fn is_terminal_status(status: &str) -> bool {
+    matches!(status, "completed" | "failed" | "cancelled")
+}
+
+/// Cancel every operation for a bank that has not reached a terminal status
+pub fn cancel_all(
+    client: &ApiClient,
+    agent_id: &str,
+    yes: bool,
+    concurrency: usize,
+    error_mode: ErrorMode,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let mut spinner = ui::spinner_guard(output_format, "Fetching operations...");
+
+    let response = client.list_operations(agent_id, verbose);
+
+    spinner.finish();
+
+    let ops_response = response?;
+    let pending: Vec<String> = ops_response
+        .operations
+        .iter()
+        .filter(|op| !is_terminal_status(&op.status))
+        .map(|op| op.id.clone())
+        .collect();
+
+    if pending.is_empty() {
+        if output_format == OutputFormat::Pretty {
+            ui::print_info("No pending operations to cancel");
+        } else {
+            output::print_output(&Vec::<CancelOutcome>::new(), output_format)?;
+        }
+        return Ok(());
+    }
+
+    // Confirmation prompt unless -y flag is used
+    if !yes && output_format == OutputFormat::Pretty {
+        let message = format!(
+            "Are you sure you want to cancel {} pending operation(s) for bank '{}'?",
+            pending.len(),
+            agent_id
+        );
+
+        let confirmed = ui::prompt_confirmation(&message)?;
+
+        if !confirmed {
+            ui::print_info("Operation cancelled");
+            return Ok(());
+        }
+    }
+
+    let mut bulk = BulkOutcome::new(error_mode);
+    let mut outcomes = Vec::with_capacity(pending.len());
+    let cancel = |operation_id: &String| match with_retries(|| client.cancel_operation(agent_id, operation_id, verbose)) {
+        Ok(result) => CancelOutcome {
+            operation_id: operation_id.clone(),
+            success: result.success,
+            message: result.message,
+            error: None,
+        },
+        Err(e) => CancelOutcome {
+            operation_id: operation_id.clone(),
+            success: false,
+            message: Some(e.to_string()),
+            error: Some(e),
+        },
+    };
+    run_ordered(&pending, concurrency, cancel, |_, mut outcome| {
+        if output_format == OutputFormat::Pretty {
+            if outcome.success {
+                println!("  {} {}", ui::gradient_start("cancelled"), outcome.operation_id);
+            } else {
+                println!(
+                    "  {} {} {}",
+                    ui::gradient_end("failed"),
+                    outcome.operation_id,
+                    ui::dim(outcome.message.as_deref().unwrap_or(""))
+                );
+            }
+        }
+
+        if outcome.success {
+            bulk.success();
+        } else {
+            let message = outcome.message.clone().unwrap_or_else(|| "Unknown error".to_string());
+            let error = outcome.error.take().unwrap_or_else(|| anyhow::anyhow!(message));
+            bulk.failure(format!("Cancel {}", outcome.operation_id), error)?;
+        }
+        outcomes.push(outcome);
+        Ok(())
+    })?;
+
+    if output_format == OutputFormat::Pretty {
+        println!();
+        if bulk.has_failures() {
+            ui::print_warning(&format!("Cancelled {} operation(s), {} failed", bulk.succeeded, bulk.failed.len()));
+        } else {
+            ui::print_success(&format!("Cancelled {} operation(s)", bulk.succeeded));
+        }
+    } else {
+        output::print_output(&outcomes, output_format)?;
+    }
+
+    bulk.print_summary("Cancel");
+    bulk.finish("Cancel")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_terminal_status() {
+        assert!(is_terminal_status("completed"));
+        assert!(is_terminal_status("failed"));
+        assert!(is_terminal_status("cancelled"));
+        assert!(!is_terminal_status("pending"));
+        assert!(!is_terminal_status("processing"));
+    }
+}
@@ -0,0 +1,63 @@
+//! `hindsight raw`: call endpoints the CLI has no command for yet.
+
+use anyhow::{Context, Result};
+
+use crate::api::ApiClient;
+use crate::output::{self, OutputFormat};
+use crate::ui;
+
+/// HTTP method for `hindsight raw`
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+#[value(rename_all = "UPPER")]
+pub enum RawMethod {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+impl From<RawMethod> for reqwest::Method {
+    fn from(method: RawMethod) -> Self {
+        match method {
+            RawMethod::Get => reqwest::Method::GET,
+            RawMethod::Post => reqwest::Method::POST,
+            RawMethod::Put => reqwest::Method::PUT,
+            RawMethod::Patch => reqwest::Method::PATCH,
+            RawMethod::Delete => reqwest::Method::DELETE,
+        }
+    }
+}
+
+/// Send a request and print the response body. JSON bodies are pretty-printed
+/// (or converted for `-o yaml`); anything else is printed as received.
+pub fn raw(
+    client: &ApiClient,
+    method: RawMethod,
+    path: &str,
+    data: Option<String>,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let body = data
+        .map(|data| serde_json::from_str::<serde_json::Value>(&data).context("--data is not valid JSON"))
+        .transpose()?;
+
+    let mut spinner = ui::spinner_guard(output_format, &format!("{} {}...", reqwest::Method::from(method), path));
+
+    let response = client.raw(method.into(), path, body.as_ref(), verbose);
+
+    spinner.finish();
+
+    let (status, body) = response?;
+    if !status.is_success() {
+        return Err(crate::errors::response_error(body.as_bytes(), format!("Error Response: status: {}; body: {}", status, body)));
+    }
+
+    match serde_json::from_str::<serde_json::Value>(&body) {
+        Ok(value) if output_format == OutputFormat::Pretty => println!("{}", output::to_json(&value)?),
+        Ok(value) => output::print_output(&value, output_format)?,
+        Err(_) => println!("{}", body),
+    }
+    Ok(())
+}
@@ -0,0 +1,175 @@
+//! Interactive REPL for iterating on recall and reflect queries.
+
+use anyhow::{Context, Result};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::api::{ApiClient, Sampling};
+use crate::commands::memory;
+use crate::output::OutputFormat;
+use crate::ui;
+
+const HISTORY_DIR_NAME: &str = "hindsight";
+const HISTORY_FILE_NAME: &str = "history";
+
+/// A single line of REPL input, parsed into the action it requests
+#[derive(Debug, PartialEq)]
+enum ReplCommand {
+    Recall(String),
+    Reflect(String),
+    Help,
+    Quit,
+    Empty,
+    Unknown(String),
+}
+
+fn parse_line(line: &str) -> ReplCommand {
+    let line = line.trim();
+    if line.is_empty() {
+        return ReplCommand::Empty;
+    }
+
+    let (head, rest) = match line.split_once(char::is_whitespace) {
+        Some((head, rest)) => (head, rest.trim()),
+        None => (line, ""),
+    };
+
+    match head {
+        ":q" | ":quit" | ":exit" | "exit" | "quit" => ReplCommand::Quit,
+        ":h" | ":help" | "help" => ReplCommand::Help,
+        "recall" | ":recall" | ":r" if !rest.is_empty() => ReplCommand::Recall(rest.to_string()),
+        "reflect" | ":reflect" | ":t" if !rest.is_empty() => ReplCommand::Reflect(rest.to_string()),
+        "recall" | ":recall" | ":r" | "reflect" | ":reflect" | ":t" => {
+            ReplCommand::Unknown(format!("'{}' needs a query", head))
+        }
+        _ if head.starts_with(':') => ReplCommand::Unknown(format!("unknown command '{}'", head)),
+        // Bare input is treated as a recall query
+        _ => ReplCommand::Recall(line.to_string()),
+    }
+}
+
+/// History file location (~/.local/share/hindsight/history on Linux)
+fn history_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(HISTORY_DIR_NAME).join(HISTORY_FILE_NAME))
+}
+
+fn print_help() {
+    println!("  {} {}", ui::gradient_start("<query>"), ui::dim("recall memories (default)"));
+    println!("  {} {}", ui::gradient_start("recall <query>"), ui::dim("recall memories"));
+    println!("  {} {}", ui::gradient_start("reflect <query>"), ui::dim("reflect on a query"));
+    println!("  {} {}", ui::gradient_start(":help"), ui::dim("show this help"));
+    println!("  {} {}", ui::gradient_start(":quit"), ui::dim("exit (or Ctrl-D)"));
+    println!();
+    println!("  {}", ui::dim("Use the up/down arrows to recall and edit previous queries."));
+    println!();
+}
+
+/// Run the interactive REPL against a bank
+pub fn run(
+    client: &ApiClient,
+    bank_id: &str,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let mut editor = DefaultEditor::new().context("Failed to initialize line editor")?;
+
+    let history = history_path();
+    if let Some(path) = &history {
+        // A missing history file is expected on first run
+        let _ = editor.load_history(path);
+    }
+
+    ui::print_section_header(&format!("Hindsight REPL: {}", bank_id));
+    print_help();
+
+    let prompt = format!("{}> ", bank_id);
+    loop {
+        match editor.readline(&prompt) {
+            Ok(line) => {
+                let command = parse_line(&line);
+                if !matches!(command, ReplCommand::Empty) {
+                    let _ = editor.add_history_entry(line.trim());
+                }
+
+                let result = match command {
+                    ReplCommand::Recall(query) => {
+                        memory::recall(client, bank_id, vec![query], memory::RecallOptions::default(), verbose, output_format)
+                    }
+                    ReplCommand::Reflect(query) => memory::reflect(
+                        client,
+                        bank_id,
+                        query,
+                        memory::DEFAULT_BUDGET.to_string(),
+                        None,
+                        None,
+                        None,
+                        false,
+                        false,
+                        Sampling::default(),
+                        false,
+                        false,
+                        verbose,
+                        output_format,
+                    ),
+                    ReplCommand::Help => {
+                        print_help();
+                        Ok(())
+                    }
+                    ReplCommand::Quit => break,
+                    ReplCommand::Empty => Ok(()),
+                    ReplCommand::Unknown(message) => {
+                        ui::print_warning(&format!("{} (type :help for commands)", message));
+                        Ok(())
+                    }
+                };
+
+                // Errors are reported inline so a failed query doesn't end the session
+                if let Err(e) = result {
+                    ui::print_error(&e.to_string());
+                }
+            }
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e).context("Failed to read input"),
+        }
+    }
+
+    if let Some(path) = &history {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create history directory: {}", parent.display()))?;
+        }
+        editor
+            .save_history(path)
+            .with_context(|| format!("Failed to save history: {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line_bare_query_is_recall() {
+        assert_eq!(parse_line("  where does alice work  "), ReplCommand::Recall("where does alice work".to_string()));
+    }
+
+    #[test]
+    fn test_parse_line_explicit_commands() {
+        assert_eq!(parse_line("recall coffee"), ReplCommand::Recall("coffee".to_string()));
+        assert_eq!(parse_line("reflect what do I like?"), ReplCommand::Reflect("what do I like?".to_string()));
+        assert_eq!(parse_line(":quit"), ReplCommand::Quit);
+        assert_eq!(parse_line(":help"), ReplCommand::Help);
+        assert_eq!(parse_line(""), ReplCommand::Empty);
+    }
+
+    #[test]
+    fn test_parse_line_missing_query() {
+        assert!(matches!(parse_line("reflect"), ReplCommand::Unknown(_)));
+        assert!(matches!(parse_line(":bogus"), ReplCommand::Unknown(_)));
+    }
+}
@@ -13,13 +13,14 @@ pub fn list(
     query: Option<String>,
     limit: i64,
     offset: i64,
+    count_only: bool,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching tags..."))
+    let mut spinner = if !count_only {
+        ui::spinner_guard(output_format, "Fetching tags...")
     } else {
-        None
+        ui::SpinnerGuard::none()
     };
 
     let response = client.list_tags(
@@ -30,12 +31,14 @@ pub fn list(
         verbose,
     );
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
-    }
+    spinner.finish();
 
     match response {
         Ok(result) => {
+            if count_only {
+                return output::print_count(result.total, output_format);
+            }
+
             if output_format == OutputFormat::Pretty {
                 ui::print_section_header(&format!("Tags: {}", bank_id));
 
@@ -1,8 +1,10 @@
 use anyhow::{Context, Result};
+use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::io::{self, Write};
 use std::path::PathBuf;
+use std::sync::Mutex;
 
 const DEFAULT_API_URL: &str = "http://localhost:8888";
 const CONFIG_FILE_NAME: &str = "config";
@@ -12,6 +14,10 @@ const CONFIG_DIR_NAME: &str = ".hindsight";
 pub struct Config {
     pub api_url: String,
     pub api_key: Option<String>,
+    /// Tenant segment inserted into every API path (`/tenants/<id>/...`)
+    pub tenant: Option<String>,
+    /// Bank used when a bank-scoped command omits its bank id
+    pub bank: Option<String>,
     pub source: ConfigSource,
 }
 
@@ -37,7 +43,24 @@ impl Config {
     /// 1. Environment variable (HINDSIGHT_API_URL, HINDSIGHT_API_KEY) - highest priority, for overrides
     /// 2. Local config file (~/.hindsight/config.toml)
     /// 3. Default (http://localhost:8888)
+    ///
+    /// The tenant and default bank are resolved independently: HINDSIGHT_TENANT / HINDSIGHT_BANK,
+    /// then `tenant` / `bank` in the config file.
     pub fn load() -> Result<Self> {
+        let mut config = Self::load_server()?;
+        let tenant = match env::var("HINDSIGHT_TENANT") {
+            Ok(tenant) => Some(tenant),
+            Err(_) => Self::load_file_value("tenant")?,
+        };
+        config.tenant = tenant.map(|t| validate_tenant(&t)).transpose()?;
+        config.bank = match env::var("HINDSIGHT_BANK") {
+            Ok(bank) => Some(bank).filter(|b| !b.trim().is_empty()),
+            Err(_) => Self::load_file_value("bank")?,
+        };
+        Ok(config)
+    }
+
+    fn load_server() -> Result<Self> {
         // Load API key from environment (highest priority)
         let env_api_key = env::var("HINDSIGHT_API_KEY").ok();
 
@@ -69,14 +92,14 @@ impl Config {
                 api_url
             );
         }
-        Ok(Config { api_url, api_key, source })
+        Ok(Config { api_url, api_key, tenant: None, bank: None, source })
     }
 
-    fn config_dir() -> Option<PathBuf> {
+    pub fn config_dir() -> Option<PathBuf> {
         dirs::home_dir().map(|home| home.join(CONFIG_DIR_NAME))
     }
 
-    fn config_file_path() -> Option<PathBuf> {
+    pub fn config_file_path() -> Option<PathBuf> {
         Self::config_dir().map(|dir| dir.join(CONFIG_FILE_NAME))
     }
 
@@ -97,7 +120,7 @@ impl Config {
         let mut api_key: Option<String> = None;
 
         // Simple TOML parsing for api_url and api_key
-        for line in content.lines() {
+        for line in top_level_lines(&content) {
             let line = line.trim();
             if line.starts_with("api_url") {
                 if let Some(value) = line.split('=').nth(1) {
@@ -122,11 +145,54 @@ impl Config {
         }
     }
 
+    fn load_file_value(key: &str) -> Result<Option<String>> {
+        let config_path = match Self::config_file_path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(None),
+        };
+        let content = fs::read_to_string(&config_path)
+            .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
+        let value = top_level_lines(&content).find_map(|line| parse_config_value(line, key));
+        Ok(value)
+    }
+
+    /// Fact type shorthands from the `[fact_type_aliases]` config section, ignoring read errors
+    pub fn fact_type_aliases() -> HashMap<String, String> {
+        Self::config_file_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|content| parse_section(&content, "fact_type_aliases").into_iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// A display preference from the config file, ignoring read errors
+    pub fn file_setting(key: &str) -> Option<String> {
+        Self::load_file_value(key).ok().flatten()
+    }
+
+    /// Fail unless the server came from the environment or the config file,
+    /// so automation never silently targets the built-in default URL
+    pub fn require_explicit_server(&self) -> Result<()> {
+        if self.source != ConfigSource::Default {
+            return Ok(());
+        }
+        let config_path = Self::config_file_path()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| format!("~/{}/{}", CONFIG_DIR_NAME, CONFIG_FILE_NAME));
+        anyhow::bail!(
+            "No API server configured and --require-explicit-server is set. Looked in:\n  \
+             - HINDSIGHT_API_URL environment variable (not set)\n  \
+             - config file {} (no api_url)\n\
+             Refusing to fall back to the default {}",
+            config_path,
+            DEFAULT_API_URL
+        )
+    }
+
     pub fn save_api_url(api_url: &str) -> Result<PathBuf> {
-        Self::save_config(api_url, None)
+        Self::save_config(api_url, None, None, None)
     }
 
-    pub fn save_config(api_url: &str, api_key: Option<&str>) -> Result<PathBuf> {
+    pub fn save_config(api_url: &str, api_key: Option<&str>, tenant: Option<&str>, bank: Option<&str>) -> Result<PathBuf> {
         let config_dir = Self::config_dir()
             .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
 
@@ -137,10 +203,8 @@ impl Config {
         }
 
         let config_path = config_dir.join(CONFIG_FILE_NAME);
-        let mut content = format!("api_url = \"{}\"\n", api_url);
-        if let Some(key) = api_key {
-            content.push_str(&format!("api_key = \"{}\"\n", key));
-        }
+        let existing = fs::read_to_string(&config_path).unwrap_or_default();
+        let content = render_config(&existing, api_url, api_key, tenant, bank);
 
         fs::write(&config_path, content)
             .with_context(|| format!("Failed to write config file: {}", config_path.display()))?;
@@ -153,6 +217,66 @@ impl Config {
     }
 }
 
+/// Bank id for a bank-scoped command: the positional if given, else the default bank
+pub fn resolve_bank(positional: Option<String>, default: Option<&str>) -> Result<String> {
+    let bank = positional.or_else(|| default.map(str::to_string)).ok_or_else(|| {
+        anyhow::anyhow!(
+            "No bank given. Pass BANK_ID, or set a default with --bank, HINDSIGHT_BANK or `bank = \"...\"` in {}",
+            Config::config_file_path()
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|| format!("~/{}/{}", CONFIG_DIR_NAME, CONFIG_FILE_NAME))
+        )
+    })?;
+    normalize_bank_id(&bank)
+}
+
+/// Bank ids whose normalization changed them, reported with --verbose
+static BANK_ID_CHANGES: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// Trim a pasted bank id, and lowercase it when `lowercase_bank_ids = true` is set
+/// in the config file (only for servers whose bank ids are case-insensitive)
+pub fn normalize_bank_id(raw: &str) -> Result<String> {
+    let lowercase = Config::file_setting("lowercase_bank_ids").is_some_and(|v| v == "true");
+    let bank = normalize_bank_id_with(raw, lowercase)?;
+    if bank != raw {
+        if let Ok(mut changes) = BANK_ID_CHANGES.lock() {
+            changes.push((raw.to_string(), bank.clone()));
+        }
+    }
+    Ok(bank)
+}
+
+fn normalize_bank_id_with(raw: &str, lowercase: bool) -> Result<String> {
+    let bank = raw.trim();
+    if bank.is_empty() {
+        anyhow::bail!("Bank id cannot be empty");
+    }
+    Ok(if lowercase { bank.to_lowercase() } else { bank.to_string() })
+}
+
+/// Print the bank ids normalized so far (`--verbose`)
+pub fn report_bank_id_changes() {
+    let changes = BANK_ID_CHANGES.lock().map(|mut c| std::mem::take(&mut *c)).unwrap_or_default();
+    for (raw, bank) in changes {
+        eprintln!("Normalized bank id {:?} to {:?}", raw, bank);
+    }
+}
+
+/// Check a tenant id is usable as a single URL path segment
+pub fn validate_tenant(tenant: &str) -> Result<String> {
+    let tenant = tenant.trim();
+    if tenant.is_empty() {
+        anyhow::bail!("Tenant id cannot be empty");
+    }
+    if tenant.contains('/') || tenant.contains('\\') {
+        anyhow::bail!("Invalid tenant id '{}': must not contain slashes", tenant);
+    }
+    if tenant == "." || tenant == ".." {
+        anyhow::bail!("Invalid tenant id '{}'", tenant);
+    }
+    Ok(tenant.to_string())
+}
+
 /// Prompt user for API URL interactively
 pub fn prompt_api_url(current_url: Option<&str>) -> Result<String> {
     let default = current_url.unwrap_or(DEFAULT_API_URL);
@@ -176,6 +300,77 @@ pub fn generate_doc_id() -> String {
     format!("cli_put_{}", now.format("%Y%m%d_%H%M%S"))
 }
 
+/// Map a fact type alias to its full name; unknown names pass through unchanged
+pub fn resolve_fact_type(name: &str, aliases: &HashMap<String, String>) -> String {
+    aliases.get(name).cloned().unwrap_or_else(|| name.to_string())
+}
+
+/// Lines before the first `[section]` header, where the top-level keys live
+fn top_level_lines(content: &str) -> impl Iterator<Item = &str> {
+    content.lines().take_while(|line| !line.trim_start().starts_with('['))
+}
+
+/// Top-level keys `save_config` writes; every other setting is carried over
+const MANAGED_KEYS: &[&str] = &["api_url", "api_key", "tenant", "bank"];
+
+/// New config file content: the managed keys, then the rest of the existing file's
+/// top-level settings (such as `spinner_style`) and its sections, unchanged
+fn render_config(existing: &str, api_url: &str, api_key: Option<&str>, tenant: Option<&str>, bank: Option<&str>) -> String {
+    let mut content = format!("api_url = \"{}\"\n", api_url);
+    if let Some(key) = api_key {
+        content.push_str(&format!("api_key = \"{}\"\n", key));
+    }
+    if let Some(tenant) = tenant {
+        content.push_str(&format!("tenant = \"{}\"\n", tenant));
+    }
+    if let Some(bank) = bank {
+        content.push_str(&format!("bank = \"{}\"\n", bank));
+    }
+    for line in top_level_lines(existing) {
+        let managed = line
+            .split_once('=')
+            .is_some_and(|(key, _)| MANAGED_KEYS.contains(&key.trim().trim_matches('"')));
+        if !managed && !line.trim().is_empty() {
+            content.push_str(line);
+            content.push('\n');
+        }
+    }
+    if let Some(sections) = sections_text(existing) {
+        content.push('\n');
+        content.push_str(sections);
+    }
+    content
+}
+
+/// Everything from the first `[section]` header on
+fn sections_text(content: &str) -> Option<&str> {
+    let top_level_len: usize = content
+        .split_inclusive('\n')
+        .take_while(|line| !line.trim_start().starts_with('['))
+        .map(str::len)
+        .sum();
+    content.get(top_level_len..).filter(|rest| !rest.is_empty())
+}
+
+/// `key = value` pairs under a `[name]` header, up to the next header
+pub fn parse_section(content: &str, name: &str) -> Vec<(String, String)> {
+    let header = format!("[{}]", name);
+    content
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| *line != header)
+        .skip(1)
+        .take_while(|line| !line.starts_with('['))
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim().trim_matches('"').to_string();
+            let value = value.trim().trim_matches('"').trim_matches('\'').to_string();
+            (!key.is_empty() && !value.is_empty()).then_some((key, value))
+        })
+        .collect()
+}
+
 /// Parse a simple TOML-like config line and extract value.
 /// Handles both quoted and unquoted values.
 pub fn parse_config_value(line: &str, key: &str) -> Option<String> {
@@ -192,6 +387,16 @@ pub fn parse_config_value(line: &str, key: &str) -> Option<String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_require_explicit_server() {
+        let config = |source| Config { api_url: DEFAULT_API_URL.to_string(), api_key: None, tenant: None, bank: None, source };
+        assert!(config(ConfigSource::Environment).require_explicit_server().is_ok());
+        assert!(config(ConfigSource::LocalFile).require_explicit_server().is_ok());
+        let err = config(ConfigSource::Default).require_explicit_server().unwrap_err().to_string();
+        assert!(err.contains("HINDSIGHT_API_URL"));
+        assert!(err.contains("config file"));
+    }
+
     #[test]
     fn test_config_source_display() {
         assert_eq!(format!("{}", ConfigSource::LocalFile), "config file");
@@ -266,6 +471,55 @@ mod tests {
         assert_ne!(id1, id2);
     }
 
+    #[test]
+    fn test_parse_section() {
+        let content = "api_url = \"http://localhost:8888\"\n\n[fact_type_aliases]\n# shorthands\nw = \"world\"\nexp = 'experience'\n\n[other]\nx = \"y\"\n";
+        assert_eq!(
+            parse_section(content, "fact_type_aliases"),
+            vec![("w".to_string(), "world".to_string()), ("exp".to_string(), "experience".to_string())]
+        );
+        assert!(parse_section(content, "missing").is_empty());
+        assert_eq!(sections_text(content).map(|s| s.lines().next()), Some(Some("[fact_type_aliases]")));
+        assert_eq!(sections_text("api_url = x\n"), None);
+        // Section keys never shadow top-level ones
+        assert_eq!(top_level_lines(content).find_map(|l| parse_config_value(l, "x")), None);
+    }
+
+    #[test]
+    fn test_resolve_fact_type() {
+        let aliases = HashMap::from([("w".to_string(), "world".to_string())]);
+        assert_eq!(resolve_fact_type("w", &aliases), "world");
+        assert_eq!(resolve_fact_type("opinion", &aliases), "opinion");
+    }
+
+    #[test]
+    fn test_render_config_round_trips_spinner_style() {
+        let existing = "api_url = \"http://old:8888\"\nspinner_style = \"dots\"\n\n[fact_type_aliases]\nw = \"world\"\n";
+        let content = render_config(existing, "http://new:8888", Some("sk-test"), None, None);
+        let value = |key| top_level_lines(&content).find_map(|line| parse_config_value(line, key));
+
+        assert_eq!(value("api_url").as_deref(), Some("http://new:8888"));
+        assert_eq!(value("api_key").as_deref(), Some("sk-test"));
+        assert_eq!(value("spinner_style").as_deref(), Some("dots"));
+        assert_eq!(parse_section(&content, "fact_type_aliases"), vec![("w".to_string(), "world".to_string())]);
+
+        // Saving again is stable
+        assert_eq!(render_config(&content, "http://new:8888", Some("sk-test"), None, None), content);
+    }
+
+    #[test]
+    fn test_render_config_keeps_unmanaged_keys() {
+        let existing = "# written by hand\napi_url = \"http://old:8888\"\nbank = \"old\"\nlowercase_bank_ids = true\n";
+        let content = render_config(existing, "http://new:8888", None, Some("acme"), Some("team"));
+        let value = |key| top_level_lines(&content).find_map(|line| parse_config_value(line, key));
+
+        assert_eq!(value("lowercase_bank_ids").as_deref(), Some("true"));
+        assert_eq!(value("tenant").as_deref(), Some("acme"));
+        assert_eq!(value("bank").as_deref(), Some("team"));
+        assert_eq!(content.matches("bank = ").count(), 1);
+        assert!(content.contains("# written by hand\n"));
+    }
+
     #[test]
     fn test_parse_config_value_quoted() {
         assert_eq!(
@@ -323,8 +577,32 @@ mod tests {
         let config = Config {
             api_url: "http://test:8080".to_string(),
             api_key: None,
+            tenant: None,
+            bank: None,
             source: ConfigSource::Default,
         };
         assert_eq!(config.api_url(), "http://test:8080");
     }
+
+    #[test]
+    fn test_resolve_bank_prefers_positional() {
+        assert_eq!(resolve_bank(Some("a".to_string()), Some("b")).unwrap(), "a");
+        assert_eq!(resolve_bank(None, Some("b")).unwrap(), "b");
+        assert!(resolve_bank(None, None).unwrap_err().to_string().contains("--bank"));
+    }
+
+    #[test]
+    fn test_normalize_bank_id_with() {
+        assert_eq!(normalize_bank_id_with("  My-Bank\n", false).unwrap(), "My-Bank");
+        assert_eq!(normalize_bank_id_with(" My-Bank ", true).unwrap(), "my-bank");
+        assert!(normalize_bank_id_with("   ", false).is_err());
+    }
+
+    #[test]
+    fn test_validate_tenant() {
+        assert_eq!(validate_tenant(" acme ").unwrap(), "acme");
+        assert!(validate_tenant("acme/other").is_err());
+        assert!(validate_tenant("..").is_err());
+        assert!(validate_tenant("").is_err());
+    }
 }
@@ -0,0 +1,118 @@
+//! `--print-curl`: show every API request as an equivalent curl command.
+//!
+//! Requests are observed through the client's request hook. Headers configured on
+//! the HTTP client itself (request id, authorization, `--header`) are not part of
+//! the observed request, so they are registered here when the client is created.
+//! Sensitive values are always masked.
+
+use std::sync::OnceLock;
+
+use crate::api::{self, CustomHeader};
+
+/// What to do after printing a request
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum CurlMode {
+    /// Print each request, then send it
+    Send,
+    /// Print the first request and exit without sending anything
+    Exit,
+}
+
+struct CurlSettings {
+    mode: CurlMode,
+    /// Client-level headers, already redacted
+    headers: Vec<(String, String)>,
+}
+
+static SETTINGS: OnceLock<CurlSettings> = OnceLock::new();
+
+const REDACTED: &str = "<redacted>";
+
+/// Start printing requests made by this process
+pub fn enable(mode: CurlMode, request_id: &str, has_api_key: bool, extra_headers: &[CustomHeader]) {
+    let mut headers = vec![("x-request-id".to_string(), request_id.to_string())];
+    if has_api_key {
+        headers.push(("authorization".to_string(), format!("Bearer {}", REDACTED)));
+    }
+    for header in extra_headers {
+        let value = if header.is_sensitive() {
+            REDACTED.to_string()
+        } else {
+            header.value.to_str().unwrap_or(REDACTED).to_string()
+        };
+        headers.push((header.name.to_string(), value));
+    }
+
+    if SETTINGS.set(CurlSettings { mode, headers }).is_ok() {
+        hindsight_client::hooks::set_request_observer(observe);
+    }
+}
+
+fn observe(request: &reqwest::Request) {
+    let Some(settings) = SETTINGS.get() else { return };
+
+    let mut headers: Vec<(String, String)> = request
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            let value = if api::is_sensitive_header(name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                value.to_str().unwrap_or(REDACTED).to_string()
+            };
+            (name.to_string(), value)
+        })
+        .collect();
+    headers.extend(settings.headers.iter().cloned());
+
+    let body = request.body().and_then(|b| b.as_bytes()).map(String::from_utf8_lossy);
+
+    // stderr, so the command's own output stays parseable
+    eprintln!("{}", format_curl(request.method().as_str(), request.url().as_str(), &headers, body.as_deref()));
+
+    if settings.mode == CurlMode::Exit {
+        std::process::exit(0);
+    }
+}
+
+/// Quote for POSIX shells: wrap in single quotes, escaping embedded ones
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn format_curl(method: &str, url: &str, headers: &[(String, String)], body: Option<&str>) -> String {
+    let mut parts = vec![format!("curl -X {} {}", method, shell_quote(url))];
+    for (name, value) in headers {
+        parts.push(format!("-H {}", shell_quote(&format!("{}: {}", name, value))));
+    }
+    if let Some(body) = body {
+        parts.push(format!("--data {}", shell_quote(body)));
+    }
+    parts.join(" \\\n  ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote_escapes_single_quotes() {
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn test_format_curl() {
+        let headers = vec![
+            ("content-type".to_string(), "application/json".to_string()),
+            ("authorization".to_string(), "Bearer <redacted>".to_string()),
+        ];
+        let curl = format_curl("POST", "http://host/v1/default/banks/b/memories/recall", &headers, Some(r#"{"query":"x"}"#));
+        assert_eq!(
+            curl,
+            "curl -X POST 'http://host/v1/default/banks/b/memories/recall' \\\n  \
+             -H 'content-type: application/json' \\\n  \
+             -H 'authorization: Bearer <redacted>' \\\n  \
+             --data '{\"query\":\"x\"}'"
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! Human-friendly durations for CLI flags (`30s`, `5m`, `1h`, `2d`).
+//!
+//! Every flag that takes a time argument uses [`parse_duration`] as its clap
+//! value parser, so all commands accept the same syntax.
+
+use anyhow::Result;
+use std::time::Duration;
+
+/// Parse a duration: a whole number with an optional `s`, `m`, `h` or `d` suffix.
+/// A bare number is taken as seconds.
+pub fn parse_duration(raw: &str) -> Result<Duration> {
+    let raw = raw.trim();
+    if raw.starts_with('-') {
+        anyhow::bail!("invalid duration '{}': must not be negative", raw);
+    }
+    let split = raw.find(|c: char| !c.is_ascii_digit()).unwrap_or(raw.len());
+    let (number, unit) = raw.split_at(split);
+    if number.is_empty() {
+        anyhow::bail!("invalid duration '{}': expected a number like 30s, 5m, 1h or 2d", raw);
+    }
+    let value: u64 = number
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid duration '{}': number is too large", raw))?;
+    let multiplier = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => anyhow::bail!("invalid duration '{}': unknown unit '{}' (use s, m, h or d)", raw, unit),
+    };
+    value
+        .checked_mul(multiplier)
+        .map(Duration::from_secs)
+        .ok_or_else(|| anyhow::anyhow!("invalid duration '{}': number is too large", raw))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("45").unwrap(), Duration::from_secs(45));
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration("1h").unwrap(), Duration::from_secs(3600));
+        assert_eq!(parse_duration(" 2d ").unwrap(), Duration::from_secs(172_800));
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_bad_input() {
+        assert!(parse_duration("5x").unwrap_err().to_string().contains("unknown unit 'x'"));
+        assert!(parse_duration("-5s").unwrap_err().to_string().contains("negative"));
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("1.5h").is_err());
+        assert!(parse_duration("99999999999999999999d").is_err());
+    }
+}
@@ -1,18 +1,124 @@
 use colored::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::ui::{self, Glyph};
+
+/// Exit code for `--wait` flows that hit `--wait-timeout`, matching timeout(1)
+pub const WAIT_TIMEOUT_EXIT_CODE: i32 = 124;
+
+/// Exit code for `--fail-if-empty` when the call succeeded but found nothing
+pub const EMPTY_RESULTS_EXIT_CODE: i32 = 3;
+
+/// Print failures as one line (`--compact-errors`); `verbose` adds the full error after it
+static COMPACT: AtomicBool = AtomicBool::new(false);
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Longest message kept in a compact error line
+const COMPACT_MESSAGE_CHARS: usize = 200;
+
+pub fn set_compact(compact: bool, verbose: bool) {
+    COMPACT.store(compact, Ordering::Relaxed);
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+/// Set by `--fail-if-empty` commands once their (already printed) results were empty
+static EMPTY_RESULTS: AtomicBool = AtomicBool::new(false);
+
+pub fn report_empty_results() {
+    EMPTY_RESULTS.store(true, Ordering::Relaxed);
+}
+
+/// Exit with [`EMPTY_RESULTS_EXIT_CODE`] if a command reported empty results
+pub fn exit_if_empty_results() {
+    if EMPTY_RESULTS.load(Ordering::Relaxed) {
+        std::process::exit(EMPTY_RESULTS_EXIT_CODE);
+    }
+}
+
+pub fn handle_api_error(err: anyhow::Error, api_url: &str, request_id: Option<&str>) -> ! {
+    if COMPACT.load(Ordering::Relaxed) {
+        let mut line = format!("error: {}", compact_summary(&err));
+        if let Some(request_id) = request_id {
+            line.push_str(&format!(" (request id {})", request_id));
+        }
+        eprintln!("{}", line);
+        if VERBOSE.load(Ordering::Relaxed) {
+            eprintln!("{:#}", err);
+        }
+        crate::pager::finish();
+        let code = if err.downcast_ref::<crate::api::WaitTimeout>().is_some() { WAIT_TIMEOUT_EXIT_CODE } else { 1 };
+        std::process::exit(code);
+    }
+
+    if let Some(timeout) = err.downcast_ref::<crate::api::WaitTimeout>() {
+        eprintln!("{} {}", ui::glyph(Glyph::Error).bright_red().bold(), timeout.to_string().bright_red().bold());
+        if !timeout.cancelled {
+            eprintln!(
+                "\n{}\n  hindsight operation get {} {}\n  hindsight operation cancel {} {}",
+                "The operation is still running. Check or cancel it with:".bright_yellow(),
+                timeout.bank_id,
+                timeout.operation_id,
+                timeout.bank_id,
+                timeout.operation_id
+            );
+        }
+        crate::pager::finish();
+        std::process::exit(WAIT_TIMEOUT_EXIT_CODE);
+    }
 
-pub fn handle_api_error(err: anyhow::Error, api_url: &str) -> ! {
     eprintln!("{}", format_error_message(&err, api_url));
+    if let Some(request_id) = request_id {
+        // Lets the server operator find this invocation in their logs
+        eprintln!("\n{}\n  {}", "Request ID:".bright_yellow(), request_id.bright_white());
+    }
+    crate::pager::finish();
     std::process::exit(1);
 }
 
 fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
     let err_str = err.to_string();
 
+    // HTML instead of JSON: checked first, since the page text can contain anything
+    if is_html_response(err) {
+        return format!(
+            "{} {}\n\n{}\n  {}\n\n{}\n  {bullet} {}\n  {bullet} {}\n\n{}\n  {}",
+            ui::glyph(Glyph::Error).bright_red().bold(),
+            "Server returned HTML, not JSON. Is the URL/path correct?".bright_red().bold(),
+            "API URL:".bright_yellow(),
+            api_url.bright_white(),
+            "Possible causes:".bright_yellow(),
+            "The API URL points at a web server or proxy instead of the Hindsight API".bright_white(),
+            "A proxy or login page is intercepting the request".bright_white(),
+            "Try:".bright_green(),
+            "Check HINDSIGHT_API_URL, including any path prefix".bright_white(),
+            bullet = ui::glyph(Glyph::Bullet)
+        );
+    }
+
+    // Redirect loop, usually a load balancer or proxy rule
+    if let Some(redirects) = err.chain().find_map(|cause| cause.downcast_ref::<crate::api::TooManyRedirects>()) {
+        return format!(
+            "{} {}\n\n{}\n  {}\n\n{}\n  {}\n\n{}\n  {bullet} {}\n  {bullet} {}\n\n{}\n  {}",
+            ui::glyph(Glyph::Error).bright_red().bold(),
+            format!("Too many redirects (more than {})", crate::api::MAX_REDIRECTS).bright_red().bold(),
+            "API URL:".bright_yellow(),
+            api_url.bright_white(),
+            "Last redirect to:".bright_yellow(),
+            redirects.last_url.bright_white(),
+            "Possible causes:".bright_yellow(),
+            "A load balancer or proxy redirects in a loop (e.g. between http and https)".bright_white(),
+            "The API URL is missing a path prefix or trailing segment the server expects".bright_white(),
+            "Try:".bright_green(),
+            "Run with --verbose to see each redirect, then point HINDSIGHT_API_URL at the final URL".bright_white(),
+            bullet = ui::glyph(Glyph::Bullet)
+        );
+    }
+
     // Connection refused
     if err_str.contains("Connection refused") || err_str.contains("tcp connect error") || err_str.contains("error sending request") {
         return format!(
-            "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n  • {}\n\n{}\n  {}",
-            "✗".bright_red().bold(),
+            "{} {}\n\n{}\n  {}\n\n{}\n  {bullet} {}\n  {bullet} {}\n  {bullet} {}\n\n{}\n  {}",
+            ui::glyph(Glyph::Error).bright_red().bold(),
             "Cannot connect to Hindsight API".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -21,15 +127,16 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
             format!("The server is running on a different address than {}", api_url).bright_white(),
             "A firewall is blocking the connection".bright_white(),
             "Try:".bright_green(),
-            "Start the Hindsight API server and ensure it's accessible".bright_white()
+            "Start the Hindsight API server and ensure it's accessible".bright_white(),
+            bullet = ui::glyph(Glyph::Bullet)
         );
     }
 
     // Timeout
     if err_str.contains("timeout") || err_str.contains("Timeout") {
         return format!(
-            "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n\n{}\n  • {}\n  • {}",
-            "✗".bright_red().bold(),
+            "{} {}\n\n{}\n  {}\n\n{}\n  {bullet} {}\n  {bullet} {}\n\n{}\n  {bullet} {}\n  {bullet} {}",
+            ui::glyph(Glyph::Error).bright_red().bold(),
             "Request timed out".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -38,15 +145,16 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
             "Network latency is too high".bright_white(),
             "Try:".bright_green(),
             "Check if the API server is healthy".bright_white(),
-            "Try again with a better network connection".bright_white()
+            "Try again with a better network connection".bright_white(),
+            bullet = ui::glyph(Glyph::Bullet)
         );
     }
 
     // DNS/Host resolution
     if err_str.contains("dns") || err_str.contains("DNS") || err_str.contains("failed to lookup") {
         return format!(
-            "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n\n{}\n  {}",
-            "✗".bright_red().bold(),
+            "{} {}\n\n{}\n  {}\n\n{}\n  {bullet} {}\n  {bullet} {}\n\n{}\n  {}",
+            ui::glyph(Glyph::Error).bright_red().bold(),
             "Cannot resolve API hostname".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -54,15 +162,16 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
             "The hostname in the API URL is incorrect".bright_white(),
             "DNS server is not responding".bright_white(),
             "Try:".bright_green(),
-            "Check the HINDSIGHT_API_URL environment variable".bright_white()
+            "Check the HINDSIGHT_API_URL environment variable".bright_white(),
+            bullet = ui::glyph(Glyph::Bullet)
         );
     }
 
     // 404 Not Found
     if err_str.contains("404") {
         return format!(
-            "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n\n{}\n  {}",
-            "✗".bright_red().bold(),
+            "{} {}\n\n{}\n  {}\n\n{}\n  {bullet} {}\n  {bullet} {}\n\n{}\n  {}",
+            ui::glyph(Glyph::Error).bright_red().bold(),
             "API endpoint not found (404)".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -70,15 +179,16 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
             "The API endpoint path has changed".bright_white(),
             "You're using an incompatible API version".bright_white(),
             "Try:".bright_green(),
-            "Check that you're using the correct Hindsight API version".bright_white()
+            "Check that you're using the correct Hindsight API version".bright_white(),
+            bullet = ui::glyph(Glyph::Bullet)
         );
     }
 
     // 401/403 Authentication
     if err_str.contains("401") || err_str.contains("403") {
         return format!(
-            "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n\n{}\n  {}",
-            "✗".bright_red().bold(),
+            "{} {}\n\n{}\n  {}\n\n{}\n  {bullet} {}\n  {bullet} {}\n\n{}\n  {}",
+            ui::glyph(Glyph::Error).bright_red().bold(),
             "Authentication failed".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -86,15 +196,16 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
             "API requires authentication".bright_white(),
             "Invalid or missing credentials".bright_white(),
             "Try:".bright_green(),
-            "Check if the API requires an API key or token".bright_white()
+            "Check if the API requires an API key or token".bright_white(),
+            bullet = ui::glyph(Glyph::Bullet)
         );
     }
 
     // 500 Server Error
     if err_str.contains("500") || err_str.contains("502") || err_str.contains("503") {
         return format!(
-            "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n\n{}\n  • {}\n  • {}",
-            "✗".bright_red().bold(),
+            "{} {}\n\n{}\n  {}\n\n{}\n  {bullet} {}\n  {bullet} {}\n\n{}\n  {bullet} {}\n  {bullet} {}",
+            ui::glyph(Glyph::Error).bright_red().bold(),
             "API server error".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -103,7 +214,8 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
             "Service temporarily unavailable".bright_white(),
             "Try:".bright_green(),
             "Check the API server logs for details".bright_white(),
-            "Try again in a few moments".bright_white()
+            "Try again in a few moments".bright_white(),
+            bullet = ui::glyph(Glyph::Bullet)
         );
     }
 
@@ -111,7 +223,7 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
     if err_str.contains("invalid URL") || err_str.contains("InvalidUri") {
         return format!(
             "{} {}\n\n{}\n  {}\n\n{}\n  {}\n\n{}\n  {}",
-            "✗".bright_red().bold(),
+            ui::glyph(Glyph::Error).bright_red().bold(),
             "Invalid API URL".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -137,8 +249,8 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
         };
 
         return format!(
-            "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n  • {}{}\n\n{}\n  • {}\n  • {}",
-            "✗".bright_red().bold(),
+            "{} {}\n\n{}\n  {}\n\n{}\n  {bullet} {}\n  {bullet} {}\n  {bullet} {}{}\n\n{}\n  {bullet} {}\n  {bullet} {}",
+            ui::glyph(Glyph::Error).bright_red().bold(),
             "Invalid API response format".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -149,14 +261,15 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
             response_hint,
             "Try:".bright_green(),
             "Run with --verbose flag to see the full request/response".bright_white(),
-            "Ensure you're using a compatible Hindsight API version".bright_white()
+            "Ensure you're using a compatible Hindsight API version".bright_white(),
+            bullet = ui::glyph(Glyph::Bullet)
         );
     }
 
     // Generic error with the full error message
     format!(
-        "{} {}\n\n{}\n  {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n  • {}",
-        "✗".bright_red().bold(),
+        "{} {}\n\n{}\n  {}\n\n{}\n  {}\n\n{}\n  {bullet} {}\n  {bullet} {}\n  {bullet} {}",
+        ui::glyph(Glyph::Error).bright_red().bold(),
         "API request failed".bright_red().bold(),
         "API URL:".bright_yellow(),
         api_url.bright_white(),
@@ -165,7 +278,8 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
         "Suggestions:".bright_green(),
         "Check that HINDSIGHT_API_URL is set correctly".bright_white(),
         "Ensure the Hindsight API server is running".bright_white(),
-        "Verify network connectivity to the API server".bright_white()
+        "Verify network connectivity to the API server".bright_white(),
+        bullet = ui::glyph(Glyph::Bullet)
     )
 }
 
@@ -183,3 +297,178 @@ pub fn print_config_help() {
     println!("    3. Default (http://localhost:8888)");
     println!();
 }
+
+/// `<context>: HTTP <status>: <message>` on one line, from an error chain that may
+/// carry a whole response (headers, body, validation details)
+fn compact_summary(err: &anyhow::Error) -> String {
+    let full = format!("{:#}", err);
+    let top = err.to_string();
+    let status = response_status(&full);
+
+    let message = response_detail(&full).or_else(|| {
+        // Without a server message, the innermost cause says what went wrong
+        let cause = err.chain().last().map(|cause| cause.to_string()).unwrap_or_default();
+        (status.is_none() && cause != top).then_some(cause)
+    });
+
+    // The generated client's own prefix carries no information once the status is pulled out
+    let top = (!top.starts_with("Error Response:") && !top.starts_with("Unexpected Response:")).then_some(top);
+    let parts: Vec<String> = [top, status.map(|s| format!("HTTP {}", s)), message]
+        .into_iter()
+        .flatten()
+        .collect();
+    let line = parts.join(": ").split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() > COMPACT_MESSAGE_CHARS {
+        format!("{}…", line.chars().take(COMPACT_MESSAGE_CHARS - 1).collect::<String>())
+    } else {
+        line
+    }
+}
+
+/// "404 Not Found" from "... status: 404 Not Found; ..." (or just "404" from a debug-printed response)
+fn response_status(text: &str) -> Option<String> {
+    let rest = &text[text.find("status: ")? + "status: ".len()..];
+    let end = rest.find([';', ',', '}']).unwrap_or(rest.len());
+    let status = rest[..end].trim();
+    status.starts_with(|c: char| c.is_ascii_digit()).then(|| status.to_string())
+}
+
+/// The server's explanation: `detail`/`error`/`message` of a JSON body, or a validation `msg`
+fn response_detail(text: &str) -> Option<String> {
+    if let Some(at) = text.find("body: ") {
+        let body = text[at + "body: ".len()..].trim();
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(body) {
+            let detail = ["detail", "error", "message"].iter().find_map(|key| value.get(key));
+            return detail.map(|detail| match detail {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            });
+        }
+        return (!body.is_empty()).then(|| body.to_string());
+    }
+    let at = text.find("msg: \"")? + "msg: \"".len();
+    let end = text[at..].find('"')?;
+    Some(text[at..at + end].to_string())
+}
+
+/// A response was an HTML page rather than JSON, typically a proxy or web server
+/// answering in place of the API
+#[derive(Debug, thiserror::Error)]
+#[error("the server returned an HTML page")]
+pub struct HtmlResponse;
+
+/// An error for a response body the CLI could not use, marked with [`HtmlResponse`]
+/// when the body is an HTML page
+pub fn response_error(body: &[u8], message: String) -> anyhow::Error {
+    if is_html_body(body) {
+        anyhow::Error::new(HtmlResponse).context(message)
+    } else {
+        anyhow::Error::msg(message)
+    }
+}
+
+/// Whether a body starts like an HTML document
+fn is_html_body(body: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&body[..body.len().min(64)]).trim_start().to_ascii_lowercase();
+    head.starts_with("<!doctype html") || head.starts_with("<html")
+}
+
+fn is_html_content_type(headers: &reqwest::header::HeaderMap) -> bool {
+    headers
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.trim_start().to_ascii_lowercase().starts_with("text/html"))
+}
+
+fn client_error_is_html<E>(err: &hindsight_client::Error<E>) -> bool {
+    match err {
+        hindsight_client::Error::InvalidResponsePayload(body, _) => is_html_body(body),
+        hindsight_client::Error::UnexpectedResponse(response) => is_html_content_type(response.headers()),
+        hindsight_client::Error::ErrorResponse(response) => is_html_content_type(response.headers()),
+        _ => false,
+    }
+}
+
+/// Whether the failed response was an HTML page: judged by its content type, or the
+/// start of its body, never by text that merely mentions HTML
+fn is_html_response(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause.is::<HtmlResponse>()
+            || cause
+                .downcast_ref::<hindsight_client::Error<hindsight_client::types::HttpValidationError>>()
+                .is_some_and(client_error_is_html)
+            || cause.downcast_ref::<hindsight_client::Error<()>>().is_some_and(client_error_is_html)
+    })
+}
+
+/// Failures worth retrying: the request never got an answer, or the server (or a proxy
+/// in front of it) reported being overloaded or briefly unavailable
+pub fn is_transient(err: &anyhow::Error) -> bool {
+    let text = format!("{:#}", err);
+    let lower = text.to_ascii_lowercase();
+    ["error sending request", "connection refused", "tcp connect error", "connection reset", "timed out", "timeout"]
+        .iter()
+        .any(|needle| lower.contains(needle))
+        || ["429", "502", "503", "504"]
+            .iter()
+            .any(|status| text.contains(&format!("status: {}", status)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_html_response() {
+        let invalid = |body: &str| {
+            let error = serde_json::from_str::<serde_json::Value>(body).unwrap_err();
+            anyhow::Error::new(hindsight_client::Error::<()>::InvalidResponsePayload(body.as_bytes().to_vec().into(), error))
+        };
+        assert!(is_html_response(&invalid("\n<!DOCTYPE html>\n<html><body>Sign in</body></html>")));
+        assert!(!is_html_response(&invalid("{\"detail\": \"expected text/html or <html> input\"} trailing")));
+
+        let page = b"<HTML><body>Bad Gateway</body></HTML>";
+        assert!(is_html_response(&response_error(page, "Error Response: status: 502".to_string()).context("Chunk 1/2 failed")));
+        let json = br#"{"detail": "<html> is not allowed in content"}"#;
+        assert!(!is_html_response(&response_error(json, "Error Response: status: 400".to_string())));
+        assert!(!is_html_response(&anyhow::anyhow!("Error Response: status: 404; body: <html>")));
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::CONTENT_TYPE, "text/html; charset=utf-8".parse().unwrap());
+        assert!(is_html_content_type(&headers));
+        headers.insert(reqwest::header::CONTENT_TYPE, "application/json".parse().unwrap());
+        assert!(!is_html_content_type(&headers));
+    }
+
+    #[test]
+    fn test_compact_summary() {
+        let summary = |err: anyhow::Error| compact_summary(&err);
+        assert_eq!(
+            summary(anyhow::Error::msg("Error Response: status: 404 Not Found; body: {\"detail\": \"Bank not found\"}")),
+            "HTTP 404 Not Found: Bank not found"
+        );
+        assert_eq!(
+            summary(anyhow::Error::msg("Error Response: status: 422 Unprocessable Entity; headers: {}; value: HttpValidationError { detail: [ValidationError { loc: [], msg: \"field required\", type_: \"missing\" }] }")
+                .context("Chunk 1/2 failed")),
+            "Chunk 1/2 failed: HTTP 422 Unprocessable Entity: field required"
+        );
+        assert_eq!(
+            summary(anyhow::anyhow!("Connection refused (os error 111)").context("Communication Error: error sending request")),
+            "Communication Error: error sending request: Connection refused (os error 111)"
+        );
+        assert_eq!(summary(anyhow::anyhow!("Bank ID must not be empty")), "Bank ID must not be empty");
+        let long = summary(anyhow::anyhow!("Error Response: status: 500 Internal Server Error; body: {}", "x".repeat(500)));
+        assert_eq!(long.chars().count(), COMPACT_MESSAGE_CHARS);
+    }
+
+    #[test]
+    fn test_is_transient() {
+        let transient = |text: &str| is_transient(&anyhow::Error::msg(text.to_string()));
+        assert!(transient("Communication Error: error sending request for url (http://localhost:8888/)"));
+        assert!(transient("Error Response: status: 503 Service Unavailable; body: "));
+        assert!(transient("Unexpected Response: Response { url: \"http://h/\", status: 429, headers: {} }"));
+        assert!(is_transient(&anyhow::anyhow!("operation timed out").context("Failed to get document")));
+        assert!(!transient("Error Response: status: 404 Not Found; body: {}"));
+        assert!(!transient("Error Response: status: 422 Unprocessable Entity; body: {\"detail\": \"value 5030\"}"));
+    }
+}
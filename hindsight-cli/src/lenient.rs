@@ -0,0 +1,224 @@
+//! `--parse-lenient`: keep working when a response does not match the expected shape.
+//!
+//! A response that fails to deserialize is re-serialized one value per line, so the
+//! error's line number identifies the offending field. That field is dropped and the
+//! parse retried, which leaves optional fields at `None`/empty. A required field that
+//! is dropped turns into a "missing field" error on its parent, and so on up to the
+//! root, where the original error is returned: lenient mode never invents data.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// `items[3].tags`
+fn format_path(path: &[Segment]) -> String {
+    let mut out = String::new();
+    for segment in path {
+        match segment {
+            Segment::Key(key) if out.is_empty() => out.push_str(key),
+            Segment::Key(key) => {
+                out.push('.');
+                out.push_str(key);
+            }
+            Segment::Index(i) => out.push_str(&format!("[{}]", i)),
+        }
+    }
+    out
+}
+
+/// JSON text with one value per line, and the path of the value on each line
+#[derive(Default)]
+struct Tracked {
+    text: String,
+    lines: Vec<Vec<Segment>>,
+}
+
+impl Tracked {
+    fn end_line(&mut self, path: &[Segment]) {
+        self.text.push('\n');
+        self.lines.push(path.to_vec());
+    }
+
+    /// Write `value` from the current position. A container's closing bracket is
+    /// left on an open line, which the caller attributes to the container's path.
+    fn write(&mut self, value: &Value, path: &mut Vec<Segment>) {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                self.text.push('{');
+                self.end_line(path);
+                for (i, (key, child)) in map.iter().enumerate() {
+                    path.push(Segment::Key(key.clone()));
+                    self.text.push_str(&Value::String(key.clone()).to_string());
+                    self.text.push(':');
+                    self.write(child, path);
+                    if i + 1 < map.len() {
+                        self.text.push(',');
+                    }
+                    self.end_line(path);
+                    path.pop();
+                }
+                self.text.push('}');
+            }
+            Value::Array(items) if !items.is_empty() => {
+                self.text.push('[');
+                self.end_line(path);
+                for (i, child) in items.iter().enumerate() {
+                    path.push(Segment::Index(i));
+                    self.write(child, path);
+                    if i + 1 < items.len() {
+                        self.text.push(',');
+                    }
+                    self.end_line(path);
+                    path.pop();
+                }
+                self.text.push(']');
+            }
+            scalar => self.text.push_str(&scalar.to_string()),
+        }
+    }
+}
+
+/// Remove the object field at `path`; false if it is not there
+fn remove(value: &mut Value, path: &[Segment]) -> bool {
+    let Some((last, parents)) = path.split_last() else { return false };
+    let mut current = value;
+    for segment in parents {
+        let next = match (segment, current) {
+            (Segment::Key(key), Value::Object(map)) => map.get_mut(key),
+            (Segment::Index(i), Value::Array(items)) => items.get_mut(*i),
+            _ => None,
+        };
+        match next {
+            Some(next) => current = next,
+            None => return false,
+        }
+    }
+    match (last, current) {
+        (Segment::Key(key), Value::Object(map)) => map.remove(key).is_some(),
+        _ => false,
+    }
+}
+
+/// Parse `body`, dropping fields that fail to deserialize.
+/// Returns the value and the paths of the dropped fields.
+pub fn parse<T: DeserializeOwned>(body: &[u8]) -> Result<(T, Vec<String>), serde_json::Error> {
+    let mut value: Value = serde_json::from_slice(body)?;
+    let mut dropped = Vec::new();
+    let mut first_error = None;
+
+    loop {
+        let mut tracked = Tracked::default();
+        tracked.write(&value, &mut Vec::new());
+        tracked.end_line(&[]);
+
+        let error = match serde_json::from_str::<T>(&tracked.text) {
+            Ok(parsed) => return Ok((parsed, dropped)),
+            Err(e) => e,
+        };
+        if !error.is_data() {
+            return Err(first_error.unwrap_or(error));
+        }
+
+        // Array elements can only be dropped as a whole field, never individually
+        let mut path = error
+            .line()
+            .checked_sub(1)
+            .and_then(|line| tracked.lines.get(line))
+            .cloned()
+            .unwrap_or_default();
+        while matches!(path.last(), Some(Segment::Index(_))) {
+            path.pop();
+        }
+        if !remove(&mut value, &path) {
+            return Err(first_error.unwrap_or(error));
+        }
+        first_error.get_or_insert(error);
+        dropped.push(format_path(&path));
+    }
+}
+
+/// Path of the field that makes `body` fail to deserialize as `T`, if the failure
+/// can be pinned to one (a missing field belongs to no line, so it can't)
+pub fn locate<T: DeserializeOwned>(body: &[u8]) -> Option<String> {
+    let value: Value = serde_json::from_slice(body).ok()?;
+    let mut tracked = Tracked::default();
+    tracked.write(&value, &mut Vec::new());
+    tracked.end_line(&[]);
+
+    let error = serde_json::from_str::<T>(&tracked.text).err()?;
+    let path = tracked.lines.get(error.line().checked_sub(1)?)?;
+    (!path.is_empty()).then(|| format_path(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct Item {
+        id: String,
+        #[serde(default)]
+        score: Option<f64>,
+        #[serde(default)]
+        tags: Vec<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Page {
+        items: Vec<Item>,
+        #[serde(default)]
+        total: Option<i64>,
+    }
+
+    #[test]
+    fn test_parse_drops_mismatched_optional_fields() {
+        let body = br#"{"items": [{"id": "a", "score": "high"}, {"id": "b", "tags": ["x", 1]}], "total": 2}"#;
+        let (page, dropped) = parse::<Page>(body).unwrap();
+
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.items[0].score, None);
+        assert!(page.items[1].tags.is_empty());
+        assert_eq!(page.total, Some(2));
+        assert_eq!(dropped, vec!["items[0].score", "items[1].tags"]);
+    }
+
+    #[test]
+    fn test_parse_fails_when_a_required_field_is_renamed() {
+        let body = br#"{"items": [{"identifier": "a"}]}"#;
+        let error = parse::<Page>(body).unwrap_err();
+
+        assert!(error.to_string().contains("missing field `id`"), "{}", error);
+    }
+
+    #[test]
+    fn test_locate_names_the_failing_field() {
+        assert_eq!(locate::<Page>(br#"{"items": [{"id": "a"}, {"id": "b", "score": "x"}]}"#).as_deref(), Some("items[1].score"));
+        assert_eq!(locate::<Page>(br#"{"items": []}"#), None);
+    }
+
+    #[test]
+    fn test_parse_keeps_valid_responses_unchanged() {
+        let (page, dropped) = parse::<Page>(br#"{"items": [], "total": 0}"#).unwrap();
+
+        assert!(page.items.is_empty());
+        assert!(dropped.is_empty());
+    }
+}
@@ -1,14 +1,24 @@
 mod api;
+mod bulk;
+mod cache;
 mod commands;
 mod config;
+mod curl;
+mod duration;
 mod errors;
+mod lenient;
+mod memo;
 mod output;
+mod pager;
+mod suggest;
+mod table;
+mod timestamp;
 mod ui;
 mod utils;
 
 use anyhow::Result;
-use api::ApiClient;
-use clap::{Parser, Subcommand, ValueEnum};
+use api::{ApiClient, CustomHeader};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use config::Config;
 use output::OutputFormat;
 use std::path::PathBuf;
@@ -18,6 +28,8 @@ enum Format {
     Pretty,
     Json,
     Yaml,
+    Raw,
+    Table,
 }
 
 impl From<Format> for OutputFormat {
@@ -26,6 +38,8 @@ impl From<Format> for OutputFormat {
             Format::Pretty => OutputFormat::Pretty,
             Format::Json => OutputFormat::Json,
             Format::Yaml => OutputFormat::Yaml,
+            Format::Raw => OutputFormat::Raw,
+            Format::Table => OutputFormat::Table,
         }
     }
 }
@@ -37,7 +51,10 @@ impl From<Format> for OutputFormat {
 #[command(before_help = get_before_help())]
 #[command(after_help = get_after_help())]
 struct Cli {
-    /// Output format (pretty, json, yaml)
+    /// Output format (pretty, json, yaml, raw, table). 'raw' prints only the main text, unstyled:
+    /// fact texts for memory recall, the answer for memory reflect, the original text
+    /// for document get, and the content for mental-model get and directive get.
+    /// 'table' prints aligned columns (see --columns)
     #[arg(short = 'o', long, global = true, default_value = "pretty")]
     output: Format,
 
@@ -45,10 +62,311 @@ struct Cli {
     #[arg(short = 'v', long, global = true)]
     verbose: bool,
 
+    /// Always page long output through $PAGER (default: only with less, when it doesn't fit the terminal; PAGER= disables)
+    #[arg(long, global = true, conflicts_with = "no_pager")]
+    pager: bool,
+
+    /// Never page output
+    #[arg(long, global = true)]
+    no_pager: bool,
+
+    /// Bypass the on-disk cache of bank lists and profiles
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// Always send searches to the server, even when an identical one was just made
+    #[arg(long, global = true)]
+    no_memo: bool,
+
+    /// How long an identical search is answered from memory instead of the server (0s disables)
+    #[arg(long, global = true, value_name = "DURATION", default_value = "5s", value_parser = duration::parse_duration)]
+    memo_ttl: std::time::Duration,
+
+    /// Fail instead of falling back to the default server when none is configured
+    #[arg(long, global = true, env = "HINDSIGHT_REQUIRE_EXPLICIT_SERVER")]
+    require_explicit_server: bool,
+
+    /// Request ID sent as X-Request-ID on every request (default: a new UUID per invocation)
+    #[arg(long, global = true, value_name = "ID")]
+    request_id: Option<String>,
+
+    /// Give up on --wait polling after this long, e.g. 90s, 10m, 1h (exit code 124)
+    #[arg(long, global = true, value_name = "DURATION", default_value = "10m", allow_hyphen_values = true, value_parser = duration::parse_duration)]
+    wait_timeout: std::time::Duration,
+
+    /// Cancel the operation when --wait-timeout is exceeded
+    #[arg(long, global = true)]
+    cancel_on_timeout: bool,
+
+    /// Show the progress spinner even when stdout is not a terminal
+    #[arg(long, global = true, conflicts_with = "no_spinner")]
+    spinner: bool,
+
+    /// Never show the progress spinner
+    #[arg(long, global = true)]
+    no_spinner: bool,
+
+    /// Spinner look (falls back to 'simple' when stdout is not a terminal)
+    #[arg(long, global = true, value_enum, value_name = "STYLE", env = "HINDSIGHT_SPINNER_STYLE")]
+    spinner_style: Option<ui::SpinnerStyle>,
+
+    /// Print the total wall-clock time of the command to stderr when it finishes
+    #[arg(long, global = true)]
+    show_elapsed: bool,
+
+    /// Print recalled and source facts one per line: '<id> [type] <text preview>'
+    #[arg(long, global = true)]
+    compact_facts: bool,
+
+    /// Characters of fact text shown in pretty fact views; 0 shows the full text
+    /// (default: previews fit the terminal, detailed views show the full text)
+    #[arg(long, global = true, value_name = "N")]
+    max_text_width: Option<usize>,
+
+    /// Show each fact's context, in the compact layout too (default: detailed view only)
+    #[arg(long, global = true, conflicts_with = "hide_context")]
+    show_context: bool,
+
+    /// Leave fact context out of every fact display
+    #[arg(long, global = true)]
+    hide_context: bool,
+
+    /// Show timestamps in UTC instead of the local timezone
+    #[arg(long, global = true)]
+    utc: bool,
+
+    /// Use plain ASCII status symbols ([OK], [!], [x]); the default when the locale is not UTF-8
+    #[arg(long, global = true)]
+    ascii: bool,
+
+    /// Thousands separator for counts in pretty output
+    #[arg(long, global = true, value_enum, value_name = "STYLE", default_value = "comma")]
+    locale: ui::NumberLocale,
+
+    /// Decimal places for activation scores and timings in pretty output
+    #[arg(long, global = true, value_name = "N", default_value_t = 3)]
+    precision: usize,
+
+    /// Tenant id inserted into API paths as /tenants/<id>/ (overrides HINDSIGHT_TENANT and the config file)
+    #[arg(long, global = true, value_name = "ID", value_parser = config::validate_tenant)]
+    tenant: Option<String>,
+
+    /// Default bank for mental model, directive and repl commands when BANK_ID is omitted
+    /// (overrides HINDSIGHT_BANK and `bank` in the config file). Other commands take
+    /// their bank as a required argument and ignore it. With `configure`, saves it
+    /// as the default in the config file.
+    #[arg(long, global = true, value_name = "ID")]
+    bank: Option<String>,
+
+    /// Fact type shorthand as ALIAS=TYPE, e.g. 'w=world' (repeatable; adds to
+    /// `[fact_type_aliases]` in the config file)
+    #[arg(long, global = true, value_name = "ALIAS=TYPE", value_delimiter = ',', value_parser = parse_fact_type_alias)]
+    fact_type_map: Vec<(String, String)>,
+
+    /// Print each API request as a curl command on stderr; '--print-curl=exit' stops before sending
+    #[arg(long, global = true, value_enum, value_name = "MODE", num_args = 0..=1, default_missing_value = "send", require_equals = true)]
+    print_curl: Option<curl::CurlMode>,
+
+    /// Extra header sent with every request, as 'Name: Value' (repeatable)
+    #[arg(long = "header", short = 'H', global = true, value_name = "HEADER", value_parser = CustomHeader::parse)]
+    headers: Vec<CustomHeader>,
+
+    /// Drop response fields that fail to parse instead of failing the command
+    /// (for servers on a slightly different version; --verbose lists the fields)
+    #[arg(long, global = true)]
+    parse_lenient: bool,
+
+    /// Answer yes to every confirmation prompt. Without it, prompts are declined
+    /// when stdin is not a terminal (e.g. in CI), instead of waiting for input
+    #[arg(long, global = true, env = "HINDSIGHT_ASSUME_YES", value_parser = clap::builder::FalseyValueParser::new())]
+    assume_yes: bool,
+
+    /// Spaces per nesting level in JSON output
+    #[arg(long, global = true, value_name = "N", default_value_t = 2, value_parser = clap::value_parser!(u8).range(0..=16))]
+    indent: u8,
+
+    /// Print JSON output on a single line
+    #[arg(long, global = true, conflicts_with = "indent")]
+    compact: bool,
+
+    /// Leave null-valued keys out of JSON/YAML output instead of printing them as null
+    #[arg(long, global = true)]
+    omit_null: bool,
+
+    /// Wrap JSON/YAML output as {"ok": true, "command": ..., "data": ...}, and print
+    /// {"ok": false, "command": ..., "error": ...} on stdout when the command fails
+    #[arg(long, global = true)]
+    envelope: bool,
+
+    /// Report a failure as one line on stderr: status and short message (add -v for
+    /// the full error)
+    #[arg(long, global = true)]
+    compact_errors: bool,
+
+    /// Add a "meta" object to JSON/YAML output: CLI version, server URL, request ID,
+    /// start time, elapsed time and the command's effective arguments
+    #[arg(long, global = true)]
+    with_meta: bool,
+
+    /// Columns for --output table, as comma-separated dotted paths into the response,
+    /// e.g. 'nodes_by_fact_type.episodic,pending_operations' (default: the scalar
+    /// top-level fields)
+    #[arg(long, global = true, value_name = "PATHS", value_delimiter = ',')]
+    columns: Vec<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// Client-side paging for list commands whose endpoint returns everything
+#[derive(Args, Debug, Clone, Copy)]
+struct PageArgs {
+    /// Show only this page of results (1-based; default page size 20)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    page: Option<u64>,
+
+    /// Results per page (implies --page 1)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..=1000))]
+    page_size: Option<u64>,
+}
+
+impl PageArgs {
+    const DEFAULT_PAGE_SIZE: u64 = 20;
+
+    fn request(&self) -> Option<output::PageRequest> {
+        if self.page.is_none() && self.page_size.is_none() {
+            return None;
+        }
+        Some(output::PageRequest {
+            page: self.page.unwrap_or(1) as usize,
+            page_size: self.page_size.unwrap_or(Self::DEFAULT_PAGE_SIZE) as usize,
+        })
+    }
+}
+
+/// Which directives `directive enable`/`disable` toggle
+#[derive(Args, Debug)]
+struct DirectiveToggleArgs {
+    /// Bank ID (may be omitted when --bank, HINDSIGHT_BANK or `bank` in the config file is set)
+    bank_id: Option<String>,
+
+    /// Toggle every directive in the bank
+    #[arg(long, required_unless_present = "tag", conflicts_with = "tag")]
+    all: bool,
+
+    /// Toggle only directives with this tag (repeatable; any tag matches)
+    #[arg(long, value_name = "NAME", value_parser = parse_non_blank)]
+    tag: Vec<String>,
+
+    /// Update up to N directives in parallel
+    #[arg(long, value_name = "N", default_value_t = bulk::DEFAULT_CONCURRENCY as u64, value_parser = clap::value_parser!(u64).range(1..=64))]
+    concurrency: u64,
+
+    #[command(flatten)]
+    errors: BulkErrorArgs,
+}
+
+/// Error handling shared by bulk commands
+#[derive(Args, Debug, Clone, Copy)]
+struct BulkErrorArgs {
+    /// Stop at the first failed item
+    #[arg(long, conflicts_with = "collect_errors")]
+    fail_fast: bool,
+
+    /// Process every item, then print an error summary and exit non-zero if any failed (default)
+    #[arg(long)]
+    collect_errors: bool,
+
+    /// Times to retry an item after a transient failure (connection error, timeout,
+    /// 429 or 5xx gateway status)
+    #[arg(long, value_name = "N", default_value_t = bulk::DEFAULT_RETRIES)]
+    retries: usize,
+
+    /// Retries the whole command may spend across all items; once used up, the next
+    /// transient failure stops the command even with --collect-errors
+    #[arg(long, value_name = "N", default_value_t = bulk::DEFAULT_MAX_TOTAL_RETRIES)]
+    max_total_retries: usize,
+}
+
+impl BulkErrorArgs {
+    /// Install this command's retry budget and return how it handles failed items
+    fn apply(&self) -> bulk::ErrorMode {
+        bulk::set_retries(self.retries, self.max_total_retries);
+        if self.fail_fast {
+            bulk::ErrorMode::FailFast
+        } else {
+            bulk::ErrorMode::CollectErrors
+        }
+    }
+}
+
+fn parse_ratio(raw: &str) -> Result<f64, String> {
+    let value: f64 = raw.parse().map_err(|_| format!("'{}' is not a number", raw))?;
+    if !(0.0..=1.0).contains(&value) {
+        return Err(format!("must be between 0.0 and 1.0, got {}", value));
+    }
+    Ok(value)
+}
+
+fn parse_temperature(raw: &str) -> Result<f64, String> {
+    let value: f64 = raw.parse().map_err(|_| format!("'{}' is not a number", raw))?;
+    if !(value >= 0.0 && value.is_finite()) {
+        return Err(format!("must be 0 or more, got {}", value));
+    }
+    Ok(value)
+}
+
+fn parse_top_p(raw: &str) -> Result<f64, String> {
+    let value: f64 = raw.parse().map_err(|_| format!("'{}' is not a number", raw))?;
+    if !(value > 0.0 && value <= 1.0) {
+        return Err(format!("must be greater than 0.0 and at most 1.0, got {}", value));
+    }
+    Ok(value)
+}
+
+/// Create commands take `[BANK_ID] <NAME> <TEXT>`, which clap can't express directly:
+/// with only two positionals given, they are the name and text and the bank comes from --bank.
+fn shift_bank_positional(first: String, second: String, third: Option<String>) -> (Option<String>, String, String) {
+    match third {
+        Some(third) => (Some(first), second, third),
+        None => (None, first, second),
+    }
+}
+
+/// `spinner_style` in the config file; an unknown value is reported and ignored
+fn spinner_style_from_config() -> Option<ui::SpinnerStyle> {
+    let raw = Config::file_setting("spinner_style")?;
+    match ui::SpinnerStyle::from_str(&raw, true) {
+        Ok(style) => Some(style),
+        Err(_) => {
+            ui::print_warning(&format!("Ignoring unknown spinner_style '{}' in the config file", raw));
+            None
+        }
+    }
+}
+
+/// Trim a bank id (and lowercase it when configured), rejecting an empty one
+fn parse_bank_id(raw: &str) -> Result<String, String> {
+    config::normalize_bank_id(raw).map_err(|e| e.to_string())
+}
+
+/// Reject empty or whitespace-only text before it reaches the server
+fn parse_non_blank(raw: &str) -> Result<String, String> {
+    if raw.trim().is_empty() {
+        return Err("must not be empty or whitespace-only".to_string());
+    }
+    Ok(raw.to_string())
+}
+
+fn parse_fact_type_alias(raw: &str) -> Result<(String, String), String> {
+    match raw.split_once('=') {
+        Some((alias, fact_type)) if !alias.trim().is_empty() && !fact_type.trim().is_empty() => {
+            Ok((alias.trim().to_string(), fact_type.trim().to_string()))
+        }
+        _ => Err(format!("expected ALIAS=TYPE, got '{}'", raw)),
+    }
+}
+
 fn get_after_help() -> String {
     let config = config::Config::load().ok();
     let (api_url, source) = match &config {
@@ -71,11 +389,11 @@ enum Commands {
     #[command(subcommand)]
     Bank(BankCommands),
 
-    /// Manage memories (list, get, recall, reflect, retain, clear)
+    /// Manage memories (list, get, recall, reflect, retain, import, clear)
     #[command(subcommand)]
     Memory(MemoryCommands),
 
-    /// Manage documents (list, get, delete)
+    /// Manage documents (list, get, manifest, sync, delete)
     #[command(subcommand)]
     Document(DocumentCommands),
 
@@ -112,13 +430,55 @@ enum Commands {
     /// Get API version information
     Version,
 
+    /// Show which optional features and request fields the server supports
+    Capabilities,
+
+    /// Send a request to any API endpoint and print the raw response
+    #[command(after_help = "Examples:\n  hindsight raw GET /v1/default/banks\n  hindsight raw POST /v1/default/banks/my-bank/memories/recall --data '{\"query\": \"coffee\"}'")]
+    Raw {
+        /// HTTP method
+        #[arg(value_enum, ignore_case = true)]
+        method: commands::raw::RawMethod,
+
+        /// Path relative to the API URL, e.g. /v1/default/banks (query string allowed)
+        path: String,
+
+        /// JSON request body
+        #[arg(short = 'd', long)]
+        data: Option<String>,
+    },
+
     /// Interactive TUI explorer (k9s-style) for navigating banks, memories, entities, and performing recall/reflect
     #[command(alias = "tui")]
     Explore,
 
+    /// Interactive REPL for recall/reflect queries with persistent history
+    Repl {
+        /// Bank ID (may be omitted when --agent, --bank, HINDSIGHT_BANK or `bank` in the config file is set)
+        #[arg(value_parser = parse_bank_id)]
+        bank_id: Option<String>,
+
+        /// Bank ID, in place of the positional argument
+        #[arg(long, value_name = "ID", value_parser = parse_bank_id, conflicts_with = "bank_id")]
+        agent: Option<String>,
+    },
+
     /// Launch the web-based control plane UI
     Ui,
 
+    /// Manage the on-disk response cache
+    #[command(subcommand)]
+    Cache(CacheCommands),
+
+    /// Show the effective configuration and where each setting came from (secrets redacted)
+    Env,
+
+    /// Show example invocations (e.g. 'hindsight examples memory recall')
+    Examples {
+        /// Command to show examples for (default: all)
+        command: Vec<String>,
+    },
+
     /// Configure the CLI (API URL, API key, etc.)
     #[command(after_help = "Configuration priority:\n  1. Environment variables (HINDSIGHT_API_URL, HINDSIGHT_API_KEY) - highest priority\n  2. Config file (~/.hindsight/config)\n  3. Default (http://localhost:8888)")]
     Configure {
@@ -131,14 +491,25 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum CacheCommands {
+    /// Remove all cached responses
+    Clear,
+}
+
 #[derive(Subcommand)]
 enum BankCommands {
     /// List all banks
-    List,
+    List {
+        /// Print only the number of banks
+        #[arg(long)]
+        count_only: bool,
+    },
 
     /// Create a new bank
     Create {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Bank name
@@ -149,6 +520,10 @@ enum BankCommands {
         #[arg(short = 'm', long)]
         mission: Option<String>,
 
+        /// Starting disposition; --skepticism/--literalism/--empathy override single traits
+        #[arg(long, value_enum, visible_alias = "personality")]
+        disposition: Option<commands::bank::DispositionPreset>,
+
         /// Skepticism trait (1-5)
         #[arg(long, value_parser = clap::value_parser!(i64).range(1..=5))]
         skepticism: Option<i64>,
@@ -160,11 +535,16 @@ enum BankCommands {
         /// Empathy trait (1-5)
         #[arg(long, value_parser = clap::value_parser!(i64).range(1..=5))]
         empathy: Option<i64>,
+
+        /// Background merged into the mission after creation
+        #[arg(long, value_parser = parse_non_blank)]
+        background: Option<String>,
     },
 
     /// Update bank properties (partial update)
     Update {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Bank name
@@ -191,18 +571,33 @@ enum BankCommands {
     /// Get bank disposition and profile
     Disposition {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
     },
 
+    /// Change some disposition traits, keeping the others as stored
+    PatchDisposition {
+        /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
+        bank_id: String,
+
+        /// Trait to change, as TRAIT=VALUE (skepticism, literalism or empathy; 1-5).
+        /// Repeat for several traits
+        #[arg(long = "set", value_name = "TRAIT=VALUE", required = true, value_parser = commands::bank::parse_trait_setting)]
+        settings: Vec<(commands::bank::DispositionTrait, u64)>,
+    },
+
     /// Get memory statistics for a bank
     Stats {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
     },
 
     /// Set bank name
     Name {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Bank name
@@ -212,6 +607,7 @@ enum BankCommands {
     /// Set bank mission
     Mission {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Mission statement
@@ -222,6 +618,7 @@ enum BankCommands {
     #[command(hide = true)]
     Background {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Background content
@@ -235,6 +632,7 @@ enum BankCommands {
     /// Get memory graph data
     Graph {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Filter by fact type (world, experience, opinion)
@@ -249,9 +647,10 @@ enum BankCommands {
     /// Delete a bank and all its data
     Delete {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
-        /// Skip confirmation prompt
+        /// Skip the confirmation prompt (which otherwise asks you to type the bank ID)
         #[arg(short = 'y', long)]
         yes: bool,
     },
@@ -259,20 +658,22 @@ enum BankCommands {
     /// Trigger consolidation to create/update observations
     Consolidate {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Wait for consolidation to complete (poll for status)
         #[arg(long)]
         wait: bool,
 
-        /// Poll interval in seconds (only used with --wait)
-        #[arg(long, default_value = "10")]
-        poll_interval: u64,
+        /// Poll interval, e.g. 10s or 1m (only used with --wait)
+        #[arg(long, default_value = "10s", allow_hyphen_values = true, value_parser = duration::parse_duration)]
+        poll_interval: std::time::Duration,
     },
 
     /// Clear all observations for a bank
     ClearObservations {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Skip confirmation prompt
@@ -286,6 +687,7 @@ enum MemoryCommands {
     /// List memory units with pagination
     List {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Filter by fact type (world, experience, opinion)
@@ -303,11 +705,15 @@ enum MemoryCommands {
         /// Offset for pagination
         #[arg(short = 's', long, default_value = "0")]
         offset: i64,
+        /// Print only the number of results
+        #[arg(long)]
+        count_only: bool,
     },
 
     /// Get a specific memory unit by ID
     Get {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Memory unit ID
@@ -317,22 +723,29 @@ enum MemoryCommands {
     /// Recall memories using semantic search
     Recall {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
-        /// Search query
-        query: String,
+        /// Search query. Several queries run as separate recalls (results are not
+        /// combined with AND/OR) and are printed grouped by query.
+        #[arg(required_unless_present_any = ["queries_file", "stdin_json"], value_parser = parse_non_blank)]
+        query: Vec<String>,
+
+        /// Read additional queries from a file, one per line ('#' starts a comment)
+        #[arg(long, value_name = "FILE")]
+        queries_file: Option<PathBuf>,
 
         /// Fact types to search (world, experience, opinion)
-        #[arg(short = 't', long, value_delimiter = ',', default_values = &["world", "experience", "opinion"])]
+        #[arg(short = 't', long, value_delimiter = ',', default_values = commands::memory::DEFAULT_FACT_TYPES)]
         fact_type: Vec<String>,
 
         /// Thinking budget (low, mid, high)
-        #[arg(short = 'b', long, default_value = "mid")]
+        #[arg(short = 'b', long, default_value = commands::memory::DEFAULT_BUDGET)]
         budget: String,
 
-        /// Maximum tokens for results
-        #[arg(long, default_value = "4096")]
-        max_tokens: i64,
+        /// Maximum tokens for results (default: derived from --budget; low 2048, mid 4096, high 8192)
+        #[arg(long)]
+        max_tokens: Option<i64>,
 
         /// Show trace information
         #[arg(long)]
@@ -343,42 +756,190 @@ enum MemoryCommands {
         include_chunks: bool,
 
         /// Maximum tokens for chunks (only used with --include-chunks)
-        #[arg(long, default_value = "8192")]
+        #[arg(long, default_value_t = commands::memory::DEFAULT_CHUNK_MAX_TOKENS)]
         chunk_max_tokens: i64,
+
+        /// Only show results whose context contains this substring (case-insensitive)
+        #[arg(long, value_name = "SUBSTR")]
+        context_filter: Option<String>,
+
+        /// Only show facts extracted from this document. Recall has no server-side
+        /// document filter, so this filters the returned results; raise --max-tokens
+        /// or --budget if too few remain.
+        #[arg(long, value_name = "ID")]
+        document_id: Option<String>,
+
+        /// Collapse results with identical text, keeping the highest-ranked one
+        #[arg(long)]
+        dedupe: bool,
+
+        /// Also collapse near-duplicates at or above this similarity (0.0-1.0, normalized edit distance)
+        #[arg(long, value_name = "RATIO", requires = "dedupe", value_parser = parse_ratio)]
+        dedupe_similarity: Option<f64>,
+
+        /// Re-sort results client-side. Facts without an event date keep their position;
+        /// dated facts are reordered among themselves
+        #[arg(long, value_enum, value_name = "ORDER")]
+        rerank: Option<commands::memory::RecallRerank>,
+
+        /// Share of recency in '--rerank blend' (0.0 = server ranking only, 1.0 = recency only)
+        #[arg(long, value_name = "RATIO", default_value = "0.5", value_parser = parse_ratio)]
+        recency_weight: f64,
+
+        /// Print request fields that differ from their defaults
+        #[arg(long)]
+        show_overrides: bool,
+
+        /// Format each result with a template, e.g. '{{id}} {{text}}' (fields match the JSON output)
+        #[arg(long, value_name = "TEMPLATE")]
+        format: Option<String>,
+
+        /// Append server and client timings for this recall as a row to a CSV file
+        #[arg(long, value_name = "FILE")]
+        trace_csv: Option<PathBuf>,
+
+        /// Read the complete recall request as JSON from stdin instead of building it
+        /// from QUERY and the request flags (client-side filters still apply)
+        #[arg(long, conflicts_with_all = ["query", "queries_file", "fact_type", "budget", "max_tokens", "chunk_max_tokens"])]
+        stdin_json: bool,
+
+        /// Run the query N times and report client and server latency (min, mean,
+        /// median, p95, max) instead of the results
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..), conflicts_with_all = ["format", "trace_csv"])]
+        benchmark: Option<u64>,
+
+        /// Extra runs before --benchmark starts measuring, left out of the statistics
+        #[arg(long, value_name = "N", default_value_t = 1, requires = "benchmark")]
+        warmup: u64,
+
+        /// After the results, list each result's rank, activation, and activation as a
+        /// percentage of the highest one
+        #[arg(long, conflicts_with_all = ["format", "benchmark"])]
+        explain_scores: bool,
+
+        /// Exit with code 3 when the recall succeeds but returns no results (with
+        /// several queries: when none of them returns any); results are still printed
+        #[arg(long, conflicts_with = "benchmark")]
+        fail_if_empty: bool,
+    },
+
+    /// Run every query in a CSV column and write the top results to a CSV file
+    /// (query, rank, result_id, activation, result_count)
+    RecallBatch {
+        /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
+        bank_id: String,
+
+        /// CSV file with a header row
+        #[arg(long, value_name = "FILE")]
+        input: PathBuf,
+
+        /// CSV file to write the results to (`--output` is the global output format)
+        #[arg(long, value_name = "FILE")]
+        results: PathBuf,
+
+        /// Column of --input holding the queries
+        #[arg(long, value_name = "NAME", default_value = "query")]
+        query_column: String,
+
+        /// Result rows to write per query
+        #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+        top: u64,
+
+        /// Fact types to search (world, experience, opinion)
+        #[arg(short = 't', long, value_delimiter = ',', default_values = commands::memory::DEFAULT_FACT_TYPES)]
+        fact_type: Vec<String>,
+
+        /// Thinking budget (low, mid, high)
+        #[arg(short = 'b', long, default_value = commands::memory::DEFAULT_BUDGET)]
+        budget: String,
+
+        /// Maximum tokens for results (default: derived from --budget; low 2048, mid 4096, high 8192)
+        #[arg(long)]
+        max_tokens: Option<i64>,
+
+        /// Run up to N queries in parallel
+        #[arg(long, value_name = "N", default_value_t = bulk::DEFAULT_CONCURRENCY as u64, value_parser = clap::value_parser!(u64).range(1..=64))]
+        concurrency: u64,
+
+        #[command(flatten)]
+        errors: BulkErrorArgs,
     },
 
     /// Generate answers using bank identity (reflect/reasoning)
     Reflect {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Query to reflect on
-        query: String,
+        #[arg(required_unless_present = "stdin_json")]
+        query: Option<String>,
 
         /// Thinking budget (low, mid, high)
-        #[arg(short = 'b', long, default_value = "mid")]
+        #[arg(short = 'b', long, default_value = commands::memory::DEFAULT_BUDGET)]
         budget: String,
 
         /// Additional context
         #[arg(short = 'c', long)]
         context: Option<String>,
 
-        /// Maximum tokens for the response (server default: 4096)
+        /// Maximum tokens for the response (default: derived from --budget; low 2048, mid 4096, high 8192)
         #[arg(short = 'm', long)]
         max_tokens: Option<i64>,
 
         /// Path to JSON schema file for structured output
         #[arg(short = 's', long)]
         schema: Option<PathBuf>,
+
+        /// Only show the memories the reflection was based on
+        #[arg(long)]
+        sources_only: bool,
+
+        /// Show the tool and LLM calls made during reflection
+        #[arg(long)]
+        trace: bool,
+
+        /// Sampling temperature for the answer (0 or more; lower is more deterministic)
+        #[arg(long, value_parser = parse_temperature)]
+        temperature: Option<f64>,
+
+        /// Nucleus sampling cutoff for the answer, in (0, 1]
+        #[arg(long, value_parser = parse_top_p)]
+        top_p: Option<f64>,
+
+        /// Seed for reproducible answers on servers that support seeding
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Read the complete reflect request as JSON from stdin instead of building it
+        /// from QUERY and the request flags (--temperature, --top-p and --seed still apply)
+        #[arg(long, conflicts_with_all = ["query", "budget", "context", "max_tokens", "schema", "sources_only", "trace"])]
+        stdin_json: bool,
+
+        /// Print request fields that differ from their defaults
+        #[arg(long)]
+        show_overrides: bool,
     },
 
     /// Store (retain) a single memory
     Retain {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Memory content
-        content: String,
+        #[arg(required_unless_present = "from_markdown")]
+        content: Option<String>,
+
+        /// Split a Markdown file into memory units instead of storing CONTENT,
+        /// all under one document; each unit's heading becomes its context
+        #[arg(long, value_name = "FILE", conflicts_with = "content")]
+        from_markdown: Option<PathBuf>,
+
+        /// How --from-markdown splits the file [default: heading]
+        #[arg(long, value_enum, requires = "from_markdown", conflicts_with = "content")]
+        split_by: Option<commands::memory::MarkdownSplit>,
 
         /// Document ID (auto-generated if not provided)
         #[arg(short = 'd', long)]
@@ -391,11 +952,16 @@ enum MemoryCommands {
         /// Queue for background processing
         #[arg(long)]
         r#async: bool,
+
+        /// Idempotency key for deduplicating retried submissions (default: hash of the request)
+        #[arg(long)]
+        idempotency_key: Option<String>,
     },
 
     /// Bulk import memories from files (retain)
     RetainFiles {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Path to file or directory
@@ -412,11 +978,72 @@ enum MemoryCommands {
         /// Queue for background processing
         #[arg(long)]
         r#async: bool,
+
+        /// Maximum number of items per retain request
+        #[arg(long, default_value = "100", value_parser = clap::value_parser!(u64).range(1..))]
+        chunk_size: u64,
+
+        /// Idempotency key for deduplicating retried submissions (default: hash of the request)
+        #[arg(long)]
+        idempotency_key: Option<String>,
+
+        #[command(flatten)]
+        errors: BulkErrorArgs,
+    },
+
+    /// Import memories from a JSON array or JSONL file of memory items (retain)
+    Import {
+        /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
+        bank_id: String,
+
+        /// Path to a .json (array of items) or .jsonl (one item per line) file
+        file: PathBuf,
+
+        /// Abort on the first invalid record instead of reporting all of them
+        #[arg(long)]
+        strict: bool,
+
+        /// Skip confirmation prompt when some records are invalid
+        #[arg(short = 'y', long)]
+        yes: bool,
+
+        /// Queue for background processing
+        #[arg(long)]
+        r#async: bool,
+
+        /// Maximum number of items per retain request
+        #[arg(long, default_value = "100", value_parser = clap::value_parser!(u64).range(1..))]
+        chunk_size: u64,
+
+        /// Store all imported items under this document ID
+        #[arg(long)]
+        document_id: Option<String>,
+
+        /// Generate a UUID document ID and store all imported items under it
+        #[arg(long, conflicts_with = "document_id")]
+        auto_document_id: bool,
+
+        /// Context applied to every record that doesn't set its own
+        #[arg(short = 'c', long)]
+        context: Option<String>,
+
+        /// Drop records whose content and context repeat an earlier record in the file
+        #[arg(long)]
+        dedupe_within_batch: bool,
+
+        /// Idempotency key for deduplicating retried submissions (default: hash of the request)
+        #[arg(long)]
+        idempotency_key: Option<String>,
+
+        #[command(flatten)]
+        errors: BulkErrorArgs,
     },
 
     /// Delete a memory unit
     Delete {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Memory unit ID
@@ -426,6 +1053,7 @@ enum MemoryCommands {
     /// Clear all memories for a bank
     Clear {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Fact type to clear (world, agent, opinion). If not specified, clears all types.
@@ -443,6 +1071,7 @@ enum DocumentCommands {
     /// List documents for a bank
     List {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Search query to filter documents
@@ -460,20 +1089,77 @@ enum DocumentCommands {
         /// Offset for pagination
         #[arg(short = 's', long, default_value = "0")]
         offset: i32,
+
+        /// Fetch every page, printing each as it arrives
+        #[arg(long, conflicts_with_all = ["date", "limit", "offset", "count_only"])]
+        all: bool,
+
+        /// Format each result with a template, e.g. '{{id}} {{text}}' (fields match the JSON output)
+        #[arg(long, value_name = "TEMPLATE")]
+        format: Option<String>,
+        /// Print only the number of results
+        #[arg(long, conflicts_with = "format")]
+        count_only: bool,
+
+        /// Exit with code 3 when no documents are listed (the listing is still printed)
+        #[arg(long)]
+        fail_if_empty: bool,
     },
 
     /// Get a specific document by ID
     Get {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Document ID
         document_id: String,
     },
 
+    /// Print a manifest of every document (id, content hash, update time, unit count)
+    /// for detecting changes between runs
+    Manifest {
+        /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
+        bank_id: String,
+
+        /// Print CSV instead of JSON
+        #[arg(long, conflicts_with = "since")]
+        csv: bool,
+
+        /// Instead of the manifest, list documents added, changed or removed since
+        /// this earlier JSON manifest
+        #[arg(long, value_name = "FILE")]
+        since: Option<PathBuf>,
+    },
+
+    /// Export documents changed since the last sync into a directory (one JSON file
+    /// each) and update the manifest kept there
+    Sync {
+        /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
+        bank_id: String,
+
+        /// Directory holding the manifest.json and a documents/ subdirectory with
+        /// the exported documents
+        dir: PathBuf,
+
+        /// Compare against this manifest instead of DIR/manifest.json
+        #[arg(long, value_name = "FILE")]
+        since: Option<PathBuf>,
+
+        /// Fetch up to N documents in parallel
+        #[arg(long, value_name = "N", default_value_t = bulk::DEFAULT_CONCURRENCY as u64, value_parser = clap::value_parser!(u64).range(1..=64))]
+        concurrency: u64,
+
+        #[command(flatten)]
+        errors: BulkErrorArgs,
+    },
+
     /// Delete a document and all its memory units
     Delete {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Document ID
@@ -486,16 +1172,21 @@ enum EntityCommands {
     /// List entities for a bank
     List {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Maximum number of results
         #[arg(short = 'l', long, default_value = "100")]
         limit: i64,
+        /// Print only the number of results
+        #[arg(long)]
+        count_only: bool,
     },
 
     /// Get detailed information about an entity
     Get {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Entity ID
@@ -505,6 +1196,7 @@ enum EntityCommands {
     /// Regenerate observations for an entity
     Regenerate {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Entity ID
@@ -517,25 +1209,51 @@ enum OperationCommands {
     /// List async operations for a bank
     List {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
+
+        /// Format each result with a template, e.g. '{{id}} {{text}}' (fields match the JSON output)
+        #[arg(long, value_name = "TEMPLATE")]
+        format: Option<String>,
+        /// Print only the number of results
+        #[arg(long, conflicts_with = "format")]
+        count_only: bool,
     },
 
     /// Get the status of a specific operation
     Get {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Operation ID
         operation_id: String,
     },
 
-    /// Cancel a pending async operation
+    /// Cancel a pending async operation (or all pending operations with --all)
     Cancel {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
-        /// Operation ID
-        operation_id: String,
+        /// Operation ID (omit when using --all)
+        #[arg(required_unless_present = "all")]
+        operation_id: Option<String>,
+
+        /// Cancel every operation that has not yet completed or failed
+        #[arg(long, alias = "pending", conflicts_with = "operation_id")]
+        all: bool,
+
+        /// Skip confirmation prompt (only used with --all)
+        #[arg(short = 'y', long)]
+        yes: bool,
+
+        /// Cancel up to N operations in parallel (only used with --all)
+        #[arg(long, value_name = "N", default_value_t = bulk::DEFAULT_CONCURRENCY as u64, value_parser = clap::value_parser!(u64).range(1..=64))]
+        concurrency: u64,
+
+        #[command(flatten)]
+        errors: BulkErrorArgs,
     },
 }
 
@@ -544,6 +1262,7 @@ enum TagCommands {
     /// List tags in a bank
     List {
         /// Bank ID
+        #[arg(value_parser = parse_bank_id)]
         bank_id: String,
 
         /// Wildcard search query (e.g., 'user:*')
@@ -557,6 +1276,9 @@ enum TagCommands {
         /// Offset for pagination
         #[arg(short = 's', long, default_value = "0")]
         offset: i64,
+        /// Print only the number of results
+        #[arg(long)]
+        count_only: bool,
     },
 }
 
@@ -573,52 +1295,86 @@ enum ChunkCommands {
 enum MentalModelCommands {
     /// List mental models for a bank
     List {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID (may be omitted when --bank, HINDSIGHT_BANK or `bank` in the config file is set)
+        bank_id: Option<String>,
+        /// Print only the number of results
+        #[arg(long, conflicts_with_all = ["page", "page_size"])]
+        count_only: bool,
+        #[command(flatten)]
+        paging: PageArgs,
     },
 
     /// Get a specific mental model
+    #[command(allow_missing_positional = true)]
     Get {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID (may be omitted when --bank, HINDSIGHT_BANK or `bank` in the config file is set)
+        bank_id: Option<String>,
 
         /// Mental model ID
         mental_model_id: String,
+
+        /// Show every stored version with its timestamp, oldest first. Only servers
+        /// with a mental model history endpoint (not part of the published API) keep
+        /// versions; elsewhere this shows the current content with a warning
+        #[arg(long)]
+        history: bool,
+
+        /// With --history, show each version as a diff against the one before it
+        #[arg(long, requires = "history")]
+        diff: bool,
     },
 
     /// Create a new mental model
+    #[command(override_usage = "hindsight mental-model create [OPTIONS] [BANK_ID] <NAME> <SOURCE_QUERY>")]
     Create {
-        /// Bank ID
+        /// Bank ID (may be omitted when --bank, HINDSIGHT_BANK or `bank` in the config file is set)
+        #[arg(value_parser = parse_non_blank)]
         bank_id: String,
 
         /// Mental model name
+        #[arg(value_parser = parse_non_blank)]
         name: String,
 
         /// Source query to generate the mental model from
-        source_query: String,
+        #[arg(value_parser = parse_non_blank)]
+        source_query: Option<String>,
 
         /// Optional custom ID for the mental model (alphanumeric lowercase with hyphens)
         #[arg(long)]
         id: Option<String>,
+
+        /// Wait for the mental model to be generated and print it (bounded by --wait-timeout)
+        #[arg(long, conflicts_with = "dry_run")]
+        wait: bool,
+
+        /// Print the request body that would be sent and exit without calling the API
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Update a mental model
+    #[command(allow_missing_positional = true)]
     Update {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID (may be omitted when --bank, HINDSIGHT_BANK or `bank` in the config file is set)
+        bank_id: Option<String>,
 
         /// Mental model ID
         mental_model_id: String,
 
         /// New name
-        #[arg(long)]
+        #[arg(long, value_parser = parse_non_blank)]
         name: Option<String>,
+
+        /// Print the request body that would be sent and exit without calling the API
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Delete a mental model
+    #[command(allow_missing_positional = true)]
     Delete {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID (may be omitted when --bank, HINDSIGHT_BANK or `bank` in the config file is set)
+        bank_id: Option<String>,
 
         /// Mental model ID
         mental_model_id: String,
@@ -629,12 +1385,21 @@ enum MentalModelCommands {
     },
 
     /// Refresh a mental model (re-run the source query)
+    #[command(allow_missing_positional = true)]
     Refresh {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID (may be omitted when --bank, HINDSIGHT_BANK or `bank` in the config file is set)
+        bank_id: Option<String>,
 
         /// Mental model ID
         mental_model_id: String,
+
+        /// Wait for the refresh to complete
+        #[arg(long)]
+        wait: bool,
+
+        /// Don't show a diff of the content after waiting
+        #[arg(long, requires = "wait")]
+        no_diff: bool,
     },
 }
 
@@ -642,52 +1407,93 @@ enum MentalModelCommands {
 enum DirectiveCommands {
     /// List directives for a bank
     List {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID (may be omitted when --bank, HINDSIGHT_BANK or `bank` in the config file is set)
+        bank_id: Option<String>,
+
+        /// Sort order
+        #[arg(long, value_enum, default_value = "priority")]
+        sort: commands::directive::DirectiveSort,
+        /// Print only the number of results
+        #[arg(long, conflicts_with_all = ["page", "page_size"])]
+        count_only: bool,
+        #[command(flatten)]
+        paging: PageArgs,
     },
 
     /// Get a specific directive
+    #[command(allow_missing_positional = true)]
     Get {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID (may be omitted when --bank, HINDSIGHT_BANK or `bank` in the config file is set)
+        bank_id: Option<String>,
 
         /// Directive ID
         directive_id: String,
     },
 
+    /// Print the content of all active directives, highest priority first, as one
+    /// text block ready to paste into a prompt
+    Render {
+        /// Bank ID (may be omitted when --bank, HINDSIGHT_BANK or `bank` in the config file is set)
+        bank_id: Option<String>,
+
+        /// Text between directives; \n and \t are expanded
+        #[arg(long, value_name = "TEXT", default_value = "\\n\\n")]
+        separator: String,
+    },
+
     /// Create a new directive
+    #[command(override_usage = "hindsight directive create [OPTIONS] [BANK_ID] <NAME> <CONTENT>")]
     Create {
-        /// Bank ID
+        /// Bank ID (may be omitted when --bank, HINDSIGHT_BANK or `bank` in the config file is set)
+        #[arg(value_parser = parse_non_blank)]
         bank_id: String,
 
         /// Directive name
+        #[arg(value_parser = parse_non_blank)]
         name: String,
 
         /// Directive content (the text to inject into prompts)
-        content: String,
+        #[arg(value_parser = parse_non_blank)]
+        content: Option<String>,
+
+        /// Print the request body that would be sent and exit without calling the API
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Update a directive
+    #[command(allow_missing_positional = true)]
     Update {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID (may be omitted when --bank, HINDSIGHT_BANK or `bank` in the config file is set)
+        bank_id: Option<String>,
 
         /// Directive ID
         directive_id: String,
 
         /// New name
-        #[arg(long)]
+        #[arg(long, value_parser = parse_non_blank)]
         name: Option<String>,
 
         /// New content
-        #[arg(long)]
+        #[arg(long, value_parser = parse_non_blank)]
         content: Option<String>,
+
+        /// Print the request body that would be sent and exit without calling the API
+        #[arg(long)]
+        dry_run: bool,
     },
 
+    /// Activate every directive, or those with a given tag
+    Enable(DirectiveToggleArgs),
+
+    /// Deactivate every directive, or those with a given tag
+    Disable(DirectiveToggleArgs),
+
     /// Delete a directive
+    #[command(allow_missing_positional = true)]
     Delete {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID (may be omitted when --bank, HINDSIGHT_BANK or `bank` in the config file is set)
+        bank_id: Option<String>,
 
         /// Directive ID
         directive_id: String,
@@ -698,6 +1504,65 @@ enum DirectiveCommands {
     },
 }
 
+/// Subcommand names as typed, e.g. "memory recall", for `--envelope`
+fn command_path(matches: &clap::ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub)) = current.subcommand() {
+        names.push(name);
+        current = sub;
+    }
+    names.join(" ")
+}
+
+/// Arguments whose values are credentials, never echoed by `--with-meta`
+const SECRET_PARAMS: &[&str] = &["api_key"];
+
+/// The leaf subcommand's arguments after defaults, as JSON, for `--with-meta`.
+/// Global options are left out; a repeated argument becomes an array. Secrets are
+/// masked and credentials are dropped from URLs.
+fn effective_params(matches: &clap::ArgMatches) -> serde_json::Value {
+    let mut command = Cli::command();
+    let mut current = matches;
+    while let Some((name, sub)) = current.subcommand() {
+        command = match command.find_subcommand(name) {
+            Some(sub_command) => sub_command.clone(),
+            None => return serde_json::Value::Null,
+        };
+        current = sub;
+    }
+
+    let mut params = serde_json::Map::new();
+    for arg in command.get_arguments().filter(|arg| !arg.is_global_set()) {
+        let id = arg.get_id().as_str();
+        let Ok(Some(raw)) = current.try_get_raw(id) else { continue };
+        let redact = |value: String| match id {
+            _ if SECRET_PARAMS.contains(&id) => "<redacted>".to_string(),
+            _ if id.ends_with("url") => api::redact_url_credentials(&value),
+            _ => value,
+        };
+        let mut values: Vec<serde_json::Value> = raw.map(|v| redact(v.to_string_lossy().into_owned()).into()).collect();
+        let value = if matches!(arg.get_action(), clap::ArgAction::Append) || values.len() != 1 {
+            serde_json::Value::Array(values)
+        } else {
+            values.remove(0)
+        };
+        params.insert(id.to_string(), value);
+    }
+    serde_json::Value::Object(params)
+}
+
+/// Commands with a single text payload for `--output raw` (see [`output::RAW_COMMANDS`])
+fn supports_raw(command: &Commands) -> bool {
+    matches!(
+        command,
+        Commands::Memory(MemoryCommands::Recall { .. } | MemoryCommands::Reflect { .. })
+            | Commands::Document(DocumentCommands::Get { .. })
+            | Commands::MentalModel(MentalModelCommands::Get { .. })
+            | Commands::Directive(DirectiveCommands::Get { .. } | DirectiveCommands::Render { .. })
+    )
+}
+
 fn main() {
     if let Err(_) = run() {
         std::process::exit(1);
@@ -705,14 +1570,58 @@ fn main() {
 }
 
 fn run() -> Result<()> {
-    let cli = Cli::parse();
+    let started = std::time::Instant::now();
+    let started_at = chrono::Utc::now();
+    let matches = Cli::command()
+        .try_get_matches()
+        .unwrap_or_else(|e| suggest::with_suggestion(e, &Cli::command()).exit());
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if cli.envelope {
+        output::set_envelope(Some(command_path(&matches)));
+    }
 
     let output_format: OutputFormat = cli.output.into();
+    if output_format == OutputFormat::Raw && !supports_raw(&cli.command) {
+        let supported: Vec<&str> = output::RAW_COMMANDS.iter().map(|(command, _)| *command).collect();
+        ui::print_error(&format!("--output raw is only supported by: {}", supported.join(", ")));
+        anyhow::bail!("--output raw is not supported by this command");
+    }
+    if !cli.columns.is_empty() && output_format != OutputFormat::Table {
+        ui::print_error("--columns only applies to --output table");
+        anyhow::bail!("--columns requires --output table");
+    }
+    table::set_columns(cli.columns.clone());
     let verbose = cli.verbose;
+    errors::set_compact(cli.compact_errors, verbose);
+    timestamp::set_verbose(verbose);
+    output::set_json_indent((!cli.compact).then_some(cli.indent as usize));
+    output::set_omit_null(cli.omit_null);
+    ui::set_output_format(output_format);
+    ui::set_assume_yes(cli.assume_yes);
+    ui::set_utc_timestamps(cli.utc);
+    ui::set_ascii_glyphs(cli.ascii || !ui::locale_supports_unicode());
+    ui::set_number_format(cli.locale, cli.precision);
+    ui::set_compact_facts(cli.compact_facts);
+    ui::set_max_text_width(cli.max_text_width);
+    ui::set_fact_context(match (cli.show_context, cli.hide_context) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    });
+    ui::set_spinner_mode(if cli.no_spinner {
+        ui::SpinnerMode::Never
+    } else if cli.spinner {
+        ui::SpinnerMode::Always
+    } else {
+        ui::SpinnerMode::Auto
+    });
+    if let Some(style) = cli.spinner_style.or_else(spinner_style_from_config) {
+        ui::set_spinner_style(style);
+    }
 
     // Handle configure command before loading full config (it doesn't need API client)
     if let Commands::Configure { api_url, api_key } = cli.command {
-        return handle_configure(api_url, api_key, output_format);
+        return handle_configure(api_url, api_key, cli.tenant, cli.bank, output_format);
     }
 
     // Handle ui command - needs config but not API client
@@ -720,42 +1629,153 @@ fn run() -> Result<()> {
         return handle_ui(output_format);
     }
 
+    if let Commands::Cache(CacheCommands::Clear) = cli.command {
+        return handle_cache_clear(output_format);
+    }
+
+    if let Commands::Env = cli.command {
+        let flags = commands::env::EnvFlags {
+            tenant: cli.tenant.as_deref(),
+            bank: cli.bank.as_deref(),
+            request_id: cli.request_id.as_deref(),
+            headers: &cli.headers,
+            wait_timeout: cli.wait_timeout,
+            cancel_on_timeout: cli.cancel_on_timeout,
+            no_cache: cli.no_cache,
+        };
+        return commands::env::run(&flags, output_format);
+    }
+
+    if let Commands::Examples { command } = cli.command {
+        let command = (!command.is_empty()).then(|| command.join(" "));
+        return commands::examples::run(command, output_format);
+    }
+
     // Load configuration
-    let config = Config::from_env().unwrap_or_else(|e| {
+    let mut config = Config::from_env().unwrap_or_else(|e| {
         ui::print_error(&format!("Configuration error: {}", e));
         errors::print_config_help();
         std::process::exit(1);
     });
 
-    let api_url = config.api_url().to_string();
+    if cli.require_explicit_server {
+        if let Err(e) = config.require_explicit_server() {
+            ui::print_error(&e.to_string());
+            errors::print_config_help();
+            std::process::exit(1);
+        }
+    }
+
+    if cli.tenant.is_some() {
+        config.tenant = cli.tenant.clone();
+    }
+    if cli.bank.is_some() {
+        config.bank = cli.bank.clone();
+        if !matches!(cli.command, Commands::MentalModel(_) | Commands::Directive(_) | Commands::Repl { .. }) {
+            ui::print_warning("--bank only applies to mental-model, directive and repl commands; ignoring it");
+        }
+    }
+    // A missing bank is a usage error, not an API failure
+    let bank = |positional: Option<String>| {
+        let bank = config::resolve_bank(positional, config.bank.as_deref()).unwrap_or_else(|e| {
+            ui::print_error(&e.to_string());
+            std::process::exit(2);
+        });
+        if verbose {
+            config::report_bank_id_changes();
+        }
+        bank
+    };
+
+    let mut fact_type_aliases = Config::fact_type_aliases();
+    fact_type_aliases.extend(cli.fact_type_map.iter().cloned());
+    let fact_type_of = |name: String| config::resolve_fact_type(&name, &fact_type_aliases);
+
+    let api_url = api::tenant_base_url(config.api_url(), config.tenant.as_deref());
     let api_key = config.api_key.clone();
+    let has_api_key = api_key.is_some();
 
     // Create API client
-    let client = ApiClient::new(api_url.clone(), api_key).unwrap_or_else(|e| {
-        errors::handle_api_error(e, &api_url);
+    let client = ApiClient::new(api_url.clone(), api_key, cli.request_id.clone(), &cli.headers, cli.verbose).unwrap_or_else(|e| {
+        errors::handle_api_error(e, &api_url, None);
     });
+    if verbose {
+        eprintln!("Request ID: {}", client.request_id());
+        config::report_bank_id_changes();
+        for header in &cli.headers {
+            eprintln!("Custom header: {}", header.redacted());
+        }
+    }
+    lenient::set_enabled(cli.parse_lenient);
+    if let Some(mode) = cli.print_curl {
+        curl::enable(mode, client.request_id(), has_api_key, &cli.headers);
+        // The spinner would draw over the printed commands
+        ui::set_spinner_mode(ui::SpinnerMode::Never);
+    }
+    // Cached responses never reach the server, so --print-curl would show nothing
+    let client = if cli.no_cache || cli.print_curl.is_some() {
+        client
+    } else {
+        let api_key = config.api_key.as_deref().unwrap_or_default();
+        let headers: Vec<String> = cli.headers.iter().map(|h| format!("{}: {}", h.name, h.value.to_str().unwrap_or_default())).collect();
+        let identity: Vec<&str> = std::iter::once(api_key).chain(headers.iter().map(String::as_str)).collect();
+        client.with_cache(cache::ResponseCache::for_server(&api_url, &identity))
+    };
+    let client = client.with_wait_timeout(cli.wait_timeout, cli.cancel_on_timeout);
+    let memo_ttl = (!cli.no_memo && cli.print_curl.is_none() && !cli.memo_ttl.is_zero()).then_some(cli.memo_ttl);
+    let client = client.with_recall_memo(memo_ttl);
+    // Discovery would be the request --print-curl shows instead of the command's own
+    let client = client.with_capability_discovery(cli.print_curl.is_none());
+    if cli.with_meta {
+        output::set_meta(Some(output::ClientMeta {
+            server_url: api::redact_url_credentials(&api_url),
+            request_id: client.request_id().to_string(),
+            started_at,
+            started,
+            params: effective_params(&matches),
+        }));
+    }
+
+    // Paging would swallow the interactive commands' own terminal handling
+    let pager_mode = if cli.no_pager || matches!(cli.command, Commands::Repl { .. } | Commands::Explore) {
+        pager::PagerMode::Never
+    } else if cli.pager {
+        pager::PagerMode::Always
+    } else {
+        pager::PagerMode::Auto
+    };
+    pager::configure(pager_mode);
 
     // Execute command and handle errors
     let result: Result<()> = match cli.command {
         Commands::Configure { .. } => unreachable!(), // Handled above
         Commands::Ui => unreachable!(), // Handled above
+        Commands::Cache(_) => unreachable!(), // Handled above
+        Commands::Env => unreachable!(), // Handled above
+        Commands::Examples { .. } => unreachable!(), // Handled above
         Commands::Explore => commands::explore::run(&client),
+        Commands::Repl { bank_id, agent } => commands::repl::run(&client, &bank(bank_id.or(agent)), verbose, output_format),
 
         // Health, Metrics, and Version
         Commands::Health => commands::health::health(&client, verbose, output_format),
         Commands::Metrics => commands::health::metrics(&client, verbose, output_format),
         Commands::Version => commands::health::version(&client, verbose, output_format),
+        Commands::Capabilities => commands::health::capabilities(&client, verbose, output_format),
+        Commands::Raw { method, path, data } => commands::raw::raw(&client, method, &path, data, verbose, output_format),
 
         // Bank commands
         Commands::Bank(bank_cmd) => match bank_cmd {
-            BankCommands::List => commands::bank::list(&client, verbose, output_format),
-            BankCommands::Create { bank_id, name, mission, skepticism, literalism, empathy } => {
-                commands::bank::create(&client, &bank_id, name, mission, skepticism, literalism, empathy, verbose, output_format)
+            BankCommands::List { count_only } => commands::bank::list(&client, count_only, verbose, output_format),
+            BankCommands::Create { bank_id, name, mission, disposition, skepticism, literalism, empathy, background } => {
+                commands::bank::create(&client, &bank_id, name, mission, disposition, skepticism, literalism, empathy, background, verbose, output_format)
             }
             BankCommands::Update { bank_id, name, mission, skepticism, literalism, empathy } => {
                 commands::bank::update(&client, &bank_id, name, mission, skepticism, literalism, empathy, verbose, output_format)
             }
             BankCommands::Disposition { bank_id } => commands::bank::disposition(&client, &bank_id, verbose, output_format),
+            BankCommands::PatchDisposition { bank_id, settings } => {
+                commands::bank::patch_disposition(&client, &bank_id, &settings, verbose, output_format)
+            }
             BankCommands::Stats { bank_id } => commands::bank::stats(&client, &bank_id, verbose, output_format),
             BankCommands::Name { bank_id, name } => commands::bank::update_name(&client, &bank_id, &name, verbose, output_format),
             BankCommands::Mission { bank_id, mission } => {
@@ -765,7 +1785,7 @@ fn run() -> Result<()> {
                 commands::bank::update_background(&client, &bank_id, &content, no_update_disposition, verbose, output_format)
             }
             BankCommands::Graph { bank_id, fact_type, limit } => {
-                commands::bank::graph(&client, &bank_id, fact_type, limit, verbose, output_format)
+                commands::bank::graph(&client, &bank_id, fact_type.map(fact_type_of), limit, verbose, output_format)
             }
             BankCommands::Delete { bank_id, yes } => {
                 commands::bank::delete(&client, &bank_id, yes, verbose, output_format)
@@ -780,23 +1800,53 @@ fn run() -> Result<()> {
 
         // Memory commands
         Commands::Memory(memory_cmd) => match memory_cmd {
-            MemoryCommands::List { bank_id, fact_type, query, limit, offset } => {
-                commands::memory::list(&client, &bank_id, fact_type, query, limit, offset, verbose, output_format)
+            MemoryCommands::List { bank_id, fact_type, query, limit, offset, count_only } => {
+                commands::memory::list(&client, &bank_id, fact_type.map(fact_type_of), query, limit, offset, count_only, verbose, output_format)
             }
             MemoryCommands::Get { bank_id, memory_id } => {
                 commands::memory::get(&client, &bank_id, &memory_id, verbose, output_format)
             }
-            MemoryCommands::Recall { bank_id, query, fact_type, budget, max_tokens, trace, include_chunks, chunk_max_tokens } => {
-                commands::memory::recall(&client, &bank_id, query, fact_type, budget, max_tokens, trace, include_chunks, chunk_max_tokens, verbose, output_format)
+            MemoryCommands::Recall { bank_id, query, queries_file, fact_type, budget, max_tokens, trace, include_chunks, chunk_max_tokens, context_filter, document_id, dedupe, dedupe_similarity, rerank, recency_weight, show_overrides, format, trace_csv, stdin_json, benchmark, warmup, explain_scores, fail_if_empty } => {
+                let options = commands::memory::RecallOptions {
+                    queries_file,
+                    fact_type: fact_type.into_iter().map(fact_type_of).collect(),
+                    budget,
+                    max_tokens,
+                    trace,
+                    include_chunks,
+                    chunk_max_tokens,
+                    context_filter,
+                    document_id,
+                    dedupe_similarity: dedupe.then(|| dedupe_similarity.unwrap_or(1.0)),
+                    rerank: rerank.map(|order| (order, recency_weight)),
+                    template: format,
+                    show_overrides,
+                    trace_csv,
+                    stdin_json,
+                    benchmark: benchmark.map(|runs| (runs as usize, warmup as usize)),
+                    explain_scores,
+                    fail_if_empty,
+                };
+                commands::memory::recall(&client, &bank_id, query, options, verbose, output_format)
             }
-            MemoryCommands::Reflect { bank_id, query, budget, context, max_tokens, schema } => {
-                commands::memory::reflect(&client, &bank_id, query, budget, context, max_tokens, schema, verbose, output_format)
+            MemoryCommands::RecallBatch { bank_id, input, results, query_column, top, fact_type, budget, max_tokens, concurrency, errors } => {
+                commands::memory::recall_batch(&client, &bank_id, input, results, query_column, top as usize, fact_type.into_iter().map(fact_type_of).collect(), budget, max_tokens, concurrency as usize, errors.apply(), verbose, output_format)
             }
-            MemoryCommands::Retain { bank_id, content, doc_id, context, r#async } => {
-                commands::memory::retain(&client, &bank_id, content, doc_id, context, r#async, verbose, output_format)
+            MemoryCommands::Reflect { bank_id, query, budget, context, max_tokens, schema, sources_only, trace, temperature, top_p, seed, stdin_json, show_overrides } => {
+                let sampling = api::Sampling { temperature, top_p, seed };
+                commands::memory::reflect(&client, &bank_id, query.unwrap_or_default(), budget, context, max_tokens, schema, sources_only, trace, sampling, show_overrides, stdin_json, verbose, output_format)
+            }
+            MemoryCommands::Retain { bank_id, content, from_markdown, split_by, doc_id, context, r#async, idempotency_key } => {
+                match from_markdown {
+                    Some(path) => commands::memory::retain_markdown(&client, &bank_id, path, split_by.unwrap_or(commands::memory::MarkdownSplit::Heading), doc_id, context, r#async, idempotency_key, verbose, output_format),
+                    None => commands::memory::retain(&client, &bank_id, content.unwrap_or_default(), doc_id, context, r#async, idempotency_key, verbose, output_format),
+                }
             }
-            MemoryCommands::RetainFiles { bank_id, path, recursive, context, r#async } => {
-                commands::memory::retain_files(&client, &bank_id, path, recursive, context, r#async, verbose, output_format)
+            MemoryCommands::RetainFiles { bank_id, path, recursive, context, r#async, chunk_size, idempotency_key, errors } => {
+                commands::memory::retain_files(&client, &bank_id, path, recursive, context, r#async, chunk_size as usize, idempotency_key, errors.apply(), verbose, output_format)
+            }
+            MemoryCommands::Import { bank_id, file, strict, yes, r#async, chunk_size, document_id, auto_document_id, context, dedupe_within_batch, idempotency_key, errors } => {
+                commands::memory::import(&client, &bank_id, file, strict, yes, r#async, chunk_size as usize, document_id, auto_document_id, context, dedupe_within_batch, idempotency_key, errors.apply(), verbose, output_format)
             }
             MemoryCommands::Delete { bank_id, unit_id } => {
                 commands::memory::delete(&client, &bank_id, &unit_id, verbose, output_format)
@@ -808,12 +1858,18 @@ fn run() -> Result<()> {
 
         // Document commands
         Commands::Document(doc_cmd) => match doc_cmd {
-            DocumentCommands::List { bank_id, query, date, limit, offset } => {
-                commands::document::list(&client, &bank_id, query, date, limit, offset, verbose, output_format)
+            DocumentCommands::List { bank_id, query, date, limit, offset, all, format, count_only, fail_if_empty } => {
+                commands::document::list(&client, &bank_id, query, date, limit, offset, all, format, count_only, fail_if_empty, verbose, output_format)
             }
             DocumentCommands::Get { bank_id, document_id } => {
                 commands::document::get(&client, &bank_id, &document_id, verbose, output_format)
             }
+            DocumentCommands::Manifest { bank_id, csv, since } => {
+                commands::document::manifest(&client, &bank_id, csv, since, verbose, output_format)
+            }
+            DocumentCommands::Sync { bank_id, dir, since, concurrency, errors } => {
+                commands::document::sync(&client, &bank_id, dir, since, concurrency as usize, errors.apply(), verbose, output_format)
+            }
             DocumentCommands::Delete { bank_id, document_id } => {
                 commands::document::delete(&client, &bank_id, &document_id, verbose, output_format)
             }
@@ -821,8 +1877,8 @@ fn run() -> Result<()> {
 
         // Entity commands
         Commands::Entity(entity_cmd) => match entity_cmd {
-            EntityCommands::List { bank_id, limit } => {
-                commands::entity::list(&client, &bank_id, limit, verbose, output_format)
+            EntityCommands::List { bank_id, limit, count_only } => {
+                commands::entity::list(&client, &bank_id, limit, count_only, verbose, output_format)
             }
             EntityCommands::Get { bank_id, entity_id } => {
                 commands::entity::get(&client, &bank_id, &entity_id, verbose, output_format)
@@ -834,8 +1890,8 @@ fn run() -> Result<()> {
 
         // Tag commands
         Commands::Tag(tag_cmd) => match tag_cmd {
-            TagCommands::List { bank_id, query, limit, offset } => {
-                commands::tag::list(&client, &bank_id, query, limit, offset, verbose, output_format)
+            TagCommands::List { bank_id, query, limit, offset, count_only } => {
+                commands::tag::list(&client, &bank_id, query, limit, offset, count_only, verbose, output_format)
             }
         },
 
@@ -848,68 +1904,102 @@ fn run() -> Result<()> {
 
         // Operation commands
         Commands::Operation(op_cmd) => match op_cmd {
-            OperationCommands::List { bank_id } => {
-                commands::operation::list(&client, &bank_id, verbose, output_format)
+            OperationCommands::List { bank_id, format, count_only } => {
+                commands::operation::list(&client, &bank_id, format, count_only, verbose, output_format)
             }
             OperationCommands::Get { bank_id, operation_id } => {
                 commands::operation::get(&client, &bank_id, &operation_id, verbose, output_format)
             }
-            OperationCommands::Cancel { bank_id, operation_id } => {
-                commands::operation::cancel(&client, &bank_id, &operation_id, verbose, output_format)
+            OperationCommands::Cancel { bank_id, operation_id, all, yes, concurrency, errors } => {
+                if all {
+                    commands::operation::cancel_all(&client, &bank_id, yes, concurrency as usize, errors.apply(), verbose, output_format)
+                } else {
+                    let operation_id = operation_id.expect("clap enforces operation_id without --all");
+                    commands::operation::cancel(&client, &bank_id, &operation_id, verbose, output_format)
+                }
             }
         },
 
         // Mental model commands
         Commands::MentalModel(mm_cmd) => match mm_cmd {
-            MentalModelCommands::List { bank_id } => {
-                commands::mental_model::list(&client, &bank_id, verbose, output_format)
+            MentalModelCommands::List { bank_id, count_only, paging } => {
+                commands::mental_model::list(&client, &bank(bank_id), count_only, paging.request(), verbose, output_format)
+            }
+            MentalModelCommands::Get { bank_id, mental_model_id, history: true, diff } => {
+                commands::mental_model::history(&client, &bank(bank_id), &mental_model_id, diff, verbose, output_format)
             }
-            MentalModelCommands::Get { bank_id, mental_model_id } => {
-                commands::mental_model::get(&client, &bank_id, &mental_model_id, verbose, output_format)
+            MentalModelCommands::Get { bank_id, mental_model_id, history: false, .. } => {
+                commands::mental_model::get(&client, &bank(bank_id), &mental_model_id, verbose, output_format)
             }
-            MentalModelCommands::Create { bank_id, name, source_query, id } => {
-                commands::mental_model::create(&client, &bank_id, &name, &source_query, id.as_deref(), verbose, output_format)
+            MentalModelCommands::Create { bank_id, name, source_query, id, wait, dry_run } => {
+                let (bank_id, name, source_query) = shift_bank_positional(bank_id, name, source_query);
+                commands::mental_model::create(&client, &bank(bank_id), &name, &source_query, id.as_deref(), wait, dry_run, verbose, output_format)
             }
-            MentalModelCommands::Update { bank_id, mental_model_id, name } => {
-                commands::mental_model::update(&client, &bank_id, &mental_model_id, name, verbose, output_format)
+            MentalModelCommands::Update { bank_id, mental_model_id, name, dry_run } => {
+                commands::mental_model::update(&client, &bank(bank_id), &mental_model_id, name, dry_run, verbose, output_format)
             }
             MentalModelCommands::Delete { bank_id, mental_model_id, yes } => {
-                commands::mental_model::delete(&client, &bank_id, &mental_model_id, yes, verbose, output_format)
+                commands::mental_model::delete(&client, &bank(bank_id), &mental_model_id, yes, verbose, output_format)
             }
-            MentalModelCommands::Refresh { bank_id, mental_model_id } => {
-                commands::mental_model::refresh(&client, &bank_id, &mental_model_id, verbose, output_format)
+            MentalModelCommands::Refresh { bank_id, mental_model_id, wait, no_diff } => {
+                commands::mental_model::refresh(&client, &bank(bank_id), &mental_model_id, wait, no_diff, verbose, output_format)
             }
         },
 
         // Directive commands
         Commands::Directive(dir_cmd) => match dir_cmd {
-            DirectiveCommands::List { bank_id } => {
-                commands::directive::list(&client, &bank_id, verbose, output_format)
+            DirectiveCommands::List { bank_id, sort, count_only, paging } => {
+                commands::directive::list(&client, &bank(bank_id), sort, count_only, paging.request(), verbose, output_format)
             }
             DirectiveCommands::Get { bank_id, directive_id } => {
-                commands::directive::get(&client, &bank_id, &directive_id, verbose, output_format)
+                commands::directive::get(&client, &bank(bank_id), &directive_id, verbose, output_format)
+            }
+            DirectiveCommands::Render { bank_id, separator } => {
+                commands::directive::render(&client, &bank(bank_id), &separator, verbose, output_format)
+            }
+            DirectiveCommands::Create { bank_id, name, content, dry_run } => {
+                let (bank_id, name, content) = shift_bank_positional(bank_id, name, content);
+                commands::directive::create(&client, &bank(bank_id), &name, &content, dry_run, verbose, output_format)
+            }
+            DirectiveCommands::Update { bank_id, directive_id, name, content, dry_run } => {
+                commands::directive::update(&client, &bank(bank_id), &directive_id, name, content, dry_run, verbose, output_format)
             }
-            DirectiveCommands::Create { bank_id, name, content } => {
-                commands::directive::create(&client, &bank_id, &name, &content, verbose, output_format)
+            DirectiveCommands::Enable(args) => {
+                commands::directive::set_active(&client, &bank(args.bank_id), &args.tag, true, args.concurrency as usize, args.errors.apply(), verbose, output_format)
             }
-            DirectiveCommands::Update { bank_id, directive_id, name, content } => {
-                commands::directive::update(&client, &bank_id, &directive_id, name, content, verbose, output_format)
+            DirectiveCommands::Disable(args) => {
+                commands::directive::set_active(&client, &bank(args.bank_id), &args.tag, false, args.concurrency as usize, args.errors.apply(), verbose, output_format)
             }
             DirectiveCommands::Delete { bank_id, directive_id, yes } => {
-                commands::directive::delete(&client, &bank_id, &directive_id, yes, verbose, output_format)
+                commands::directive::delete(&client, &bank(bank_id), &directive_id, yes, verbose, output_format)
             }
         },
     };
 
     // Handle API errors with nice messages
     if let Err(e) = result {
-        errors::handle_api_error(e, &api_url);
+        if cli.show_elapsed {
+            ui::print_elapsed(started.elapsed(), false);
+        }
+        output::print_error_envelope(&e, Some(client.request_id()), output_format);
+        errors::handle_api_error(e, &api_url, Some(client.request_id()));
     }
 
+    pager::finish();
+    if cli.show_elapsed {
+        ui::print_elapsed(started.elapsed(), true);
+    }
+    errors::exit_if_empty_results();
     Ok(())
 }
 
-fn handle_configure(api_url: Option<String>, api_key: Option<String>, output_format: OutputFormat) -> Result<()> {
+fn handle_configure(
+    api_url: Option<String>,
+    api_key: Option<String>,
+    tenant: Option<String>,
+    bank: Option<String>,
+    output_format: OutputFormat,
+) -> Result<()> {
     // Load current config to show current state
     let current_config = Config::load().ok();
 
@@ -929,6 +2019,9 @@ fn handle_configure(api_url: Option<String>, api_key: Option<String>, output_for
                 };
                 println!("  Current API Key: {}", masked);
             }
+            if let Some(ref tenant) = config.tenant {
+                println!("  Current Tenant: {}", tenant);
+            }
             println!("  Source: {}", config.source);
             println!();
         }
@@ -956,8 +2049,13 @@ fn handle_configure(api_url: Option<String>, api_key: Option<String>, output_for
     // Use provided api_key, or keep existing one if not provided
     let new_api_key = api_key.or_else(|| current_config.as_ref().and_then(|c| c.api_key.clone()));
 
+    // Use provided tenant, or keep existing one if not provided
+    let new_tenant = tenant.or_else(|| current_config.as_ref().and_then(|c| c.tenant.clone()));
+    // Only an explicit --bank changes the saved default; HINDSIGHT_BANK is never persisted
+    let bank = bank.or_else(|| Config::file_setting("bank"));
+
     // Save to config file
-    let config_path = Config::save_config(&new_api_url, new_api_key.as_deref())?;
+    let config_path = Config::save_config(&new_api_url, new_api_key.as_deref(), new_tenant.as_deref(), bank.as_deref())?;
 
     if output_format == OutputFormat::Pretty {
         ui::print_success(&format!("Configuration saved to {}", config_path.display()));
@@ -971,12 +2069,20 @@ fn handle_configure(api_url: Option<String>, api_key: Option<String>, output_for
             };
             println!("  API Key: {}", masked);
         }
+        if let Some(ref tenant) = new_tenant {
+            println!("  Tenant: {}", tenant);
+        }
+        if let Some(ref bank) = bank {
+            println!("  Default bank: {}", bank);
+        }
         println!();
         println!("Note: Environment variables HINDSIGHT_API_URL and HINDSIGHT_API_KEY will override these settings.");
     } else {
         let result = serde_json::json!({
             "api_url": new_api_url,
             "api_key_set": new_api_key.is_some(),
+            "tenant": new_tenant,
+            "bank": bank,
             "config_path": config_path.display().to_string(),
         });
         output::print_output(&result, output_format)?;
@@ -985,6 +2091,17 @@ fn handle_configure(api_url: Option<String>, api_key: Option<String>, output_for
     Ok(())
 }
 
+fn handle_cache_clear(output_format: OutputFormat) -> Result<()> {
+    let cleared = cache::clear_all()?;
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_success(&format!("Cleared {} cached response(s)", cleared));
+    } else {
+        output::print_output(&serde_json::json!({ "cleared": cleared }), output_format)?;
+    }
+    Ok(())
+}
+
 fn handle_ui(output_format: OutputFormat) -> Result<()> {
     use std::process::Command;
 
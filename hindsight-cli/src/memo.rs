@@ -0,0 +1,70 @@
+//! Short-lived in-process memoization of identical searches.
+//!
+//! The REPL, the explorer and retrying scripts often repeat a search within a few
+//! seconds. Results are kept only for `ttl`, so a longer gap always reaches the server.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Clone)]
+pub struct Memo<T> {
+    ttl: Duration,
+    entries: Arc<Mutex<HashMap<String, (Instant, T)>>>,
+}
+
+impl<T: Clone> Memo<T> {
+    pub fn new(ttl: Duration) -> Self {
+        Memo { ttl, entries: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    /// The value stored under `key`, if it is younger than the TTL
+    pub fn get(&self, key: &str) -> Option<T> {
+        self.get_at(key, Instant::now())
+    }
+
+    fn get_at(&self, key: &str, now: Instant) -> Option<T> {
+        let mut entries = self.entries.lock().ok()?;
+        entries.retain(|_, (stored_at, _)| now.duration_since(*stored_at) < self.ttl);
+        entries.get(key).map(|(_, value)| value.clone())
+    }
+
+    pub fn put(&self, key: String, value: T) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(key, (Instant::now(), value));
+        }
+    }
+
+    /// Forget every stored value, e.g. after a write that could change them
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memo_expires_after_ttl() {
+        let memo = Memo::new(Duration::from_secs(5));
+        memo.put("search".to_string(), 42);
+
+        let now = Instant::now();
+        assert_eq!(memo.get_at("search", now), Some(42));
+        assert_eq!(memo.get_at("other", now), None);
+        assert_eq!(memo.get_at("search", now + Duration::from_secs(6)), None);
+        // Expired entries are dropped, not just hidden
+        assert_eq!(memo.get_at("search", now), None);
+    }
+
+    #[test]
+    fn test_memo_clear() {
+        let memo = Memo::new(Duration::from_secs(60));
+        memo.put("search".to_string(), 42);
+        memo.clear();
+        assert_eq!(memo.get("search"), None);
+    }
+}
@@ -1,13 +1,31 @@
 use anyhow::Result;
+use serde::de::{DeserializeOwned, Visitor};
 use serde::Serialize;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum OutputFormat {
     Pretty,
     Json,
     Yaml,
+    /// Only the command's main text, unstyled (see [`RAW_COMMANDS`])
+    Raw,
+    /// Aligned columns, chosen with `--columns` (see [`crate::table`])
+    Table,
 }
 
+/// Commands that support `--output raw`, and what they print
+pub const RAW_COMMANDS: &[(&str, &str)] = &[
+    ("memory recall", "the text of each fact, one per line"),
+    ("memory reflect", "the answer text (with --sources-only: the source facts, one per line)"),
+    ("document get", "the original document text"),
+    ("mental-model get", "the mental model content"),
+    ("directive get", "the directive content"),
+    ("directive render", "the joined directive contents"),
+];
+
 impl OutputFormat {
     /// Parse output format from string
     pub fn from_str(s: &str) -> Option<Self> {
@@ -15,14 +33,69 @@ impl OutputFormat {
             "json" => Some(OutputFormat::Json),
             "yaml" | "yml" => Some(OutputFormat::Yaml),
             "pretty" | "text" => Some(OutputFormat::Pretty),
+            "raw" => Some(OutputFormat::Raw),
+            "table" => Some(OutputFormat::Table),
             _ => None,
         }
     }
 }
 
-/// Format data as JSON string
+/// Spaces per JSON nesting level (`--indent`); None prints on one line (`--compact`)
+static JSON_INDENT: Mutex<Option<usize>> = Mutex::new(Some(2));
+
+pub fn set_json_indent(indent: Option<usize>) {
+    *JSON_INDENT.lock().unwrap() = indent;
+}
+
+fn json_indent() -> Option<String> {
+    JSON_INDENT.lock().ok().and_then(|indent| *indent).map(|n| " ".repeat(n))
+}
+
+/// `--omit-null`: drop null-valued keys from JSON/YAML output
+static OMIT_NULL: AtomicBool = AtomicBool::new(false);
+
+pub fn set_omit_null(omit: bool) {
+    OMIT_NULL.store(omit, Ordering::Relaxed);
+}
+
+fn omit_null() -> bool {
+    OMIT_NULL.load(Ordering::Relaxed)
+}
+
+/// Remove null-valued keys from every object in `value`. Nulls inside arrays stay,
+/// since dropping them would shift the positions of the other elements.
+fn strip_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            fields.retain(|_, field| !field.is_null());
+            fields.values_mut().for_each(strip_nulls);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+/// Format data as JSON string, indented per `--indent`/`--compact`
 pub fn to_json<T: Serialize>(data: &T) -> Result<String> {
-    Ok(serde_json::to_string_pretty(data)?)
+    to_json_with(data, json_indent().as_deref())
+}
+
+fn to_json_with<T: Serialize>(data: &T, indent: Option<&str>) -> Result<String> {
+    let mut buf = Vec::new();
+    write_json(&mut buf, data, indent)?;
+    Ok(String::from_utf8(buf)?)
+}
+
+/// Serialize straight into `out`, so large responses are never held as one string
+fn write_json<W: Write, T: Serialize>(out: W, data: &T, indent: Option<&str>) -> Result<()> {
+    match indent {
+        Some(indent) => {
+            let mut serializer = serde_json::Serializer::with_formatter(out, serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes()));
+            data.serialize(&mut serializer)?;
+        }
+        None => serde_json::to_writer(out, data)?,
+    }
+    Ok(())
 }
 
 /// Format data as YAML string
@@ -30,10 +103,136 @@ pub fn to_yaml<T: Serialize>(data: &T) -> Result<String> {
     Ok(serde_yaml::to_string(data)?)
 }
 
+/// `--dry-run`: show the request a command would send without sending it.
+/// The body goes to stdout as JSON so it can be piped; the description goes to stderr.
+pub fn print_dry_run<T: Serialize>(method: &str, url: &reqwest::Url, request: &T) {
+    eprintln!("{}", crate::ui::dim(&format!("Dry run, not sent: {} {}", method, url)));
+    println!("{}", crate::api::format_request_body(request));
+}
+
+/// Command path for `--envelope`, e.g. "memory recall"; None when not enveloping
+static ENVELOPE: Mutex<Option<String>> = Mutex::new(None);
+
+pub fn set_envelope(command: Option<String>) {
+    *ENVELOPE.lock().unwrap() = command;
+}
+
+/// Set once a command has printed its `{"ok": true, ...}` envelope. Bulk commands print
+/// their report and then fail if any item failed; stdout must still hold one document.
+static ENVELOPE_PRINTED: AtomicBool = AtomicBool::new(false);
+
+fn envelope_command(format: OutputFormat) -> Option<String> {
+    match format {
+        OutputFormat::Json | OutputFormat::Yaml => ENVELOPE.lock().ok()?.clone(),
+        OutputFormat::Pretty | OutputFormat::Raw | OutputFormat::Table => None,
+    }
+}
+
+/// `--with-meta`: facts about this invocation, added to JSON/YAML output
+pub struct ClientMeta {
+    /// Server URL with any credentials redacted
+    pub server_url: String,
+    pub request_id: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub started: std::time::Instant,
+    /// The command's arguments after defaults were applied
+    pub params: serde_json::Value,
+}
+
+static META: Mutex<Option<ClientMeta>> = Mutex::new(None);
+
+pub fn set_meta(meta: Option<ClientMeta>) {
+    *META.lock().unwrap() = meta;
+}
+
+/// The `meta` object as of now, or None when `--with-meta` is off or doesn't apply
+fn meta_value(format: OutputFormat) -> Option<serde_json::Value> {
+    if !matches!(format, OutputFormat::Json | OutputFormat::Yaml) {
+        return None;
+    }
+    let meta = META.lock().ok()?;
+    let meta = meta.as_ref()?;
+    Some(serde_json::json!({
+        "cli_version": env!("CARGO_PKG_VERSION"),
+        "server_url": meta.server_url,
+        "request_id": meta.request_id,
+        "started_at": meta.started_at.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        "elapsed_ms": meta.started.elapsed().as_millis() as u64,
+        "params": meta.params,
+    }))
+}
+
 pub fn print_output<T: Serialize>(data: &T, format: OutputFormat) -> Result<()> {
+    let warnings = crate::ui::take_warnings();
+    let envelope = envelope_command(format);
+    let meta = meta_value(format);
+    if envelope.is_none() && meta.is_none() {
+        return print_serialized(data, format);
+    }
+    if envelope.is_some() {
+        ENVELOPE_PRINTED.store(true, Ordering::Relaxed);
+    }
+    print_serialized(&decorate(serde_json::to_value(data)?, envelope, meta, warnings), format)
+}
+
+/// Wrap output for `--envelope` and `--with-meta`, and attach warnings to the wrapper.
+/// Unwrapped output is the command's own data, so it is returned unchanged.
+fn decorate(data: serde_json::Value, envelope: Option<String>, meta: Option<serde_json::Value>, warnings: Vec<String>) -> serde_json::Value {
+    let mut value = match envelope {
+        Some(command) => serde_json::json!({ "ok": true, "command": command, "data": data }),
+        None if meta.is_some() => serde_json::json!({ "data": data }),
+        None => return data,
+    };
+    if let (Some(meta), Some(object)) = (meta, value.as_object_mut()) {
+        object.insert("meta".to_string(), meta);
+    }
+    with_warnings(value, warnings)
+}
+
+/// `--envelope`: report a failed command on stdout as `{"ok": false, ...}`, so tooling
+/// can branch on one shape. The human-readable message still goes to stderr.
+/// Skipped when the command already printed its report, e.g. a bulk command whose
+/// items partly failed: the report lists the failures and the exit code is non-zero.
+pub fn print_error_envelope(err: &anyhow::Error, request_id: Option<&str>, format: OutputFormat) {
+    let Some(command) = envelope_command(format) else { return };
+    if ENVELOPE_PRINTED.load(Ordering::Relaxed) {
+        return;
+    }
+    let envelope = serde_json::json!({
+        "ok": false,
+        "command": command,
+        "error": {
+            "message": err.to_string(),
+            "causes": err.chain().skip(1).map(|cause| cause.to_string()).collect::<Vec<_>>(),
+            "request_id": request_id,
+        },
+    });
+    let _ = print_serialized(&decorate_error(envelope, meta_value(format)), format);
+}
+
+fn decorate_error(mut envelope: serde_json::Value, meta: Option<serde_json::Value>) -> serde_json::Value {
+    if let (Some(meta), Some(object)) = (meta, envelope.as_object_mut()) {
+        object.insert("meta".to_string(), meta);
+    }
+    envelope
+}
+
+fn print_serialized<T: Serialize>(data: &T, format: OutputFormat) -> Result<()> {
+    if omit_null() && matches!(format, OutputFormat::Json | OutputFormat::Yaml) {
+        let mut value = serde_json::to_value(data)?;
+        strip_nulls(&mut value);
+        return write_serialized(&value, format);
+    }
+    write_serialized(data, format)
+}
+
+fn write_serialized<T: Serialize>(data: &T, format: OutputFormat) -> Result<()> {
     match format {
         OutputFormat::Json => {
-            println!("{}", to_json(data)?);
+            let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+            write_json(&mut out, data, json_indent().as_deref())?;
+            writeln!(out)?;
+            out.flush()?;
         }
         OutputFormat::Yaml => {
             println!("{}", to_yaml(data)?);
@@ -42,6 +241,291 @@ pub fn print_output<T: Serialize>(data: &T, format: OutputFormat) -> Result<()>
             // This should not be called - pretty printing is handled in ui.rs
             unreachable!("Pretty format should be handled separately")
         }
+        OutputFormat::Raw => {
+            // main rejects raw for commands that don't print a text payload
+            anyhow::bail!("--output raw is not supported by this command")
+        }
+        OutputFormat::Table => {
+            crate::table::print(&serde_json::to_value(data)?)?;
+        }
+    }
+    Ok(())
+}
+
+/// `--output raw`: the text exactly as the server returned it, newline-terminated
+pub fn print_raw(text: &str) {
+    if text.ends_with('\n') {
+        print!("{}", text);
+    } else {
+        println!("{}", text);
+    }
+}
+
+/// Add `_warnings` to an `--envelope`/`--with-meta` wrapper when there are any.
+/// Warnings are always printed to stderr as well.
+fn with_warnings(mut value: serde_json::Value, warnings: Vec<String>) -> serde_json::Value {
+    if warnings.is_empty() {
+        return value;
+    }
+    if let Some(object) = value.as_object_mut() {
+        object.insert("_warnings".to_string(), serde_json::json!(warnings));
+    }
+    value
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or newline
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Print only a count (`--count-only`): a bare integer in Pretty mode, `{"count": N}` otherwise
+pub fn print_count(count: i64, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Pretty => println!("{}", count),
+        _ => print_output(&serde_json::json!({ "count": count }), format)?,
+    }
+    Ok(())
+}
+
+/// Client-side page selection (`--page`/`--page-size`) for endpoints that return everything
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageRequest {
+    /// 1-based page number
+    pub page: usize,
+    pub page_size: usize,
+}
+
+/// Where a page sits in the full listing, for the "page X of Y" footer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageInfo {
+    pub page: usize,
+    pub total_pages: usize,
+    pub total_items: usize,
+}
+
+/// Keep only the requested page of `items`
+pub fn paginate<T>(items: &mut Vec<T>, request: PageRequest) -> Result<PageInfo> {
+    let total_items = items.len();
+    let total_pages = total_items.div_ceil(request.page_size).max(1);
+    if request.page > total_pages {
+        anyhow::bail!("Page {} is out of range: {} item(s) fit on {} page(s)", request.page, total_items, total_pages);
+    }
+    let start = (request.page - 1) * request.page_size;
+    items.truncate(start + request.page_size);
+    items.drain(..start);
+    Ok(PageInfo {
+        page: request.page,
+        total_pages,
+        total_items,
+    })
+}
+
+/// "Page X of Y" footer for Pretty output
+pub fn print_page_footer(info: &PageInfo) {
+    println!(
+        "{}",
+        crate::ui::dim(&format!("Page {} of {} ({} total)", info.page, info.total_pages, info.total_items))
+    );
+    if info.page < info.total_pages {
+        println!("{}", crate::ui::dim(&format!("Use --page {} for more.", info.page + 1)));
+    }
+    println!();
+}
+
+/// Writes a list one item at a time, so long listings print as pages arrive
+/// instead of after everything is collected. JSON output is still a single array
+/// and YAML a single sequence. Tables need every row to size their columns, and
+/// `--with-meta` wraps the list, so both are printed at the end.
+pub struct ListStream<W: Write> {
+    out: W,
+    format: OutputFormat,
+    count: usize,
+    envelope: Option<String>,
+    indent: Option<String>,
+    meta: Option<serde_json::Value>,
+    rows: Vec<serde_json::Value>,
+}
+
+impl<W: Write> ListStream<W> {
+    pub fn new(out: W, format: OutputFormat) -> Self {
+        let mut stream = Self::with_envelope(out, format, envelope_command(format));
+        stream.meta = meta_value(format);
+        stream
+    }
+
+    fn with_envelope(out: W, format: OutputFormat, envelope: Option<String>) -> Self {
+        Self { out, format, count: 0, envelope, indent: json_indent(), meta: None, rows: Vec::new() }
+    }
+
+    /// The `--envelope` fields before the list, ending at the `data` key
+    fn envelope_head(&self) -> Option<String> {
+        let command = self.envelope.as_ref()?;
+        Some(match (self.format, &self.indent) {
+            (OutputFormat::Json, Some(_)) => format!("{{\n\"ok\": true,\n\"command\": {},\n\"data\": ", serde_json::json!(command)),
+            (OutputFormat::Json, None) => format!("{{\"ok\":true,\"command\":{},\"data\":", serde_json::json!(command)),
+            _ => format!("ok: true\ncommand: {}\ndata:", serde_json::json!(command)),
+        })
+    }
+
+    pub fn item<T: Serialize>(&mut self, item: &T) -> Result<()> {
+        if omit_null() {
+            let mut value = serde_json::to_value(item)?;
+            strip_nulls(&mut value);
+            return self.write_item(&value);
+        }
+        self.write_item(item)
+    }
+
+    fn write_item<T: Serialize>(&mut self, item: &T) -> Result<()> {
+        if self.format == OutputFormat::Table || self.meta.is_some() {
+            self.rows.push(serde_json::to_value(item)?);
+            self.count += 1;
+            return Ok(());
+        }
+        if self.count == 0 {
+            if let Some(head) = self.envelope_head() {
+                // A YAML block sequence starts on the line after its key
+                let separator = if self.format == OutputFormat::Yaml { "\n" } else { "" };
+                write!(self.out, "{}{}", head, separator)?;
+            }
+        }
+        match self.format {
+            OutputFormat::Json => {
+                let (open, next) = if self.indent.is_some() { ("[\n", ",\n") } else { ("[", ",") };
+                let separator = if self.count == 0 { open } else { next };
+                let pad = self.indent.as_deref().unwrap_or_default();
+                let indented = to_json_with(item, self.indent.as_deref())?.replace('\n', &format!("\n{}", pad));
+                write!(self.out, "{}{}{}", separator, pad, indented)?;
+            }
+            OutputFormat::Yaml => {
+                write!(self.out, "{}", to_yaml(&[item])?)?;
+            }
+            OutputFormat::Pretty | OutputFormat::Raw | OutputFormat::Table => unreachable!("{:?} format should be handled separately", self.format),
+        }
+        self.count += 1;
+        self.out.flush()?;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<()> {
+        if self.format == OutputFormat::Table {
+            let rows = serde_json::Value::Array(std::mem::take(&mut self.rows));
+            writeln!(self.out, "{}", crate::table::render(&rows, &crate::table::columns())?)?;
+            self.out.flush()?;
+            return Ok(());
+        }
+        if let Some(meta) = self.meta.take() {
+            let rows = serde_json::Value::Array(std::mem::take(&mut self.rows));
+            let value = decorate(rows, self.envelope.take(), Some(meta), Vec::new());
+            match self.format {
+                OutputFormat::Json => writeln!(self.out, "{}", to_json_with(&value, self.indent.as_deref())?)?,
+                _ => write!(self.out, "{}", to_yaml(&value)?)?,
+            }
+            self.out.flush()?;
+            return Ok(());
+        }
+        if self.count == 0 {
+            if let Some(head) = self.envelope_head() {
+                write!(self.out, "{}{}", head, if self.format == OutputFormat::Yaml { " " } else { "" })?;
+            }
+        }
+        match (self.format, self.count) {
+            (_, 0) => writeln!(self.out, "[]")?,
+            (OutputFormat::Json, _) => writeln!(self.out, "{}]", if self.indent.is_some() { "\n" } else { "" })?,
+            _ => {}
+        }
+        if self.envelope.is_some() && self.format == OutputFormat::Json {
+            writeln!(self.out, "}}")?;
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Field names `T` declares, read from its `Deserialize` impl. Unlike the serialized
+/// keys, this includes optional fields that are skipped when unset. Empty for maps.
+fn declared_fields<T: DeserializeOwned>() -> &'static [&'static str] {
+    struct Fields<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> serde::Deserializer<'de> for Fields<'_> {
+        type Error = serde::de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> std::result::Result<V::Value, Self::Error> {
+            Err(serde::de::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> std::result::Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(serde::de::Error::custom("fields collected"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map enum identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(Fields(&mut fields));
+    fields
+}
+
+/// Render one item with a `{{field}}` template, using the item's serialized fields.
+/// Fields the type declares but the item leaves unset render as empty.
+pub fn render_template<T: Serialize + DeserializeOwned>(template: &str, item: &T) -> Result<String> {
+    let value = serde_json::to_value(item)?;
+    let fields = value.as_object();
+    let declared = declared_fields::<T>();
+
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| anyhow::anyhow!("Unclosed '{{{{' in format template"))?;
+        let name = after[..end].trim();
+
+        match fields.and_then(|f| f.get(name)) {
+            Some(serde_json::Value::String(s)) => rendered.push_str(s),
+            Some(serde_json::Value::Null) => {}
+            Some(other) => rendered.push_str(&other.to_string()),
+            None if declared.contains(&name) => {}
+            None => {
+                let mut available: Vec<&str> = fields
+                    .map(|f| f.keys().map(String::as_str).collect())
+                    .unwrap_or_default();
+                available.extend(declared.iter().copied());
+                available.sort_unstable();
+                available.dedup();
+                anyhow::bail!(
+                    "Unknown field '{}' in format template. Available fields: {}",
+                    name,
+                    available.join(", ")
+                );
+            }
+        }
+        rest = &after[end + 2..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// Print each item on its own line using a `{{field}}` template
+pub fn print_template<T: Serialize + DeserializeOwned>(template: &str, items: &[T]) -> Result<()> {
+    for item in items {
+        println!("{}", render_template(template, item)?);
     }
     Ok(())
 }
@@ -49,6 +533,94 @@ pub fn print_output<T: Serialize>(data: &T, format: OutputFormat) -> Result<()>
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    /// Counts bytes and remembers the largest single write it received
+    #[derive(Default)]
+    struct ChunkRecorder {
+        total: usize,
+        largest: usize,
+    }
+
+    impl Write for ChunkRecorder {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.total += buf.len();
+            self.largest = self.largest.max(buf.len());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_json_streams_large_responses() {
+        let facts: Vec<_> = (0..20_000)
+            .map(|i| serde_json::json!({"id": format!("fact-{}", i), "text": "x".repeat(100), "tags": ["a", "b"]}))
+            .collect();
+        let data = serde_json::json!({"results": facts});
+        for indent in [None, Some("  ")] {
+            let mut recorder = ChunkRecorder::default();
+            {
+                let mut out = std::io::BufWriter::new(&mut recorder);
+                write_json(&mut out, &data, indent).unwrap();
+                out.flush().unwrap();
+            }
+            assert_eq!(recorder.total, to_json_with(&data, indent).unwrap().len());
+            assert!(recorder.total > 2_000_000);
+            // Output reaches the writer in buffer-sized pieces, never as one string
+            assert!(recorder.largest <= 8 * 1024, "largest write was {} bytes", recorder.largest);
+        }
+    }
+
+    #[test]
+    fn test_strip_nulls_keeps_array_positions() {
+        let mut value = serde_json::json!({
+            "id": null,
+            "text": "a",
+            "results": [{ "fact_type": null, "score": 1 }, null],
+        });
+        strip_nulls(&mut value);
+        assert_eq!(value, serde_json::json!({ "text": "a", "results": [{ "score": 1 }, null] }));
+    }
+
+    #[test]
+    fn test_decorate_adds_warnings_only_to_wrappers() {
+        let warnings = vec!["skipped 2 duplicates".to_string()];
+
+        let plain = decorate(serde_json::json!({ "count": 3 }), None, None, warnings.clone());
+        assert_eq!(plain, serde_json::json!({ "count": 3 }));
+
+        let wrapped = decorate(serde_json::json!({ "count": 3 }), Some("bank list".to_string()), None, warnings);
+        assert_eq!(wrapped["_warnings"], serde_json::json!(["skipped 2 duplicates"]));
+        assert_eq!(wrapped["data"], serde_json::json!({ "count": 3 }));
+    }
+
+    #[test]
+    fn test_with_warnings_only_extends_objects() {
+        let warnings = vec!["skipped 2 duplicates".to_string()];
+
+        let object = with_warnings(serde_json::json!({ "count": 3 }), warnings.clone());
+        assert_eq!(object, serde_json::json!({ "count": 3, "_warnings": ["skipped 2 duplicates"] }));
+
+        let list = with_warnings(serde_json::json!([1, 2]), warnings);
+        assert_eq!(list, serde_json::json!([1, 2]));
+    }
+
+    #[test]
+    fn test_paginate_windows_items() {
+        let mut items: Vec<u32> = (1..=25).collect();
+        let info = paginate(&mut items, PageRequest { page: 3, page_size: 10 }).unwrap();
+        assert_eq!(items, [21, 22, 23, 24, 25]);
+        assert_eq!(info, PageInfo { page: 3, total_pages: 3, total_items: 25 });
+
+        let mut items: Vec<u32> = (1..=25).collect();
+        assert!(paginate(&mut items, PageRequest { page: 4, page_size: 10 }).is_err());
+
+        let mut empty: Vec<u32> = Vec::new();
+        let info = paginate(&mut empty, PageRequest { page: 1, page_size: 10 }).unwrap();
+        assert_eq!(info.total_pages, 1);
+    }
     use serde::{Deserialize, Serialize};
 
     #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -58,6 +630,89 @@ mod tests {
         active: bool,
     }
 
+    fn stream(format: OutputFormat, items: &[TestData]) -> String {
+        stream_enveloped(format, items, None)
+    }
+
+    fn stream_enveloped(format: OutputFormat, items: &[TestData], envelope: Option<&str>) -> String {
+        let mut buf = Vec::new();
+        let mut stream = ListStream::with_envelope(&mut buf, format, envelope.map(str::to_string));
+        for item in items {
+            stream.item(item).unwrap();
+        }
+        stream.finish().unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_list_stream_json_is_one_array() {
+        let items = vec![
+            TestData { name: "a".to_string(), count: 1, active: true },
+            TestData { name: "b".to_string(), count: 2, active: false },
+        ];
+        let parsed: Vec<TestData> = serde_json::from_str(&stream(OutputFormat::Json, &items)).unwrap();
+        assert_eq!(parsed, items);
+        let empty: Vec<TestData> = serde_json::from_str(&stream(OutputFormat::Json, &[])).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_list_stream_yaml_is_one_sequence() {
+        let items = vec![
+            TestData { name: "a".to_string(), count: 1, active: true },
+            TestData { name: "b".to_string(), count: 2, active: false },
+        ];
+        let parsed: Vec<TestData> = serde_yaml::from_str(&stream(OutputFormat::Yaml, &items)).unwrap();
+        assert_eq!(parsed, items);
+    }
+
+    #[test]
+    fn test_to_json_with_indent() {
+        let data = serde_json::json!({ "a": [1] });
+        assert_eq!(to_json_with(&data, Some("    ")).unwrap(), "{\n    \"a\": [\n        1\n    ]\n}");
+        assert_eq!(to_json_with(&data, None).unwrap(), "{\"a\":[1]}");
+    }
+
+    #[test]
+    fn test_list_stream_envelope() {
+        #[derive(Deserialize)]
+        struct Envelope {
+            ok: bool,
+            command: String,
+            data: Vec<TestData>,
+        }
+        let items = vec![TestData { name: "a".to_string(), count: 1, active: true }];
+
+        for format in [OutputFormat::Json, OutputFormat::Yaml] {
+            for list in [&items[..], &[]] {
+                let text = stream_enveloped(format, list, Some("document list"));
+                let parsed: Envelope = match format {
+                    OutputFormat::Json => serde_json::from_str(&text).unwrap(),
+                    _ => serde_yaml::from_str(&text).unwrap(),
+                };
+                assert!(parsed.ok);
+                assert_eq!(parsed.command, "document list");
+                assert_eq!(parsed.data, list);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decorate_with_meta() {
+        let meta = serde_json::json!({ "cli_version": "1.0" });
+
+        let wrapped = decorate(serde_json::json!([1]), None, Some(meta.clone()), Vec::new());
+        assert_eq!(wrapped, serde_json::json!({ "data": [1], "meta": { "cli_version": "1.0" } }));
+
+        let enveloped = decorate(serde_json::json!([1]), Some("bank list".to_string()), Some(meta), vec!["w".to_string()]);
+        assert_eq!(
+            enveloped,
+            serde_json::json!({ "ok": true, "command": "bank list", "data": [1], "meta": { "cli_version": "1.0" }, "_warnings": ["w"] })
+        );
+
+        assert_eq!(decorate(serde_json::json!([1]), None, None, Vec::new()), serde_json::json!([1]));
+    }
+
     #[test]
     fn test_output_format_from_str_json() {
         assert_eq!(OutputFormat::from_str("json"), Some(OutputFormat::Json));
@@ -162,4 +817,42 @@ mod tests {
         assert!(json.contains("\\\\"));
         assert!(json.contains("\\n"));
     }
+
+    #[test]
+    fn test_render_template() {
+        let data = TestData { name: "alpha".to_string(), count: 3, active: true };
+        assert_eq!(
+            render_template("{{name}} ({{ count }}) active={{active}}", &data).unwrap(),
+            "alpha (3) active=true"
+        );
+    }
+
+    #[test]
+    fn test_render_template_unknown_field() {
+        let data = TestData { name: "alpha".to_string(), count: 3, active: true };
+        let err = render_template("{{id}}", &data).unwrap_err().to_string();
+        assert!(err.contains("Unknown field 'id'"));
+        assert!(err.contains("active, count, name"));
+    }
+
+    #[test]
+    fn test_render_template_unset_optional_field() {
+        #[derive(Serialize, Deserialize)]
+        struct WithOptional {
+            name: String,
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            document_id: Option<String>,
+        }
+
+        let item = WithOptional { name: "alpha".to_string(), document_id: None };
+        assert_eq!(render_template("{{name}}:{{document_id}}", &item).unwrap(), "alpha:");
+        let err = render_template("{{doc_id}}", &item).unwrap_err().to_string();
+        assert!(err.contains("document_id, name"));
+    }
+
+    #[test]
+    fn test_render_template_unclosed() {
+        let data = TestData { name: "alpha".to_string(), count: 3, active: true };
+        assert!(render_template("{{name", &data).is_err());
+    }
 }
@@ -0,0 +1,187 @@
+//! Optional pager for long Pretty output.
+//!
+//! Commands that can produce long output call [`start`] right before printing,
+//! after any spinner has finished. When paging is enabled, stdout is redirected
+//! into `$PAGER` (default `less -R`) for the rest of the process, and [`finish`]
+//! waits for the pager to exit. Nothing is paged unless stdout is a terminal, and an
+//! empty `PAGER` (or `cat`) turns paging off, as it does for git.
+
+use std::sync::Mutex;
+
+use crate::output::OutputFormat;
+
+/// When to send Pretty output through the pager
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PagerMode {
+    /// Page only when stdout is a terminal and the output doesn't fit on screen.
+    /// Only less can tell, so other pagers are used with `--pager` alone.
+    Auto,
+    /// Always page when stdout is a terminal
+    Always,
+    /// Never page
+    Never,
+}
+
+const DEFAULT_PAGER: &str = "less -R";
+
+static MODE: Mutex<PagerMode> = Mutex::new(PagerMode::Never);
+
+#[cfg(unix)]
+static ACTIVE: Mutex<Option<imp::ActivePager>> = Mutex::new(None);
+
+/// Set the paging mode for this process
+pub fn configure(mode: PagerMode) {
+    *MODE.lock().unwrap() = mode;
+}
+
+/// `$LESS` flags to use when the user hasn't set their own
+fn less_flags(mode: PagerMode) -> &'static str {
+    match mode {
+        // -F quits immediately when the output fits on one screen
+        PagerMode::Auto => "FRX",
+        _ => "RX",
+    }
+}
+
+/// The pager to run: `$PAGER`, or less when it is unset. None when `PAGER` is
+/// empty or `cat`.
+fn pager_command(pager_env: Option<String>) -> Option<String> {
+    match pager_env {
+        None => Some(DEFAULT_PAGER.to_string()),
+        Some(pager) if pager.trim().is_empty() || pager.trim() == "cat" => None,
+        Some(pager) => Some(pager),
+    }
+}
+
+/// Whether the pager command runs less, the only pager auto mode can rely on to
+/// quit by itself when the output fits on one screen
+fn is_less(command: &str) -> bool {
+    command
+        .split_whitespace()
+        .next()
+        .and_then(|program| std::path::Path::new(program).file_name())
+        .is_some_and(|name| name == "less")
+}
+
+/// Start paging the remaining stdout output, if enabled
+pub fn start(output_format: OutputFormat) {
+    let mode = *MODE.lock().unwrap();
+    if mode == PagerMode::Never || output_format != OutputFormat::Pretty {
+        return;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::io::IsTerminal;
+
+        let dumb_terminal = std::env::var("TERM").is_ok_and(|term| term == "dumb");
+        if !std::io::stdout().is_terminal() || dumb_terminal {
+            return;
+        }
+
+        let Some(command) = pager_command(std::env::var("PAGER").ok()) else {
+            return;
+        };
+        if mode == PagerMode::Auto && !is_less(&command) {
+            return;
+        }
+
+        let mut active = ACTIVE.lock().unwrap();
+        if active.is_some() {
+            return;
+        }
+
+        // A pager that fails to start just means unpaged output
+        *active = imp::ActivePager::spawn(&command, less_flags(mode)).ok();
+    }
+}
+
+/// Flush output and wait for the pager (if any) to exit
+pub fn finish() {
+    #[cfg(unix)]
+    if let Some(pager) = ACTIVE.lock().unwrap().take() {
+        pager.close();
+    }
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::io::{self, Write};
+    use std::os::fd::{AsRawFd, IntoRawFd};
+    use std::process::{Child, Command, Stdio};
+
+    pub struct ActivePager {
+        child: Child,
+        saved_stdout: i32,
+    }
+
+    impl ActivePager {
+        pub fn spawn(command: &str, less_flags: &str) -> io::Result<Self> {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c").arg(command).stdin(Stdio::piped());
+            if std::env::var_os("LESS").is_none() {
+                cmd.env("LESS", less_flags);
+            }
+
+            let mut child = cmd.spawn()?;
+            let pipe = child.stdin.take().expect("pager stdin is piped");
+
+            io::stdout().flush()?;
+            let stdout_fd = io::stdout().as_raw_fd();
+            // SAFETY: plain fd juggling on descriptors we own; stdout is restored in close()
+            let saved_stdout = unsafe { libc::dup(stdout_fd) };
+            if saved_stdout < 0 {
+                let _ = child.kill();
+                return Err(io::Error::last_os_error());
+            }
+            let pipe_fd = pipe.into_raw_fd();
+            let redirected = unsafe { libc::dup2(pipe_fd, stdout_fd) };
+            unsafe { libc::close(pipe_fd) };
+            if redirected < 0 {
+                unsafe { libc::close(saved_stdout) };
+                let _ = child.kill();
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(Self { child, saved_stdout })
+        }
+
+        pub fn close(mut self) {
+            let _ = io::stdout().flush();
+            // Restoring stdout closes the pipe, so the pager sees EOF
+            unsafe {
+                libc::dup2(self.saved_stdout, io::stdout().as_raw_fd());
+                libc::close(self.saved_stdout);
+            }
+            let _ = self.child.wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_less_flags_auto_quits_when_short() {
+        assert!(less_flags(PagerMode::Auto).contains('F'));
+        assert!(!less_flags(PagerMode::Always).contains('F'));
+        assert!(less_flags(PagerMode::Always).contains('R'));
+    }
+
+    #[test]
+    fn test_pager_command_honours_empty_pager() {
+        assert_eq!(pager_command(None).as_deref(), Some(DEFAULT_PAGER));
+        assert_eq!(pager_command(Some("most".to_string())).as_deref(), Some("most"));
+        assert_eq!(pager_command(Some(String::new())), None);
+        assert_eq!(pager_command(Some(" cat ".to_string())), None);
+    }
+
+    #[test]
+    fn test_is_less() {
+        assert!(is_less(DEFAULT_PAGER));
+        assert!(is_less("/usr/bin/less -S"));
+        assert!(!is_less("more"));
+        assert!(!is_less("lesspipe"));
+    }
+}
@@ -0,0 +1,136 @@
+//! "Did you mean" hints for subcommands clap can't match on its own.
+//!
+//! clap already suggests close matches among the subcommands and flags of the
+//! command being parsed. This covers what it can't see: a nested subcommand
+//! typed at the wrong level (`hindsight recal`) and command names from older
+//! CLI versions (`hindsight search`, `hindsight think`).
+
+use clap::error::{ContextKind, ContextValue, ErrorKind};
+use clap::{Command, Error};
+
+/// Command names from older CLI versions and where they live now
+const LEGACY_COMMANDS: &[(&str, &str)] = &[
+    ("agent", "bank"),
+    ("search", "memory recall"),
+    ("think", "memory reflect"),
+    ("put", "memory retain"),
+    ("put-files", "memory retain-files"),
+    ("reflection", "mental-model"),
+    ("operations", "operation"),
+];
+
+/// Levenshtein distance between two sequences
+pub fn edit_distance<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Every visible subcommand below `cmd` as (name, full path), e.g. ("recall", "memory recall")
+fn subcommand_paths(cmd: &Command, prefix: &str, out: &mut Vec<(String, String)>) {
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set() && s.get_name() != "help") {
+        let path = format!("{}{}", prefix, sub.get_name());
+        out.push((sub.get_name().to_string(), path.clone()));
+        subcommand_paths(sub, &format!("{} ", path), out);
+    }
+}
+
+fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    edit_distance(&a, &b)
+}
+
+/// Closest command path for a mistyped or outdated subcommand name, with its distance
+fn closest(root: &Command, typed: &str) -> Option<(usize, String)> {
+    let mut candidates: Vec<(String, String)> = LEGACY_COMMANDS
+        .iter()
+        .map(|(name, path)| (name.to_string(), path.to_string()))
+        .collect();
+    subcommand_paths(root, "", &mut candidates);
+
+    candidates
+        .into_iter()
+        .filter_map(|(name, path)| {
+            let distance = distance(typed, &name);
+            // Allow roughly one typo per three characters
+            (distance <= (name.len() / 3).max(1)).then_some((distance, path))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(distance, path)| (distance, format!("{} {}", root.get_name(), path)))
+}
+
+/// Put our suggestion on an unknown-subcommand error, unless clap already found
+/// an equally close sibling (clap's own matching is looser and can be far off)
+pub fn with_suggestion(mut err: Error, root: &Command) -> Error {
+    if err.kind() != ErrorKind::InvalidSubcommand {
+        return err;
+    }
+    let typed = match err.get(ContextKind::InvalidSubcommand) {
+        Some(ContextValue::String(typed)) => typed.clone(),
+        _ => return err,
+    };
+    let Some((ours, path)) = closest(root, &typed) else {
+        return err;
+    };
+    let clap_best = match err.get(ContextKind::SuggestedSubcommand) {
+        Some(ContextValue::String(s)) => Some(distance(&typed, s)),
+        Some(ContextValue::Strings(list)) => list.iter().map(|s| distance(&typed, s)).min(),
+        _ => None,
+    };
+    if clap_best.is_none_or(|theirs| ours < theirs) {
+        err.insert(ContextKind::SuggestedSubcommand, ContextValue::String(path));
+    }
+    err
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_edit_distance() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        assert_eq!(edit_distance(&chars("serach"), &chars("search")), 2);
+        assert_eq!(edit_distance(&chars("kitten"), &chars("sitting")), 3);
+        assert_eq!(edit_distance(&chars(""), &chars("abc")), 3);
+    }
+
+    fn suggest(root: &Command, typed: &str) -> Option<String> {
+        closest(root, typed).map(|(_, path)| path)
+    }
+
+    #[test]
+    fn test_suggest_legacy_and_nested_commands() {
+        let root = crate::Cli::command();
+        assert_eq!(suggest(&root, "serach").as_deref(), Some("hindsight memory recall"));
+        assert_eq!(suggest(&root, "think").as_deref(), Some("hindsight memory reflect"));
+        assert_eq!(suggest(&root, "recal").as_deref(), Some("hindsight memory recall"));
+        assert_eq!(suggest(&root, "xyzzy"), None);
+    }
+
+    #[test]
+    fn test_with_suggestion_overrides_distant_clap_match() {
+        let root = crate::Cli::command();
+        let err = root.clone().try_get_matches_from(["hindsight", "serach"]).unwrap_err();
+        let err = with_suggestion(err, &root);
+        assert!(matches!(
+            err.get(ContextKind::SuggestedSubcommand),
+            Some(ContextValue::String(s)) if s == "hindsight memory recall"
+        ));
+
+        // clap's exact sibling match is kept
+        let err = root.clone().try_get_matches_from(["hindsight", "bank", "lst"]).unwrap_err();
+        let err = with_suggestion(err, &root);
+        assert!(err.to_string().contains("'list'"));
+    }
+}
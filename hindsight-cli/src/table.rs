@@ -0,0 +1,168 @@
+//! `--output table`: responses as aligned columns.
+//!
+//! A list renders one row per item; any other response renders as a single row.
+//! `--columns` picks what to show with dotted paths into the serialized value,
+//! e.g. `nodes_by_fact_type.episodic` or `results.0.text`.
+
+use anyhow::Result;
+use serde_json::Value;
+use std::sync::Mutex;
+
+/// Column paths from `--columns`; empty shows the scalar top-level fields
+static COLUMNS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+pub fn set_columns(columns: Vec<String>) {
+    *COLUMNS.lock().unwrap() = columns;
+}
+
+pub fn columns() -> Vec<String> {
+    COLUMNS.lock().map(|columns| columns.clone()).unwrap_or_default()
+}
+
+/// Split a column path into its segments, or None when it isn't `key(.key)*`
+/// with keys of letters, digits, `_` and `-`
+fn parse_path(path: &str) -> Option<Vec<&str>> {
+    path.split('.')
+        .map(|segment| {
+            let valid = !segment.is_empty()
+                && segment.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            valid.then_some(segment)
+        })
+        .collect()
+}
+
+/// Follow `path` through objects (by key) and arrays (by index)
+fn resolve<'a>(value: &'a Value, path: &[&str]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |current, segment| match current {
+        Value::Object(fields) => fields.get(*segment),
+        Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    })
+}
+
+/// Cell text: strings unquoted, null and missing paths empty, anything else as JSON
+fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Top-level fields holding scalars, in the response's order
+fn default_columns(row: &Value) -> Vec<String> {
+    let Some(fields) = row.as_object() else {
+        return Vec::new();
+    };
+    fields
+        .iter()
+        .filter(|(_, value)| !value.is_object() && !value.is_array())
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+/// Lay out `data` as a table with one line per row
+pub fn render(data: &Value, columns: &[String]) -> Result<String> {
+    let rows: Vec<&Value> = match data {
+        Value::Array(items) => items.iter().collect(),
+        other => vec![other],
+    };
+
+    let columns = if columns.is_empty() {
+        rows.first().map(|row| default_columns(row)).unwrap_or_default()
+    } else {
+        columns.to_vec()
+    };
+    let mut paths = Vec::with_capacity(columns.len());
+    for column in &columns {
+        let Some(path) = parse_path(column) else {
+            let mut available: Vec<&str> = rows
+                .first()
+                .and_then(|row| row.as_object())
+                .map(|fields| fields.keys().map(String::as_str).collect())
+                .unwrap_or_default();
+            available.sort_unstable();
+            anyhow::bail!(
+                "Invalid column path '{}'. Use dotted keys, e.g. a.b.0. Top-level keys: {}",
+                column,
+                available.join(", ")
+            );
+        };
+        paths.push(path);
+    }
+
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| paths.iter().map(|path| cell(resolve(row, path))).collect())
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .fold(column.chars().count(), usize::max)
+        })
+        .collect();
+
+    let line = |values: &[String]| {
+        let padded: Vec<String> = values
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!("{:<width$}", value, width = width))
+            .collect();
+        padded.join("  ").trim_end().to_string()
+    };
+    let mut table = line(&columns);
+    for row in &cells {
+        table.push('\n');
+        table.push_str(&line(row));
+    }
+    Ok(table)
+}
+
+/// Print `data` as a table using the `--columns` selection
+pub fn print(data: &Value) -> Result<()> {
+    println!("{}", render(data, &columns())?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_nested_paths() {
+        let stats = serde_json::json!({
+            "bank_id": "b",
+            "nodes_by_fact_type": { "episodic": 12 },
+            "pending_operations": 3,
+        });
+        let columns = vec!["nodes_by_fact_type.episodic".to_string(), "pending_operations".to_string(), "missing.path".to_string()];
+        assert_eq!(
+            render(&stats, &columns).unwrap(),
+            "nodes_by_fact_type.episodic  pending_operations  missing.path\n12                           3"
+        );
+    }
+
+    #[test]
+    fn test_render_list_with_default_columns() {
+        let banks = serde_json::json!([
+            { "bank_id": "alpha", "name": null, "tags": ["x"] },
+            { "bank_id": "b", "name": "Beta", "tags": [] },
+        ]);
+        assert_eq!(render(&banks, &[]).unwrap(), "bank_id  name\nalpha\nb        Beta");
+
+        let columns = vec!["tags.0".to_string()];
+        assert_eq!(render(&banks, &columns).unwrap(), "tags.0\nx\n");
+    }
+
+    #[test]
+    fn test_render_invalid_path_lists_keys() {
+        let data = serde_json::json!({ "b": 1, "a": 2 });
+        let err = render(&data, &["a..b".to_string()]).unwrap_err().to_string();
+        assert!(err.contains("Invalid column path 'a..b'"));
+        assert!(err.contains("Top-level keys: a, b"));
+    }
+}
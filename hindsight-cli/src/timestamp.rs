@@ -0,0 +1,106 @@
+//! Timestamps returned by the server (`created_at`, `updated_at`, event dates).
+//!
+//! Servers have sent RFC 3339 with and without fractional seconds, offsets
+//! without a colon, and date-times with no offset at all (taken as UTC).
+//! [`parse`] tries each known shape and, when none fits, keeps the raw text as
+//! [`Timestamp::Unparsed`] so displays can still show what the server sent.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Date-times carrying an offset, besides RFC 3339 proper
+const OFFSET_FORMATS: &[&str] = &["%Y-%m-%dT%H:%M:%S%.f%z", "%Y-%m-%d %H:%M:%S%.f%:z", "%Y-%m-%d %H:%M:%S%.f%z"];
+
+/// Date-times without an offset, read as UTC
+const NAIVE_FORMATS: &[&str] = &["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Timestamp {
+    Parsed(DateTime<Utc>),
+    /// Not in any known format; the text as received
+    Unparsed(String),
+}
+
+impl Timestamp {
+    pub fn as_utc(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Timestamp::Parsed(dt) => Some(*dt),
+            Timestamp::Unparsed(_) => None,
+        }
+    }
+}
+
+/// Warn about unparseable timestamps (`--verbose`)
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Timestamps already warned about, so a list with one bad value warns once
+static WARNED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+fn parse_known(raw: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Some(dt) = OFFSET_FORMATS.iter().find_map(|format| DateTime::parse_from_str(raw, format).ok()) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Some(dt) = NAIVE_FORMATS.iter().find_map(|format| NaiveDateTime::parse_from_str(raw, format).ok()) {
+        return Some(dt.and_utc());
+    }
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+}
+
+/// Parse a server timestamp, keeping the raw text when no known format matches
+pub fn parse(raw: &str) -> Timestamp {
+    let trimmed = raw.trim();
+    if let Some(dt) = parse_known(trimmed) {
+        return Timestamp::Parsed(dt);
+    }
+    if VERBOSE.load(Ordering::Relaxed) && !trimmed.is_empty() {
+        let first = WARNED.lock().unwrap().get_or_insert_with(HashSet::new).insert(trimmed.to_string());
+        if first {
+            crate::ui::print_warning(&format!("could not parse timestamp '{}'; showing it as received", trimmed));
+        }
+    }
+    Timestamp::Unparsed(raw.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_known_formats() {
+        let expected = "2024-03-05T14:30:00Z".parse::<DateTime<Utc>>().unwrap();
+        for raw in [
+            "2024-03-05T14:30:00Z",
+            "2024-03-05T14:30:00.000Z",
+            "2024-03-05T16:30:00+02:00",
+            "2024-03-05T16:30:00+0200",
+            "2024-03-05 16:30:00+02:00",
+            "2024-03-05T14:30:00",
+            "2024-03-05T14:30:00.123456",
+            "2024-03-05 14:30:00",
+            "2024-03-05T14:30",
+            " 2024-03-05T14:30:00Z ",
+        ] {
+            let parsed = parse(raw).as_utc().unwrap_or_else(|| panic!("{} did not parse", raw));
+            assert_eq!(parsed.timestamp(), expected.timestamp(), "{}", raw);
+        }
+        assert_eq!(parse("2024-03-05").as_utc().unwrap().to_rfc3339(), "2024-03-05T00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_keeps_unknown_text() {
+        assert_eq!(parse("last tuesday"), Timestamp::Unparsed("last tuesday".to_string()));
+        assert_eq!(parse(""), Timestamp::Unparsed(String::new()));
+    }
+}
@@ -1,8 +1,12 @@
 use crate::api::{BankProfileResponse, RecallResult, RecallResponse, ReflectResponse};
+use crate::output::OutputFormat;
+use crate::timestamp::Timestamp;
 use colored::*;
-use hindsight_client::types::ChunkData;
+use hindsight_client::types::{ChunkData, ReflectBasedOn, ReflectTrace};
 use indicatif::{ProgressBar, ProgressStyle};
 use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
 
 /// The logo as ANSI-colored text, generated by test-logo.py
 const LOGO: &str = include_str!("logo.ansi");
@@ -67,17 +71,265 @@ pub fn dim(text: &str) -> String {
     format!("\x1b[38;2;128;128;128m{}\x1b[0m", text)
 }
 
+/// Plain-ASCII symbols instead of Unicode (`--ascii`, or a locale without UTF-8)
+static ASCII_GLYPHS: AtomicBool = AtomicBool::new(false);
+
+pub fn set_ascii_glyphs(ascii: bool) {
+    ASCII_GLYPHS.store(ascii, Ordering::Relaxed);
+}
+
+fn ascii_glyphs() -> bool {
+    ASCII_GLYPHS.load(Ordering::Relaxed)
+}
+
+/// Whether the locale promises UTF-8 output. As in libc, the first of
+/// LC_ALL, LC_CTYPE and LANG that is set wins; none set means the "C" locale.
+pub fn locale_supports_unicode() -> bool {
+    if cfg!(windows) {
+        return true;
+    }
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty());
+    locale.is_some_and(|locale| is_utf8_locale(&locale))
+}
+
+fn is_utf8_locale(locale: &str) -> bool {
+    let locale = locale.to_ascii_lowercase();
+    locale.contains("utf-8") || locale.contains("utf8")
+}
+
+/// Status symbols shared by every command
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Glyph {
+    Success,
+    Warning,
+    Error,
+    Bullet,
+    Arrow,
+    Pending,
+}
+
+pub fn glyph(kind: Glyph) -> &'static str {
+    glyph_for(kind, ascii_glyphs())
+}
+
+fn glyph_for(kind: Glyph, ascii: bool) -> &'static str {
+    match (kind, ascii) {
+        (Glyph::Success, false) => "✓",
+        (Glyph::Success, true) => "[OK]",
+        (Glyph::Warning, false) => "⚠",
+        (Glyph::Warning, true) => "[!]",
+        (Glyph::Error, false) => "✗",
+        (Glyph::Error, true) => "[x]",
+        (Glyph::Bullet, false) => "•",
+        (Glyph::Bullet, true) => "*",
+        (Glyph::Arrow, false) => "→",
+        (Glyph::Arrow, true) => "->",
+        (Glyph::Pending, false) => "⏳",
+        (Glyph::Pending, true) => "...",
+    }
+}
+
+/// `─── title ───` sub-section rule (`--- title ---` in ASCII mode)
+pub fn rule(title: &str) -> String {
+    let line = if ascii_glyphs() { "---" } else { "───" };
+    format!("{} {} {}", line, title, line)
+}
+
+/// Show timestamps in UTC instead of the local timezone (`--utc`)
+static UTC_TIMESTAMPS: AtomicBool = AtomicBool::new(false);
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+pub fn set_utc_timestamps(utc: bool) {
+    UTC_TIMESTAMPS.store(utc, Ordering::Relaxed);
+}
+
+/// Render an API timestamp for Pretty output, in local time unless `--utc` was given
+pub fn format_timestamp(raw: &str) -> String {
+    format_timestamp_in(raw, UTC_TIMESTAMPS.load(Ordering::Relaxed))
+}
+
+/// Timestamps without an offset are UTC; anything unparseable is shown as-is
+fn format_timestamp_in(raw: &str, utc: bool) -> String {
+    match crate::timestamp::parse(raw) {
+        Timestamp::Parsed(dt) if utc => format!("{} UTC", dt.format(TIMESTAMP_FORMAT)),
+        Timestamp::Parsed(dt) => dt
+            .with_timezone(&chrono::Local)
+            .format(&format!("{} %:z", TIMESTAMP_FORMAT))
+            .to_string(),
+        Timestamp::Unparsed(raw) => raw,
+    }
+}
+
+/// The calendar date of an API timestamp, for narrow columns; same time zone rules
+/// as [`format_timestamp`]
+pub fn format_date(raw: &str) -> String {
+    format_date_in(raw, UTC_TIMESTAMPS.load(Ordering::Relaxed))
+}
+
+fn format_date_in(raw: &str, utc: bool) -> String {
+    match crate::timestamp::parse(raw) {
+        Timestamp::Parsed(dt) if utc => dt.format("%Y-%m-%d").to_string(),
+        Timestamp::Parsed(dt) => dt.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string(),
+        Timestamp::Unparsed(raw) => raw,
+    }
+}
+
+/// Thousands separator for counts in Pretty output (`--locale`)
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum NumberLocale {
+    /// 1,234,567
+    Comma,
+    /// 1 234 567
+    Space,
+    /// 1234567
+    None,
+}
+
+static NUMBER_LOCALE: Mutex<NumberLocale> = Mutex::new(NumberLocale::Comma);
+
+/// Decimal places for activation scores and timings (`--precision`)
+static PRECISION: AtomicUsize = AtomicUsize::new(3);
+
+pub fn set_number_format(locale: NumberLocale, precision: usize) {
+    *NUMBER_LOCALE.lock().unwrap() = locale;
+    PRECISION.store(precision, Ordering::Relaxed);
+}
+
+/// Render a count for Pretty output with the configured thousands separator
+pub fn format_count(count: i64) -> String {
+    format_count_in(count, *NUMBER_LOCALE.lock().unwrap())
+}
+
+fn format_count_in(count: i64, locale: NumberLocale) -> String {
+    let separator = match locale {
+        NumberLocale::Comma => ',',
+        NumberLocale::Space => ' ',
+        NumberLocale::None => return count.to_string(),
+    };
+    let digits = count.unsigned_abs().to_string();
+    let mut out = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(separator);
+        }
+        out.push(digit);
+    }
+    if count < 0 {
+        out.insert(0, '-');
+    }
+    out
+}
+
+/// Render a score or timing with `--precision` decimal places
+pub fn format_decimal(value: f64) -> String {
+    format!("{:.*}", PRECISION.load(Ordering::Relaxed), value)
+}
+
 pub fn get_logo() -> &'static str {
     LOGO
 }
 
 pub fn print_section_header(title: &str) {
     println!();
-    println!("{}", gradient_text(&format!("━━━ {} ━━━", title)));
+    let line = if ascii_glyphs() { "===" } else { "━━━" };
+    println!("{}", gradient_text(&format!("{} {} {}", line, title, line)));
     println!();
 }
 
-pub fn print_fact(fact: &RecallResult, _show_activation: bool) {
+/// Print facts one per line (`--compact-facts`)
+static COMPACT_FACTS: AtomicBool = AtomicBool::new(false);
+
+/// Longest text preview when `--max-text-width` is unset and stdout is not a terminal
+const PREVIEW_CHARS: usize = 100;
+
+/// `--max-text-width`; None when not given
+static MAX_TEXT_WIDTH: Mutex<Option<usize>> = Mutex::new(None);
+
+pub fn set_max_text_width(width: Option<usize>) {
+    *MAX_TEXT_WIDTH.lock().unwrap() = width;
+}
+
+/// Characters of fact text a one-line preview may use, None for the full text.
+/// Without `--max-text-width` the preview fills the terminal after `reserved`
+/// columns of prefix.
+pub fn preview_width(reserved: usize) -> Option<usize> {
+    use std::io::IsTerminal;
+
+    match *MAX_TEXT_WIDTH.lock().unwrap() {
+        Some(0) => None,
+        Some(width) => Some(width),
+        None if io::stdout().is_terminal() => {
+            let columns = crossterm::terminal::size().map_or(PREVIEW_CHARS + reserved, |(columns, _)| columns as usize);
+            Some(columns.saturating_sub(reserved).max(20))
+        }
+        None => Some(PREVIEW_CHARS),
+    }
+}
+
+/// Shorten `text` to at most `max` user-visible characters, ending in `…` when cut.
+/// Cuts between grapheme clusters, so accents and emoji are never split.
+pub fn truncate_text(text: &str, max: usize) -> String {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    if text.graphemes(true).nth(max).is_none() {
+        return text.to_string();
+    }
+    let kept: String = text.graphemes(true).take(max.saturating_sub(1)).collect();
+    format!("{}…", kept)
+}
+
+pub fn set_compact_facts(compact: bool) {
+    COMPACT_FACTS.store(compact, Ordering::Relaxed);
+}
+
+fn compact_facts() -> bool {
+    COMPACT_FACTS.load(Ordering::Relaxed)
+}
+
+/// `--show-context` (Some(true)) / `--hide-context` (Some(false)); None follows the layout
+static FACT_CONTEXT: Mutex<Option<bool>> = Mutex::new(None);
+
+pub fn set_fact_context(show: Option<bool>) {
+    *FACT_CONTEXT.lock().unwrap() = show;
+}
+
+/// Whether fact displays include `context`: shown in the detailed view, hidden in compact
+pub fn show_fact_context() -> bool {
+    FACT_CONTEXT.lock().ok().and_then(|show| *show).unwrap_or(!compact_facts())
+}
+
+/// `<id> [type] <text preview>` on a single line, followed by `(context: ...)` if asked for
+fn compact_fact_line(fact: &RecallResult, show_context: bool, width: impl FnOnce(usize) -> Option<usize>) -> String {
+    let prefix = format!("{} [{}] ", fact.id, fact.type_.as_deref().unwrap_or("unknown"));
+    let text = fact.text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let preview = match width(prefix.chars().count()) {
+        Some(max) => truncate_text(&text, max),
+        None => text,
+    };
+    let line = format!("{}{}", prefix, preview);
+    match fact.context.as_deref() {
+        Some(context) if show_context && !context.trim().is_empty() => {
+            format!("{} (context: {})", line, context.split_whitespace().collect::<Vec<_>>().join(" "))
+        }
+        _ => line,
+    }
+}
+
+/// The activation score a server reported in the fact's metadata, if any
+pub fn fact_activation(fact: &RecallResult) -> Option<f64> {
+    fact.metadata.as_ref()?.get("activation")?.parse().ok()
+}
+
+pub fn print_fact(fact: &RecallResult, show_activation: bool) {
+    if compact_facts() {
+        println!("{}", compact_fact_line(fact, show_fact_context(), preview_width));
+        return;
+    }
+
     let fact_type = fact.type_.as_deref().unwrap_or("unknown");
 
     // Use gradient positions for different fact types
@@ -89,10 +341,13 @@ pub fn print_fact(fact: &RecallResult, _show_activation: bool) {
     };
 
     println!("{}", gradient(&format!("[{}]", fact_type.to_uppercase()), type_t));
-    println!("  {}", fact.text);
+    match *MAX_TEXT_WIDTH.lock().unwrap() {
+        Some(max) if max > 0 => println!("  {}", truncate_text(&fact.text, max)),
+        _ => println!("  {}", fact.text),
+    }
 
-    // Show context if available
-    if let Some(context) = &fact.context {
+    // Show context if available and not hidden with --hide-context
+    if let Some(context) = fact.context.as_ref().filter(|_| show_fact_context()) {
         println!("  {} {}", dim("context:"), dim(context));
     }
 
@@ -110,11 +365,17 @@ pub fn print_fact(fact: &RecallResult, _show_activation: bool) {
         println!("  {} {}", dim("document:"), dim(document_id));
     }
 
+    if show_activation {
+        if let Some(activation) = fact_activation(fact) {
+            println!("  {} {}", dim("activation:"), dim(&format_decimal(activation)));
+        }
+    }
+
     println!();
 }
 
 pub fn print_chunk(chunk: &ChunkData) {
-    println!("  {}", gradient_mid("─── Source Chunk ───"));
+    println!("  {}", gradient_mid(&rule("Source Chunk")));
 
     // Split text into lines and indent each line
     for line in chunk.text.lines() {
@@ -143,7 +404,9 @@ pub fn print_search_results(response: &RecallResponse, show_trace: bool, show_ch
         println!("  {}", dim("No results found."));
     } else {
         for (i, fact) in results.iter().enumerate() {
-            println!("  {}", dim(&format!("Result #{}", i + 1)));
+            if !compact_facts() {
+                println!("  {}", dim(&format!("Result #{}", i + 1)));
+            }
             print_fact(fact, true);
 
             // Show chunk if available and requested
@@ -182,53 +445,219 @@ pub fn print_think_response(response: &ReflectResponse) {
     // Display structured output if present
     if let Some(structured) = &response.structured_output {
         println!();
-        println!("{}", gradient_text("─── Structured Output ───"));
+        println!("{}", gradient_text(&rule("Structured Output")));
         println!();
-        if let Ok(json) = serde_json::to_string_pretty(structured) {
+        if let Ok(json) = crate::output::to_json(structured) {
             println!("{}", json);
         }
     }
 }
 
+/// Print only the facts a reflection was based on
+pub fn print_reflect_sources(based_on: Option<&ReflectBasedOn>) {
+    let memories = based_on.map(|b| b.memories.as_slice()).unwrap_or_default();
+    print_section_header(&format!("Sources ({})", memories.len()));
+
+    if memories.is_empty() {
+        println!("  {}", dim("No source memories returned."));
+        println!();
+        return;
+    }
+
+    for memory in memories {
+        let fact = RecallResult {
+            chunk_id: None,
+            context: memory.context.clone(),
+            document_id: None,
+            entities: None,
+            id: memory.id.clone().unwrap_or_default(),
+            mentioned_at: None,
+            metadata: None,
+            occurred_end: memory.occurred_end.clone(),
+            occurred_start: memory.occurred_start.clone(),
+            tags: None,
+            text: memory.text.clone(),
+            type_: memory.type_.clone(),
+        };
+        print_fact(&fact, false);
+    }
+}
+
 pub fn print_trace_info(trace: &serde_json::Map<String, serde_json::Value>) {
     print_section_header("Trace");
 
     if let Some(time) = trace.get("total_time").and_then(|v| v.as_f64()) {
-        println!("  {} {}", dim("total time:"), gradient_start(&format!("{:.2}ms", time)));
+        println!("  {} {}", dim("total time:"), gradient_start(&format!("{}ms", format_decimal(time))));
     }
 
     if let Some(count) = trace.get("activation_count").and_then(|v| v.as_i64()) {
-        println!("  {} {}", dim("activation count:"), gradient_end(&count.to_string()));
+        println!("  {} {}", dim("activation count:"), gradient_end(&format_count(count)));
+    }
+
+    println!();
+}
+
+/// Print the tool and LLM calls a reflection made, with their timings,
+/// and the seed sent with the request
+pub fn print_reflect_trace(trace: Option<&ReflectTrace>, seed: Option<u64>) {
+    print_section_header("Trace");
+
+    if let Some(seed) = seed {
+        println!("  {} {}", dim("seed:"), seed);
+    }
+
+    let Some(trace) = trace else {
+        println!("  {}", dim("No trace returned."));
+        println!();
+        return;
+    };
+
+    let total: i64 = trace.llm_calls.iter().map(|c| c.duration_ms).sum::<i64>()
+        + trace.tool_calls.iter().map(|c| c.duration_ms).sum::<i64>();
+    println!("  {} {}", dim("call time:"), gradient_start(&format!("{}ms", total)));
+
+    for call in &trace.tool_calls {
+        println!("  {} {} {}", dim(&format!("#{}", call.iteration)), call.tool, gradient_end(&format!("{}ms", call.duration_ms)));
+    }
+
+    for call in &trace.llm_calls {
+        println!("  {} {} {}", dim("llm"), call.scope, gradient_end(&format!("{}ms", call.duration_ms)));
     }
 
     println!();
 }
 
+/// Set for JSON/YAML/raw/table output, where stdout carries only the command's data
+static STATUS_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+pub fn set_output_format(format: OutputFormat) {
+    STATUS_TO_STDERR.store(format != OutputFormat::Pretty, Ordering::Relaxed);
+}
+
+/// Print a status line: on stdout in pretty mode, on stderr otherwise
+fn print_status(line: String) {
+    if STATUS_TO_STDERR.load(Ordering::Relaxed) {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+}
+
 pub fn print_success(message: &str) {
-    println!("{}", gradient_start(message));
+    print_status(gradient_start(&format!("{} {}", glyph(Glyph::Success), message)));
 }
 
 pub fn print_error(message: &str) {
-    eprintln!("{} {}", "error:".bright_red().bold(), message.bright_red());
+    eprintln!("{} {} {}", glyph(Glyph::Error).bright_red().bold(), "error:".bright_red().bold(), message.bright_red());
 }
 
+/// Warnings raised during this run, echoed as `_warnings` in `--envelope`/`--with-meta` output
+static WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Report a non-fatal condition: printed to stderr in the warning style and
+/// recorded so structured output can carry it too
 pub fn print_warning(message: &str) {
-    println!("{} {}", gradient_end("warning:"), message);
+    eprintln!("{} {}", gradient_end(&format!("{} warning:", glyph(Glyph::Warning))), message);
+    if let Ok(mut warnings) = WARNINGS.lock() {
+        warnings.push(message.to_string());
+    }
+}
+
+/// Warnings recorded since the last call
+pub fn take_warnings() -> Vec<String> {
+    WARNINGS.lock().map(|mut warnings| std::mem::take(&mut *warnings)).unwrap_or_default()
 }
 
 pub fn print_info(message: &str) {
-    println!("{}", gradient_start(message));
+    print_status(gradient_start(message));
 }
 
-/// Animated gradient spinner that shows text with moving gradient colors
+/// Human-readable wall-clock duration: "850ms", "3.42s" or "2m 05s"
+pub fn format_elapsed(elapsed: std::time::Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs >= 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else if secs >= 1 {
+        format!("{:.2}s", elapsed.as_secs_f64())
+    } else {
+        format!("{}ms", elapsed.as_millis())
+    }
+}
+
+/// `--show-elapsed` footer; always on stderr so JSON/YAML output stays parseable
+pub fn print_elapsed(elapsed: std::time::Duration, succeeded: bool) {
+    let verb = if succeeded { "completed in" } else { "failed after" };
+    eprintln!("{}", dim(&format!("{} {}", verb, format_elapsed(elapsed))));
+}
+
+/// How the progress spinner is drawn (`--spinner-style`)
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum SpinnerStyle {
+    /// Message text with a moving color gradient
+    Gradient,
+    /// Braille dots before the message
+    Dots,
+    /// Rotating line before the message
+    Line,
+    /// Clock face before the message
+    Clock,
+    /// Static message followed by a dot every second; no carriage returns
+    Simple,
+}
+
+static SPINNER_STYLE: std::sync::Mutex<SpinnerStyle> = std::sync::Mutex::new(SpinnerStyle::Gradient);
+
+pub fn set_spinner_style(style: SpinnerStyle) {
+    *SPINNER_STYLE.lock().unwrap() = style;
+}
+
+const DOTS_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const LINE_FRAMES: &[&str] = &["-", "\\", "|", "/"];
+const CLOCK_FRAMES: &[&str] = &["🕛", "🕐", "🕑", "🕒", "🕓", "🕔", "🕕", "🕖", "🕗", "🕘", "🕙", "🕚"];
+
+/// Animation step of the spinner thread; `Simple` only redraws once per second
+const SPINNER_TICK: std::time::Duration = std::time::Duration::from_millis(80);
+const SIMPLE_DOT_TICKS: usize = 12;
+
+/// One redraw of an animated style, starting with a carriage return
+fn spinner_frame(style: SpinnerStyle, chars: &[char], frame: usize) -> String {
+    let glyphs = match style {
+        SpinnerStyle::Dots => DOTS_FRAMES,
+        SpinnerStyle::Line => LINE_FRAMES,
+        SpinnerStyle::Clock => CLOCK_FRAMES,
+        SpinnerStyle::Gradient | SpinnerStyle::Simple => {
+            let num_frames = 30;
+            let offset = (frame % num_frames) as f32 / num_frames as f32;
+            let len = chars.len();
+            let mut result = String::from("\r");
+            for (i, ch) in chars.iter().enumerate() {
+                if *ch == ' ' {
+                    result.push(' ');
+                } else {
+                    let base_t = if len > 1 { i as f32 / (len - 1) as f32 } else { 0.0 };
+                    let t = (base_t + offset) % 1.0;
+                    let (r, g, b) = interpolate_color(GRADIENT_START, GRADIENT_END, t);
+                    result.push_str(&format!("\x1b[38;2;{};{};{}m{}", r, g, b, ch));
+                }
+            }
+            result.push_str("\x1b[0m");
+            return result;
+        }
+    };
+    let message: String = chars.iter().collect();
+    format!("\r{} {}", glyphs[frame % glyphs.len()], gradient_start(&message))
+}
+
+/// Animated spinner shown while a request is in flight
 pub struct GradientSpinner {
     message: String,
+    style: SpinnerStyle,
     running: std::sync::Arc<std::sync::atomic::AtomicBool>,
     handle: Option<std::thread::JoinHandle<()>>,
 }
 
 impl GradientSpinner {
-    pub fn new(message: &str) -> Self {
+    pub fn with_style(message: &str, style: SpinnerStyle) -> Self {
         let message = message.to_string();
         let running = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
 
@@ -237,37 +666,32 @@ impl GradientSpinner {
 
         let handle = std::thread::spawn(move || {
             let chars: Vec<char> = msg_clone.chars().collect();
-            let len = chars.len();
-            let num_frames = 30;
             let mut current_frame = 0usize;
 
+            if style == SpinnerStyle::Simple {
+                print!("{}", msg_clone);
+                let _ = io::stdout().flush();
+            }
+
             while running_clone.load(std::sync::atomic::Ordering::Relaxed) {
-                current_frame = (current_frame + 1) % num_frames;
-                let offset = current_frame as f32 / num_frames as f32;
-
-                // Build the gradient string
-                let mut result = String::from("\r");
-                for (i, ch) in chars.iter().enumerate() {
-                    if *ch == ' ' {
-                        result.push(' ');
-                    } else {
-                        let base_t = if len > 1 { i as f32 / (len - 1) as f32 } else { 0.0 };
-                        let t = (base_t + offset) % 1.0;
-                        let (r, g, b) = interpolate_color(GRADIENT_START, GRADIENT_END, t);
-                        result.push_str(&format!("\x1b[38;2;{};{};{}m{}", r, g, b, ch));
+                current_frame += 1;
+                if style == SpinnerStyle::Simple {
+                    if current_frame.is_multiple_of(SIMPLE_DOT_TICKS) {
+                        print!(".");
+                        let _ = io::stdout().flush();
                     }
+                } else {
+                    print!("{}", spinner_frame(style, &chars, current_frame));
+                    let _ = io::stdout().flush();
                 }
-                result.push_str("\x1b[0m");
-
-                print!("{}", result);
-                let _ = io::stdout().flush();
 
-                std::thread::sleep(std::time::Duration::from_millis(80));
+                std::thread::sleep(SPINNER_TICK);
             }
         });
 
         Self {
             message,
+            style,
             running,
             handle: Some(handle),
         }
@@ -278,8 +702,13 @@ impl GradientSpinner {
         if let Some(handle) = self.handle.take() {
             let _ = handle.join();
         }
-        // Clear the line
-        print!("\r{}\r", " ".repeat(self.message.len() + 10));
+        if self.style == SpinnerStyle::Simple {
+            // Nothing to erase without carriage returns; just end the line
+            println!();
+        } else {
+            // Clear the line
+            print!("\r{}\r", " ".repeat(self.message.len() + 10));
+        }
         let _ = io::stdout().flush();
     }
 }
@@ -293,7 +722,81 @@ impl Drop for GradientSpinner {
 }
 
 pub fn create_spinner(message: &str) -> GradientSpinner {
-    GradientSpinner::new(message)
+    GradientSpinner::with_style(message, *SPINNER_STYLE.lock().unwrap())
+}
+
+/// When commands show a progress spinner
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpinnerMode {
+    /// Only for Pretty output on a terminal
+    Auto,
+    /// For Pretty output, even when stdout is piped (`--spinner`)
+    Always,
+    /// Never (`--no-spinner`)
+    Never,
+}
+
+static SPINNER_MODE: std::sync::Mutex<SpinnerMode> = std::sync::Mutex::new(SpinnerMode::Auto);
+
+pub fn set_spinner_mode(mode: SpinnerMode) {
+    *SPINNER_MODE.lock().unwrap() = mode;
+}
+
+fn spinner_enabled(mode: SpinnerMode, output_format: OutputFormat, is_terminal: bool) -> bool {
+    // The spinner draws on stdout, so it must never interleave with JSON/YAML
+    if output_format != OutputFormat::Pretty {
+        return false;
+    }
+    match mode {
+        SpinnerMode::Auto => is_terminal,
+        SpinnerMode::Always => true,
+        SpinnerMode::Never => false,
+    }
+}
+
+/// A spinner that is cleared when dropped, so an early `?` return never leaves
+/// it animating over the error message
+pub struct SpinnerGuard(Option<GradientSpinner>);
+
+impl SpinnerGuard {
+    /// A guard with no spinner, for when progress shouldn't be shown
+    pub fn none() -> Self {
+        Self(None)
+    }
+
+    /// Stop and clear the spinner before printing results; later calls do nothing
+    pub fn finish(&mut self) {
+        if let Some(mut spinner) = self.0.take() {
+            spinner.finish();
+        }
+    }
+}
+
+impl Drop for SpinnerGuard {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Like [`spinner`], wrapped in a [`SpinnerGuard`]
+pub fn spinner_guard(output_format: OutputFormat, message: &str) -> SpinnerGuard {
+    SpinnerGuard(spinner(output_format, message))
+}
+
+/// Start a spinner if the output format and `--spinner`/`--no-spinner` allow it
+pub fn spinner(output_format: OutputFormat, message: &str) -> Option<GradientSpinner> {
+    use std::io::IsTerminal;
+
+    let mode = *SPINNER_MODE.lock().unwrap();
+    let is_terminal = io::stdout().is_terminal();
+    if !spinner_enabled(mode, output_format, is_terminal) {
+        return None;
+    }
+    // `--spinner` into a pipe or log file: carriage-return animation would garble it
+    if !is_terminal {
+        return Some(GradientSpinner::with_style(message, SpinnerStyle::Simple));
+    }
+    Some(create_spinner(message))
 }
 
 pub fn create_progress_bar(total: u64, message: &str) -> ProgressBar {
@@ -302,13 +805,46 @@ pub fn create_progress_bar(total: u64, message: &str) -> ProgressBar {
         ProgressStyle::default_bar()
             .template("{msg} [{bar:40.cyan/blue}] {pos}/{len} ({percent}%)")
             .unwrap()
-            .progress_chars("█▓▒░ "),
+            .progress_chars(if ascii_glyphs() { "#>-" } else { "█▓▒░ " }),
     );
     pb.set_message(message.to_string());
     pb
 }
 
+/// Answer every confirmation with yes (`--assume-yes` / `HINDSIGHT_ASSUME_YES=1`)
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+pub fn set_assume_yes(assume_yes: bool) {
+    ASSUME_YES.store(assume_yes, Ordering::Relaxed);
+}
+
+/// The answer when no one can be asked: yes when assumed, and otherwise no when
+/// stdin is not a terminal, so CI never hangs on a prompt. None means ask.
+fn unattended_answer(assume_yes: bool, stdin_is_terminal: bool) -> Option<bool> {
+    match (assume_yes, stdin_is_terminal) {
+        (true, _) => Some(true),
+        (false, false) => Some(false),
+        (false, true) => None,
+    }
+}
+
+fn answer_without_prompt(message: &str) -> Option<bool> {
+    use std::io::IsTerminal;
+
+    let answer = unattended_answer(ASSUME_YES.load(Ordering::Relaxed), io::stdin().is_terminal())?;
+    if answer {
+        eprintln!("{} {}", gradient_start(message), dim("(yes: --assume-yes)"));
+    } else {
+        eprintln!("{}", gradient_start(message));
+        print_warning("Not confirmed: stdin is not a terminal. Pass -y or set HINDSIGHT_ASSUME_YES=1 to proceed");
+    }
+    Some(answer)
+}
+
 pub fn prompt_confirmation(message: &str) -> io::Result<bool> {
+    if let Some(answer) = answer_without_prompt(message) {
+        return Ok(answer);
+    }
     print!("{} [y/N]: ", gradient_start(message));
     io::stdout().flush()?;
 
@@ -318,6 +854,21 @@ pub fn prompt_confirmation(message: &str) -> io::Result<bool> {
     Ok(input.trim().eq_ignore_ascii_case("y") || input.trim().eq_ignore_ascii_case("yes"))
 }
 
+/// Ask the user to type `expected` to confirm, for operations too destructive for y/N
+pub fn prompt_typed_confirmation(message: &str, expected: &str) -> io::Result<bool> {
+    if let Some(answer) = answer_without_prompt(message) {
+        return Ok(answer);
+    }
+    println!("{}", gradient_start(message));
+    print!("Type {} to confirm: ", gradient_end(expected));
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(input.trim() == expected)
+}
+
 pub fn print_disposition(profile: &BankProfileResponse) {
     print_section_header(&format!("Disposition: {}", profile.bank_id));
 
@@ -335,7 +886,7 @@ pub fn print_disposition(profile: &BankProfileResponse) {
     }
 
     // Print disposition traits
-    println!("{}", gradient_text("─── Disposition Traits ───"));
+    println!("{}", gradient_text(&rule("Disposition Traits")));
     println!();
 
     // New 3-trait disposition system (values 1-5)
@@ -351,7 +902,8 @@ pub fn print_disposition(profile: &BankProfileResponse) {
         let filled = ((*value - 1) * 10) as usize; // 1->0, 2->10, 3->20, 4->30, 5->40
         let empty = bar_length - filled;
 
-        let bar = format!("{}{}", "█".repeat(filled), "░".repeat(empty));
+        let (full, blank) = if ascii_glyphs() { ("#", ".") } else { ("█", "░") };
+        let bar = format!("{}{}", full.repeat(filled), blank.repeat(empty));
 
         println!("  {:<12} [{}] {}/5",
             name,
@@ -363,3 +915,158 @@ pub fn print_disposition(profile: &BankProfileResponse) {
 
     println!();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_fact_line() {
+        let fact: RecallResult = serde_json::from_value(serde_json::json!({
+            "id": "f1",
+            "text": "Alice works\nat   Google",
+            "type": "world",
+        }))
+        .unwrap();
+        let preview = |_| Some(PREVIEW_CHARS);
+        assert_eq!(compact_fact_line(&fact, true, preview), "f1 [world] Alice works at Google");
+
+        let with_context = RecallResult { context: Some("team\nmeeting".to_string()), ..fact.clone() };
+        assert_eq!(compact_fact_line(&with_context, false, preview), "f1 [world] Alice works at Google");
+        assert_eq!(compact_fact_line(&with_context, true, preview), "f1 [world] Alice works at Google (context: team meeting)");
+
+        let long = RecallResult { text: "x".repeat(150), type_: None, ..fact };
+        let line = compact_fact_line(&long, false, preview);
+        assert!(line.starts_with("f1 [unknown] "));
+        assert_eq!(line.chars().count(), "f1 [unknown] ".len() + PREVIEW_CHARS);
+
+        // The prefix width is reserved, and None keeps the whole text
+        let fitted = compact_fact_line(&long, false, |reserved| Some(40 - reserved));
+        assert_eq!(fitted.chars().count(), 40);
+        assert_eq!(compact_fact_line(&long, false, |_| None).chars().count(), "f1 [unknown] ".len() + 150);
+    }
+
+    #[test]
+    fn test_truncate_text_keeps_graphemes() {
+        assert_eq!(truncate_text("short", 10), "short");
+        assert_eq!(truncate_text("exactly", 7), "exactly");
+        // "é" written as e + combining accent stays one character
+        assert_eq!(truncate_text("cafe\u{301} au lait", 5), "cafe\u{301}…");
+        assert_eq!(truncate_text("👍🏽👍🏽👍🏽", 2), "👍🏽…");
+    }
+
+    #[test]
+    fn test_unattended_answer() {
+        assert_eq!(unattended_answer(true, false), Some(true));
+        assert_eq!(unattended_answer(true, true), Some(true));
+        assert_eq!(unattended_answer(false, false), Some(false));
+        assert_eq!(unattended_answer(false, true), None);
+    }
+
+    #[test]
+    fn test_format_count_in() {
+        assert_eq!(format_count_in(0, NumberLocale::Comma), "0");
+        assert_eq!(format_count_in(999, NumberLocale::Comma), "999");
+        assert_eq!(format_count_in(1234567, NumberLocale::Comma), "1,234,567");
+        assert_eq!(format_count_in(-12345, NumberLocale::Space), "-12 345");
+        assert_eq!(format_count_in(1234567, NumberLocale::None), "1234567");
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        use std::time::Duration;
+        assert_eq!(format_elapsed(Duration::from_millis(850)), "850ms");
+        assert_eq!(format_elapsed(Duration::from_millis(3420)), "3.42s");
+        assert_eq!(format_elapsed(Duration::from_secs(125)), "2m 05s");
+    }
+
+    #[test]
+    fn test_spinner_guard_finish_is_idempotent() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let running = Arc::new(AtomicBool::new(true));
+        let mut guard = SpinnerGuard(Some(GradientSpinner {
+            message: "Working".to_string(),
+            style: SpinnerStyle::Gradient,
+            running: running.clone(),
+            handle: None,
+        }));
+        guard.finish();
+        assert!(!running.load(Ordering::Relaxed));
+        assert!(guard.0.is_none());
+
+        running.store(true, Ordering::Relaxed);
+        guard.finish();
+        assert!(running.load(Ordering::Relaxed), "a second finish must not touch the spinner again");
+    }
+
+    #[test]
+    fn test_spinner_guard_is_empty_for_machine_output() {
+        assert!(spinner_guard(OutputFormat::Json, "Working").0.is_none());
+        assert!(SpinnerGuard::none().0.is_none());
+    }
+
+    #[test]
+    fn test_spinner_frame_styles() {
+        let chars: Vec<char> = "Working".chars().collect();
+        assert!(spinner_frame(SpinnerStyle::Line, &chars, 0).starts_with("\r- "));
+        assert!(spinner_frame(SpinnerStyle::Line, &chars, 5).starts_with("\r\\ "));
+        assert!(spinner_frame(SpinnerStyle::Dots, &chars, 0).starts_with("\r⠋ "));
+        let gradient = spinner_frame(SpinnerStyle::Gradient, &chars, 3);
+        assert!(gradient.starts_with("\r\x1b[38;2;"));
+        assert!(gradient.ends_with("\x1b[0m"));
+    }
+
+    #[test]
+    fn test_spinner_enabled() {
+        assert!(spinner_enabled(SpinnerMode::Auto, OutputFormat::Pretty, true));
+        assert!(!spinner_enabled(SpinnerMode::Auto, OutputFormat::Pretty, false));
+        assert!(spinner_enabled(SpinnerMode::Always, OutputFormat::Pretty, false));
+        assert!(!spinner_enabled(SpinnerMode::Never, OutputFormat::Pretty, true));
+        assert!(!spinner_enabled(SpinnerMode::Always, OutputFormat::Json, true));
+    }
+
+    #[test]
+    fn test_format_timestamp_utc() {
+        assert_eq!(format_timestamp_in("2024-01-15T10:00:00Z", true), "2024-01-15 10:00:00 UTC");
+        assert_eq!(format_timestamp_in("2024-01-15T12:00:00+02:00", true), "2024-01-15 10:00:00 UTC");
+        assert_eq!(format_timestamp_in("2024-01-15T10:00:00.123456", true), "2024-01-15 10:00:00 UTC");
+    }
+
+    #[test]
+    fn test_format_timestamp_local_keeps_offset() {
+        let local = format_timestamp_in("2024-01-15T10:00:00Z", false);
+        assert!(local.starts_with("2024-01-1"));
+        assert!(local.contains(':'));
+    }
+
+    #[test]
+    fn test_format_date_utc() {
+        assert_eq!(format_date_in("2024-01-15T23:30:00-02:00", true), "2024-01-16");
+        assert_eq!(format_date_in("2024-01-15 10:00:00", true), "2024-01-15");
+        assert_eq!(format_date_in("last week", true), "last week");
+    }
+
+    #[test]
+    fn test_format_timestamp_falls_back_to_raw() {
+        assert_eq!(format_timestamp_in("yesterday", false), "yesterday");
+        assert_eq!(format_timestamp_in("unknown", true), "unknown");
+    }
+
+    #[test]
+    fn test_glyphs_fall_back_to_ascii() {
+        assert_eq!(glyph_for(Glyph::Success, false), "✓");
+        assert_eq!(glyph_for(Glyph::Success, true), "[OK]");
+        assert_eq!(glyph_for(Glyph::Warning, true), "[!]");
+        assert_eq!(glyph_for(Glyph::Error, true), "[x]");
+    }
+
+    #[test]
+    fn test_is_utf8_locale() {
+        assert!(is_utf8_locale("en_US.UTF-8"));
+        assert!(is_utf8_locale("C.utf8"));
+        assert!(!is_utf8_locale("C"));
+        assert!(!is_utf8_locale("POSIX"));
+    }
+}
@@ -123,3 +123,115 @@ fn test_configure_command() {
     // Cleanup
     std::fs::remove_dir_all(&temp_dir).ok();
 }
+
+#[test]
+fn test_blank_directive_content_rejected() {
+    // Rejected during argument parsing, so no server is needed
+    let output = Command::new("cargo")
+        .args(["run", "--", "directive", "create", "my-bank", "style", "   "])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("must not be empty"));
+}
+
+#[test]
+fn test_directive_create_dry_run_prints_request() {
+    // --dry-run never contacts the server
+    let output = Command::new("cargo")
+        .args(["run", "--", "directive", "create", "my-bank", "style", "Be concise", "--dry-run"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let body: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(body["name"], "style");
+    assert_eq!(body["is_active"], true);
+    assert_eq!(body["priority"], 0);
+}
+
+#[test]
+fn test_default_bank_fills_omitted_bank_id() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "--bank", "default-bank", "directive", "create", "style", "Be concise", "--dry-run"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("/banks/default-bank/directives"));
+    let body: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(body["name"], "style");
+    assert_eq!(body["content"], "Be concise");
+}
+
+#[test]
+fn test_mental_model_create_dry_run_prints_request() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "mental-model", "create", "my-bank", "Team", "Who is on the team?", "--id", "team", "--dry-run"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("POST"));
+    assert!(stderr.contains("/v1/default/banks/my-bank/mental-models"));
+    let body: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(body["id"], "team");
+    assert_eq!(body["name"], "Team");
+    assert_eq!(body["source_query"], "Who is on the team?");
+}
+
+#[test]
+fn test_mental_model_create_wait_conflicts_with_dry_run() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "mental-model", "create", "my-bank", "Team", "Who?", "--wait", "--dry-run"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("cannot be used with"));
+}
+
+#[test]
+fn test_update_dry_run_encodes_ids_in_url() {
+    // An id with a slash or space must not change which resource the URL names
+    let output = Command::new("cargo")
+        .args(["run", "--", "mental-model", "update", "my bank", "a/b", "--name", "Renamed", "--dry-run"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("PATCH"));
+    assert!(stderr.contains("/v1/default/banks/my%20bank/mental-models/a%2Fb"));
+    let body: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(body["name"], "Renamed");
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "directive", "update", "my-bank", "d?1", "--content", "Be brief", "--dry-run"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("/v1/default/banks/my-bank/directives/d%3F1"));
+    let body: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout should be JSON");
+    assert_eq!(body["content"], "Be brief");
+    assert!(body.get("name").map_or(true, |name| name.is_null()));
+}
+
+#[test]
+fn test_update_dry_run_requires_a_change() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "directive", "update", "my-bank", "d1", "--dry-run"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("At least one of --name or --content"));
+}
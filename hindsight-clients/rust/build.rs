@@ -72,6 +72,49 @@ fn convert_anyof_to_nullable(value: &mut serde_json::Value) {
     }
 }
 
+/// Add what the client sends but the server's spec doesn't declare, so those
+/// requests still go through the generated operations
+fn extend_spec(spec: &mut serde_json::Value) {
+    // Retries of an identical retain are deduplicated server-side by this key
+    if let Some(parameters) = spec
+        .pointer_mut("/paths/~1v1~1default~1banks~1{bank_id}~1memories/post/parameters")
+        .and_then(|v| v.as_array_mut())
+    {
+        parameters.push(serde_json::json!({
+            "name": "idempotency-key",
+            "in": "header",
+            "required": false,
+            "schema": {"type": "string", "title": "Idempotency Key"}
+        }));
+    }
+
+    // Sampling parameters reflect accepts on servers that support them; the CLI
+    // drops them for servers known not to
+    if let Some(properties) = spec
+        .pointer_mut("/components/schemas/ReflectRequest/properties")
+        .and_then(|v| v.as_object_mut())
+    {
+        properties.insert("temperature".to_string(), serde_json::json!({"type": "number", "format": "double", "title": "Temperature"}));
+        properties.insert("top_p".to_string(), serde_json::json!({"type": "number", "format": "double", "title": "Top P"}));
+        properties.insert("seed".to_string(), serde_json::json!({"type": "integer", "format": "uint64", "title": "Seed"}));
+    }
+
+    // Cached bank list and profile responses are revalidated with their ETag
+    for path in [
+        "/paths/~1v1~1default~1banks/get/parameters",
+        "/paths/~1v1~1default~1banks~1{bank_id}~1profile/get/parameters",
+    ] {
+        if let Some(parameters) = spec.pointer_mut(path).and_then(|v| v.as_array_mut()) {
+            parameters.push(serde_json::json!({
+                "name": "if-none-match",
+                "in": "header",
+                "required": false,
+                "schema": {"type": "string", "title": "If-None-Match"}
+            }));
+        }
+    }
+}
+
 fn main() {
     // Get the OpenAPI spec path from hindsight-docs/static (single source of truth)
     let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
@@ -103,12 +146,17 @@ fn main() {
         }
     }
 
+    extend_spec(&mut spec_json);
+
     // Now parse as OpenAPI struct
     let spec: openapiv3::OpenAPI = serde_json::from_value(spec_json)
         .expect("Failed to parse converted OpenAPI spec");
 
-    // Generate the client
-    let mut generator = progenitor::Generator::default();
+    // Generate the client. Every request passes through `hooks::before_request`
+    // so callers can observe it (e.g. to print an equivalent curl command).
+    let mut settings = progenitor::GenerationSettings::default();
+    settings.with_pre_hook(quote::quote!(crate::hooks::before_request));
+    let mut generator = progenitor::Generator::new(&settings);
 
     // Generate code
     let tokens = generator.generate_tokens(&spec)
@@ -12,7 +12,7 @@
 //!     let client = Client::new("http://localhost:8888");
 //!
 //!     // List memory banks
-//!     let banks = client.list_banks(None).await?;
+//!     let banks = client.list_banks(None, None).await?;
 //!     println!("Found {} banks", banks.into_inner().banks.len());
 //!
 //!     Ok(())
@@ -22,6 +22,32 @@
 // Include the generated client code (which already exports Error and ResponseValue)
 include!(concat!(env!("OUT_DIR"), "/hindsight_client_generated.rs"));
 
+/// Process-wide observer for outgoing requests
+pub mod hooks {
+    use std::sync::OnceLock;
+
+    static OBSERVER: OnceLock<fn(&reqwest::Request)> = OnceLock::new();
+
+    /// Call `observer` with every request just before it is sent. Only the first
+    /// observer registered in a process takes effect.
+    ///
+    /// The request carries its own headers and body; headers configured on the
+    /// underlying `reqwest::Client` (e.g. authorization) are added later and are
+    /// not visible here.
+    pub fn set_request_observer(observer: fn(&reqwest::Request)) -> bool {
+        OBSERVER.set(observer).is_ok()
+    }
+
+    /// Run the registered observer; also for requests built outside the generated client.
+    /// This is the generated client's pre-hook, so it takes the client's inner state
+    /// (`()`, as no `inner_type` is configured) before the request.
+    pub fn before_request(_inner: &(), request: &reqwest::Request) {
+        if let Some(observer) = OBSERVER.get() {
+            observer(request);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,7 +111,7 @@ mod tests {
             document_tags: None,
         };
         let retain_response = client
-            .retain_memories(&bank_id, None, &retain_request)
+            .retain_memories(&bank_id, None, None, &retain_request)
             .await
             .expect("Failed to retain memories");
         assert!(retain_response.into_inner().success);